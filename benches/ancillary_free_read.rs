@@ -0,0 +1,43 @@
+//! Compares a plain `read` from a `UdStream` with `read_ancillary` given an empty ancillary buffer, which goes through
+//! `recvmsg`. Run with `cargo bench --bench ancillary_free_read`.
+
+#[cfg(unix)]
+mod unix {
+    use criterion::Criterion;
+    use interprocess::os::unix::udsocket::{cmsg::CmsgMutBuf, ReadAncillary, UdStream};
+    use std::{
+        hint::black_box,
+        io::{self, Read, Write},
+    };
+
+    const MSG: [u8; 64] = [0x55; 64];
+
+    /// Each iteration writes a small message and reads it back, so that reads never block. The write is the same for
+    /// both contenders, which leaves the read as the only difference.
+    fn bench_read(c: &mut Criterion, name: &str, mut read: impl FnMut(&UdStream, &mut [u8]) -> io::Result<usize>) {
+        let (sender, receiver) = UdStream::pair().expect("socketpair creation failed");
+        let mut buf = [0; MSG.len()];
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                (&sender).write_all(&MSG).expect("socket send failed");
+                black_box(read(&receiver, black_box(&mut buf)).expect("socket receive failed"))
+            })
+        });
+    }
+
+    pub fn ancillary_free_read(c: &mut Criterion) {
+        bench_read(c, "write + read", |conn, buf| (&*conn).read(buf));
+        bench_read(c, "write + read_ancillary (no cmsgs)", |conn, buf| {
+            let mut abuf = CmsgMutBuf::new(&mut []);
+            (&*conn).read_ancillary(buf, &mut abuf).map(|r| r.main)
+        });
+    }
+}
+
+#[cfg(unix)]
+criterion::criterion_group!(benches, unix::ancillary_free_read);
+#[cfg(unix)]
+criterion::criterion_main!(benches);
+
+#[cfg(not(unix))]
+fn main() {}
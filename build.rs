@@ -36,6 +36,8 @@ macro_rules! uds_cdefine {
 /// - `uds_sun_len` on platforms that have the stupid as fuck `sun_len` field (to correct max length calculation)
 /// - `uds_sock_cloexec` on platforms with SOCK_CLOEXEC
 /// - `uds_sock_nonblock` on platforms with SOCK_NONBLOCK
+/// - `uds_msg_cmsg_cloexec` on platforms with MSG_CMSG_CLOEXEC
+/// - `uds_accept4` on platforms with accept4
 /// - Credential ancillary message structure flavor:
 ///     - `uds_ucred` from Linux
 ///     - `uds_cmsgcred` from FreeBSD
@@ -65,11 +67,17 @@ fn collect_uds_features(target: &TargetTriplet) {
         mut sockcred,
         mut sockcred2,
         mut xucred,
+        mut unpcbid,
+        mut sockpeercred,
         mut sock_cloexec,
-        mut sock_nonblock] = [false; 8];
+        mut sock_nonblock,
+        mut msg_cmsg_cloexec,
+        mut accept4] = [false; 12];
     if target.os_any(&["linux", "android", "fuchsia", "redox"]) {
         // "Linux-like" in libc terminology, plus Fuchsia and Redox
         [ucred, sock_cloexec, sock_nonblock] = [true; 3];
+        msg_cmsg_cloexec = target.os_any(&["linux", "android", "fuchsia"]);
+        accept4 = msg_cmsg_cloexec;
         if (target.os("linux") && target.env("gnu"))
         || (target.os("linux") && target.env("uclibc") && target.arch_any(&["x86_64", "mips64"]))
         || target.os("android") {
@@ -85,6 +93,8 @@ fn collect_uds_features(target: &TargetTriplet) {
             "uds_peereid",
             "uds_sun_len",
         ]);
+        msg_cmsg_cloexec = target.os_any(&["freebsd", "openbsd", "netbsd", "dragonfly"]);
+        accept4 = msg_cmsg_cloexec;
 
         if target.os_any(&["freebsd", "dragonfly"]) {
             cmsgcred = true;
@@ -96,11 +106,9 @@ fn collect_uds_features(target: &TargetTriplet) {
         if target.os("netbsd") {
             // TODO does it have sock_cloexec and sock_nonblock?
             sockcred = true;
-            // TODO
-            define("uds_unpcbid");
+            unpcbid = true;
         } else if target.os("openbsd") {
-            // TODO
-            define("uds_sockpeercred");
+            sockpeercred = true;
         } else {
             // TODO
             xucred = true;
@@ -119,16 +127,16 @@ fn collect_uds_features(target: &TargetTriplet) {
             "uds_msghdr_iovlen_c_int", "uds_msghdr_controllen_socklen_t", "uds_cmsghdr_len_socklen_t"
         ])
     }
-    if ucred || cmsgcred || sockcred || sockcred2 || xucred {
+    if ucred || cmsgcred || sockcred || sockcred2 || xucred || unpcbid || sockpeercred {
         define("uds_credentials");
-        let mut contcred = false; // TODO is NetBSD sockcred a contcred?
-        contcred |= ucred | sockcred2;
+        let mut cont_credentials = false; // TODO is NetBSD sockcred a contcred?
+        cont_credentials |= ucred | sockcred2;
         if ucred || cmsgcred || sockcred || sockcred2 {
             define("uds_ancillary_credentials");
         }
-        uds_cdefine!(ucred, cmsgcred, sockcred, sockcred2, contcred, xucred);
+        uds_cdefine!(ucred, cmsgcred, sockcred, sockcred2, cont_credentials, xucred, unpcbid, sockpeercred);
     }
-    uds_cdefine!(sock_cloexec, sock_nonblock);
+    uds_cdefine!(sock_cloexec, sock_nonblock, msg_cmsg_cloexec, accept4);
 }
 
 struct TargetTriplet {
@@ -61,6 +61,18 @@ impl<S, E: Default> ConversionError<S, E> {
     }
 }
 impl<S, E> ConversionError<S, E> {
+    /// Constructs an error value from all of its parts, returning ownership of the input of the conversion.
+    ///
+    /// Unlike the other constructors, this doesn't require the details type to implement `Default`. Use
+    /// [`from_cause_and_details()`](Self::from_cause_and_details) if the input has been consumed.
+    #[inline]
+    pub fn new(source: S, cause: Option<io::Error>, details: E) -> Self {
+        Self {
+            details,
+            cause,
+            source: Some(source),
+        }
+    }
     /// Constructs an error value without an OS cause.
     pub fn from_source_and_details(source: S, details: E) -> Self {
         Self {
@@ -98,12 +110,70 @@ impl<S, E> ConversionError<S, E> {
             source: self.source.and_then(f),
         }
     }
+    /// Maps the type of the "details" field using the given closure, leaving the cause and the source untouched.
+    ///
+    /// Useful for widening the details of a lower-level conversion into those of a higher-level one.
+    pub fn map_details<Eb>(self, f: impl FnOnce(E) -> Eb) -> ConversionError<S, Eb> {
+        ConversionError {
+            details: f(self.details),
+            cause: self.cause,
+            source: self.source,
+        }
+    }
+    /// Returns ownership of the input of the conversion, if it was retained, discarding the rest of the error.
+    #[inline]
+    pub fn into_source(self) -> Option<S> {
+        self.source
+    }
+    /// Returns the underlying OS error, if any, discarding the rest of the error.
+    #[inline]
+    pub fn into_cause(self) -> Option<io::Error> {
+        self.cause
+    }
+    /// Splits the error into its source, cause and details, in that order.
+    #[inline]
+    pub fn into_parts(self) -> (Option<S>, Option<io::Error>, E) {
+        (self.source, self.cause, self.details)
+    }
 }
 impl<S, E: Display> ConversionError<S, E> {
     /// Boxes the error into an `io::Error`.
+    ///
+    /// The [`ErrorKind`](io::ErrorKind) is taken from the cause if there is one, and is
+    /// [`Other`](io::ErrorKind::Other) otherwise. Only the message is kept – use
+    /// [`.into_io_error()`](Self::into_io_error) to keep the error itself.
     pub fn to_io_error(&self) -> io::Error {
+        let kind = self.cause.as_ref().map_or(io::ErrorKind::Other, io::Error::kind);
         let msg = self.to_string();
-        io::Error::new(io::ErrorKind::Other, msg)
+        io::Error::new(kind, msg)
+    }
+}
+impl<S: Debug + Send + Sync + 'static, E: Error + Send + Sync + 'static> ConversionError<S, E> {
+    /// Boxes the error into an `io::Error`, keeping the whole `ConversionError` as its inner error, including the
+    /// retained input of the conversion.
+    ///
+    /// This is meant for passing the error through interfaces which can only return `io::Error`: unlike
+    /// [`.to_io_error()`](Self::to_io_error), which only preserves the message, the original error can be recovered
+    /// by downcasting. The [`ErrorKind`](io::ErrorKind) is chosen the same way.
+    ///
+    /// # Example
+    /// ```
+    /// use interprocess::error::{ConversionError, NoDetails};
+    /// use std::io;
+    ///
+    /// let cause = io::Error::from(io::ErrorKind::PermissionDenied);
+    /// let ioerr = ConversionError::<u32>::from_source_and_cause(42, cause).into_io_error();
+    /// assert_eq!(ioerr.kind(), io::ErrorKind::PermissionDenied);
+    ///
+    /// let original = ioerr
+    ///     .into_inner()
+    ///     .and_then(|e| e.downcast::<ConversionError<u32, NoDetails>>().ok())
+    ///     .unwrap();
+    /// assert_eq!(original.source, Some(42));
+    /// ```
+    pub fn into_io_error(self) -> io::Error {
+        let kind = self.cause.as_ref().map_or(io::ErrorKind::Other, io::Error::kind);
+        io::Error::new(kind, self)
     }
 }
 /// Boxes the error into an `io::Error`, dropping the retained file descriptor in the process.
@@ -177,6 +247,47 @@ impl Display for NoDetails {
         Ok(()) //
     }
 }
+/// Allows `ConversionError`s without details to be used as errors in their own right, most notably with `?` in
+/// functions returning boxed errors.
+impl Error for NoDetails {}
+
+/// Details of the error returned by [`TryClone::try_clone_with_details()`](crate::TryClone::try_clone_with_details),
+/// telling running out of handles or file descriptors apart from other reasons for the duplication to fail.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CloneError {
+    /// The process or the whole system has hit its limit on the number of open file descriptors (`EMFILE` or `ENFILE`)
+    /// or handles (`ERROR_TOO_MANY_OPEN_FILES` or `ERROR_NO_SYSTEM_RESOURCES`). Closing some of them or raising the
+    /// limit might make the operation succeed.
+    HandleLimit,
+    /// Any other failure, as described by the OS error.
+    #[default]
+    Other,
+}
+impl CloneError {
+    /// Classifies the given OS error.
+    pub fn from_cause(cause: &io::Error) -> Self {
+        #[cfg(unix)]
+        let limit_errors = [libc::EMFILE, libc::ENFILE];
+        #[cfg(windows)]
+        let limit_errors = [
+            winapi::shared::winerror::ERROR_TOO_MANY_OPEN_FILES as i32,
+            winapi::shared::winerror::ERROR_NO_SYSTEM_RESOURCES as i32,
+        ];
+        match cause.raw_os_error() {
+            Some(code) if limit_errors.contains(&code) => Self::HandleLimit,
+            _ => Self::Other,
+        }
+    }
+}
+impl Display for CloneError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::HandleLimit => "limit on open handles or file descriptors reached",
+            Self::Other => "duplication failed",
+        })
+    }
+}
+impl Error for CloneError {}
 
 /// Error type of `TryFrom<OwnedHandle>` conversions.
 #[cfg(windows)]
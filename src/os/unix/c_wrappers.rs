@@ -10,21 +10,36 @@ pub(super) unsafe fn fcntl_noarg(fd: BorrowedFd<'_>, cmd: c_int) -> io::Result<c
     ok_or_ret_errno!(val != -1 => val)
 }
 
+/// Duplicates the file descriptor with the close-on-exec flag set atomically, so that a concurrent `fork` and `exec`
+/// can't leak the duplicate into the child. `F_DUPFD_CLOEXEC` is part of POSIX.1-2008.
 pub(super) fn duplicate_fd(fd: BorrowedFd<'_>) -> io::Result<OwnedFd> {
-    #[cfg(target_os = "linux")]
+    let new_fd = unsafe { fcntl_int(fd, libc::F_DUPFD_CLOEXEC, 0)? };
+    Ok(unsafe { OwnedFd::from_raw_fd(new_fd) })
+}
+
+/// Makes `target` refer to the same file description as `fd`, closing whatever `target` referred to before. Unlike
+/// with a new duplicate, the close-on-exec flag of `target` is set to `cloexec` rather than always being set. Does
+/// nothing at all if `target` is `fd` itself.
+pub(super) fn dup_to(fd: BorrowedFd<'_>, target: RawFd, cloexec: bool) -> io::Result<()> {
+    if fd.as_raw_fd() == target {
+        // Same as what `dup2` does, whereas `dup3` would fail with EINVAL
+        return Ok(());
+    }
+    #[cfg(any(target_os = "linux", target_os = "android"))]
     {
-        let new_fd = unsafe { fcntl_int(fd, libc::F_DUPFD_CLOEXEC, 0)? };
-        Ok(unsafe { OwnedFd::from_raw_fd(new_fd) })
+        let flags = if cloexec { libc::O_CLOEXEC } else { 0 };
+        let success = unsafe { libc::dup3(fd.as_raw_fd(), target, flags) != -1 };
+        ok_or_ret_errno!(success => ())
     }
-    #[cfg(not(target_os = "linux"))]
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
     {
-        let (val, success) = unsafe {
-            let ret = libc::dup(fd.as_raw_fd());
-            (ret, ret != -1)
-        };
-        let new_fd = ok_or_ret_errno!(success => unsafe { OwnedFd::from_raw_fd(val) })?;
-        set_cloexec(new_fd.as_fd())?;
-        Ok(new_fd)
+        // The duplicate starts out without the flag
+        let success = unsafe { libc::dup2(fd.as_raw_fd(), target) != -1 };
+        ok_or_ret_errno!(success => ())?;
+        if cloexec {
+            set_cloexec(unsafe { BorrowedFd::borrow_raw(target) }, true)?;
+        }
+        Ok(())
     }
 }
 
@@ -39,10 +54,46 @@ pub(super) fn set_fdflags(fd: BorrowedFd<'_>, flags: i32) -> io::Result<()> {
     let success = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, flags) != -1 };
     ok_or_ret_errno!(success => ())
 }
-pub(super) fn set_cloexec(fd: BorrowedFd<'_>) -> io::Result<()> {
-    set_fdflags(fd, get_fdflags(fd)? | libc::FD_CLOEXEC)?;
+pub(super) fn set_cloexec(fd: BorrowedFd<'_>, cloexec: bool) -> io::Result<()> {
+    let flags = get_fdflags(fd)?;
+    let new_flags = if cloexec {
+        flags | libc::FD_CLOEXEC
+    } else {
+        flags & !libc::FD_CLOEXEC
+    };
+    if new_flags != flags {
+        set_fdflags(fd, new_flags)?;
+    }
+    Ok(())
+}
+pub(super) fn get_cloexec(fd: BorrowedFd<'_>) -> io::Result<bool> {
+    Ok(get_fdflags(fd)? & libc::FD_CLOEXEC != 0)
+}
+
+fn get_status_flags(fd: BorrowedFd<'_>) -> io::Result<c_int> {
+    unsafe { fcntl_noarg(fd, libc::F_GETFL) }
+}
+fn set_status_flags(fd: BorrowedFd<'_>, new_flags: c_int) -> io::Result<()> {
+    unsafe {
+        fcntl_int(fd, libc::F_SETFL, new_flags)?;
+    }
     Ok(())
 }
+pub(super) fn set_nonblocking(fd: BorrowedFd<'_>, nonblocking: bool) -> io::Result<()> {
+    let old_flags = get_status_flags(fd)?;
+    let new_flags = if nonblocking {
+        old_flags | libc::O_NONBLOCK
+    } else {
+        // Inverting the O_NONBLOCK value sets all the bits in the flag set to 1 except for the
+        // nonblocking flag, which clears the flag when ANDed.
+        old_flags & !libc::O_NONBLOCK
+    };
+    set_status_flags(fd, new_flags)
+}
+pub(super) fn get_nonblocking(fd: BorrowedFd<'_>) -> io::Result<bool> {
+    let flags = get_status_flags(fd)?;
+    Ok(flags & libc::O_NONBLOCK != 0)
+}
 
 #[cfg(uds_ucred)]
 pub(super) fn get_uid(ruid: bool) -> uid_t {
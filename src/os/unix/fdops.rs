@@ -2,13 +2,164 @@ use super::{c_wrappers, unixprelude::*};
 use crate::TryClone;
 use std::{
     io::{self, prelude::*, IoSlice, IoSliceMut},
+    mem,
     os::fd::OwnedFd,
 };
 use to_method::To;
 
+/// An owned file descriptor with `read`/`write`-based I/O, the building block of every file descriptor-backed type in
+/// this crate.
+///
+/// Can be used to implement custom IPC primitives on top of file descriptors which don't need anything more than the
+/// generic system calls, such as pipes obtained from elsewhere or character devices. Reading and writing is performed
+/// through shared references, same as with [`UdStream`](super::udsocket::UdStream), and the file descriptor is closed
+/// when the value is dropped.
+///
+/// Conversions from and into [`OwnedFd`] and raw file descriptors are available, as well as [`AsFd`] and
+/// [`AsRawFd`].
+///
+/// # Example
+/// ```
+/// # #[cfg(not(target_os = "linux"))] fn main() {}
+/// # #[cfg(target_os = "linux")] fn main() -> std::io::Result<()> {
+/// use interprocess::{os::unix::{FdOps, udsocket::UdStream}, TryClone};
+/// use std::{io::{prelude::*, IoSlice, IoSliceMut}, os::fd::OwnedFd};
+///
+/// let (a, b) = UdStream::pair()?;
+/// let (a, b) = (FdOps::new(OwnedFd::from(a)), FdOps::new(OwnedFd::from(b)));
+///
+/// a.write_vectored_all(&mut [IoSlice::new(b"Hello "), IoSlice::new(b"world")])?;
+/// let (mut hello, mut world) = ([0; 6], [0; 5]);
+/// b.try_clone()?.read_vectored_exact(&mut [IoSliceMut::new(&mut hello), IoSliceMut::new(&mut world)])?;
+/// assert_eq!((&hello, &world), (b"Hello ", b"world"));
+/// # Ok(()) }
+/// ```
 #[repr(transparent)]
-pub(super) struct FdOps(pub(super) OwnedFd);
+pub struct FdOps(pub(super) OwnedFd);
+impl FdOps {
+    /// Wraps the given file descriptor.
+    ///
+    /// The file descriptor is used as-is: its close-on-exec and nonblocking flags are not changed.
+    #[inline]
+    pub fn new(fd: OwnedFd) -> Self {
+        Self(fd)
+    }
+    /// Sets or clears the close-on-exec flag of the file descriptor.
+    ///
+    /// # System calls
+    /// - `fcntl`
+    pub fn set_cloexec(&self, cloexec: bool) -> io::Result<()> {
+        c_wrappers::set_cloexec(self.0.as_fd(), cloexec)
+    }
+    /// Checks whether the close-on-exec flag of the file descriptor is set.
+    ///
+    /// # System calls
+    /// - `fcntl`
+    pub fn get_cloexec(&self) -> io::Result<bool> {
+        c_wrappers::get_cloexec(self.0.as_fd())
+    }
+    /// Waits until all data written to the file (as well as its metadata) reaches the storage device, the same as
+    /// [`File::sync_all()`](std::fs::File::sync_all).
+    ///
+    /// Only meaningful for regular files and block devices – on pipes, sockets and most character devices, an
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) error is returned, since there is no storage device to speak of.
+    ///
+    /// # System calls
+    /// - `fsync`
+    pub fn sync_all(&self) -> io::Result<()> {
+        let success = unsafe { libc::fsync(self.0.as_raw_fd()) >= 0 };
+        ok_or_ret_errno!(success => ())
+    }
+    /// Reads until every one of `bufs` is filled, returning an `UnexpectedEof` error if end of file is reached first.
+    /// Interrupted reads are retried.
+    ///
+    /// Uses `readv` for as long as buffers are filled whole, falling back to `read` to fill the rest of a buffer that
+    /// a `readv` call stopped in the middle of.
+    ///
+    /// # System calls
+    /// - `readv`
+    /// - `read`
+    pub fn read_vectored_exact(&self, mut bufs: &mut [IoSliceMut<'_>]) -> io::Result<()> {
+        fn skip<'a, 'b>(bufs: &mut &'a mut [IoSliceMut<'b>]) {
+            *bufs = &mut mem::take(bufs)[1..];
+        }
+        loop {
+            while bufs.first().is_some_and(|b| b.is_empty()) {
+                skip(&mut bufs);
+            }
+            if bufs.is_empty() {
+                return Ok(());
+            }
+            let mut read = match (&*self).read_vectored(bufs) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "failed to fill whole buffer",
+                    ))
+                }
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            while let Some(first) = bufs.first() {
+                if read < first.len() {
+                    break;
+                }
+                read -= first.len();
+                skip(&mut bufs);
+            }
+            if read > 0 {
+                // Stopped in the middle of a buffer. `read_exact()` handles interruptions and end of file the same way.
+                (&*self).read_exact(&mut bufs[0][read..])?;
+                skip(&mut bufs);
+            }
+        }
+    }
+    /// Writes every one of `bufs`, returning a `WriteZero` error if the file stops accepting data. Interrupted writes
+    /// are retried.
+    ///
+    /// The counterpart of `read_vectored_exact()`: `IoSlice` cannot be advanced in place on the MSRV, so the rest of a
+    /// buffer that a `writev` call stopped in the middle of is written with `write`.
+    ///
+    /// # System calls
+    /// - `writev`
+    /// - `write`
+    pub fn write_vectored_all(&self, mut bufs: &mut [IoSlice<'_>]) -> io::Result<()> {
+        fn skip<'a, 'b>(bufs: &mut &'a mut [IoSlice<'b>]) {
+            *bufs = &mut mem::take(bufs)[1..];
+        }
+        loop {
+            while bufs.first().is_some_and(|b| b.is_empty()) {
+                skip(&mut bufs);
+            }
+            if bufs.is_empty() {
+                return Ok(());
+            }
+            let mut written = match (&*self).write_vectored(bufs) {
+                Ok(0) => return Err(io::Error::new(io::ErrorKind::WriteZero, "failed to write whole buffer")),
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            while let Some(first) = bufs.first() {
+                if written < first.len() {
+                    break;
+                }
+                written -= first.len();
+                skip(&mut bufs);
+            }
+            if written > 0 {
+                // Same as above, `write_all()` handles interruptions and zero-length writes the same way.
+                (&*self).write_all(&bufs[0][written..])?;
+                skip(&mut bufs);
+            }
+        }
+    }
+}
+/// A list of used system calls is available.
 impl Read for &FdOps {
+    /// # System calls
+    /// - `read`
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
         let length_to_read = buf.len();
 
@@ -18,6 +169,8 @@ impl Read for &FdOps {
         };
         ok_or_ret_errno!(success => bytes_read)
     }
+    /// # System calls
+    /// - `readv`
     fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
         let num_bufs = bufs.len().try_to::<c_int>().unwrap_or(c_int::MAX);
 
@@ -29,7 +182,10 @@ impl Read for &FdOps {
     }
     // FUTURE can_vector
 }
+/// A list of used system calls is available.
 impl Write for &FdOps {
+    /// # System calls
+    /// - `write`
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         let length_to_write = buf.len();
 
@@ -39,6 +195,8 @@ impl Write for &FdOps {
         };
         ok_or_ret_errno!(success => bytes_written)
     }
+    /// # System calls
+    /// - `writev`
     fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
         let num_bufs = bufs.len().try_to::<c_int>().unwrap_or(c_int::MAX);
 
@@ -49,13 +207,26 @@ impl Write for &FdOps {
         ok_or_ret_errno!(success => bytes_written)
     }
     // FUTURE can_vector
+    /// Does nothing, since data is handed to the kernel as soon as it's written, and thus there is nothing to flush.
+    ///
+    /// Notably, this doesn't wait for the data to reach the storage device, which is what
+    /// [`sync_all()`](FdOps::sync_all) is for. This matches [`File`](std::fs::File), and allows wrappers such as
+    /// [`BufWriter`](std::io::BufWriter) to be used with pipes and sockets, on which `fsync` fails.
+    #[inline(always)]
     fn flush(&mut self) -> io::Result<()> {
-        let success = unsafe { libc::fsync(self.0.as_raw_fd()) >= 0 };
-        ok_or_ret_errno!(success => ())
+        Ok(())
     }
 }
 
 impl TryClone for FdOps {
+    /// Duplicates the file descriptor. The new one has the close-on-exec flag set regardless of whether the original
+    /// does, since that flag belongs to the file descriptor rather than to what it refers to.
+    ///
+    /// The status flags, on the other hand, belong to the open file description, which the duplicate shares with the
+    /// original. **Enabling or disabling nonblocking mode on one of them thus affects the other as well.**
+    ///
+    /// # System calls
+    /// - `fcntl` (`F_DUPFD_CLOEXEC`)
     fn try_clone(&self) -> std::io::Result<Self> {
         let fd = c_wrappers::duplicate_fd(self.0.as_fd())?;
         Ok(Self(fd))
@@ -17,8 +17,7 @@
 pub(crate) mod imports;
 
 mod fdops;
-// Exported into child modules specifically, not this file.
-use fdops::*;
+pub use fdops::*;
 
 pub mod fifo_file;
 
@@ -0,0 +1,189 @@
+use super::{ReadAncillary, ReadAncillarySuccess};
+use crate::os::unix::{
+    udsocket::{
+        cmsg::{CmsgMut, CmsgMutExt, CmsgRef},
+        UdSocket,
+    },
+    unixprelude::*,
+};
+use std::{
+    fmt::{self, Debug, Formatter},
+    io::{self, prelude::*},
+    mem,
+    ops::Range,
+};
+
+const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+
+/// A buffering wrapper around a [`ReadAncillary`] implementor, analogous to [`BufReader`](io::BufReader), which
+/// collects the control messages received along with the buffered data instead of discarding them.
+///
+/// Since ancillary data and main-band data are received by the same system call, the control messages end up in the
+/// ancillary buffer as soon as the data they arrived with is buffered, which can happen well before that data is
+/// actually read out of the wrapper. To allow associating the two, the wrapper keeps track of its
+/// [position](Self::position) in the stream – the amount of bytes handed out to the user so far – and the
+/// [range](Self::ancillary_range) of positions covered by the receive operations which delivered the collected
+/// control messages. The buffer is only ever refilled once it's empty, so every refill corresponds to exactly one
+/// receive operation.
+///
+/// Control messages accumulate in the ancillary buffer until they are removed with
+/// [`.take_ancillary()`](Self::take_ancillary) or [`.clear_ancillary()`](Self::clear_ancillary). If the ancillary
+/// buffer fills up in the meantime, further control messages are truncated by the kernel.
+pub struct AncillaryBufReader<RA, AB> {
+    reader: RA,
+    buf: Box<[u8]>,
+    pos: usize,
+    filled: usize,
+    abuf: AB,
+    position: u64,
+    anc_range: Option<Range<u64>>,
+}
+impl<RA, AB> AncillaryBufReader<RA, AB> {
+    /// Creates a wrapper with a main-band buffer of the default capacity, currently 8 KiB.
+    #[inline]
+    pub fn new(reader: RA, abuf: AB) -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE, reader, abuf)
+    }
+    /// Creates a wrapper with a main-band buffer of the given capacity. The capacity of the ancillary buffer is
+    /// whatever `abuf` comes with.
+    pub fn with_capacity(capacity: usize, reader: RA, abuf: AB) -> Self {
+        Self {
+            reader,
+            buf: vec![0; capacity].into_boxed_slice(),
+            pos: 0,
+            filled: 0,
+            abuf,
+            position: 0,
+            anc_range: None,
+        }
+    }
+
+    /// Borrows the underlying reader.
+    #[inline(always)]
+    pub fn get_ref(&self) -> &RA {
+        &self.reader
+    }
+    /// Mutably borrows the underlying reader. Reading from it directly will cause data and control messages to be
+    /// missed by the wrapper.
+    #[inline(always)]
+    pub fn get_mut(&mut self) -> &mut RA {
+        &mut self.reader
+    }
+    /// Returns the main-band data which has been buffered but not yet read.
+    #[inline]
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[self.pos..self.filled]
+    }
+    /// Returns the capacity of the main-band buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+    /// Returns the amount of main-band data read out of the wrapper since its creation, in bytes.
+    #[inline(always)]
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+    /// Returns the range of [positions](Self::position) spanned by the data received along with the control messages
+    /// currently in the ancillary buffer, or `None` if no control messages have been collected since the last time
+    /// the ancillary buffer was emptied.
+    ///
+    /// Note that the kernel is free to deliver data sent before a control message in the same receive operation as the
+    /// message itself – Linux, for instance, ends a receive operation right after the data that a message was sent
+    /// with, but doesn't start a new one before it. The range is thus only guaranteed to contain the data that the
+    /// message was sent with, and can start earlier.
+    #[inline]
+    pub fn ancillary_range(&self) -> Option<Range<u64>> {
+        self.anc_range.clone()
+    }
+    /// Replaces the ancillary buffer with an empty one, returning the old one together with all control messages
+    /// collected so far.
+    #[inline]
+    pub fn take_ancillary(&mut self) -> AB
+    where
+        AB: Default,
+    {
+        self.anc_range = None;
+        mem::take(&mut self.abuf)
+    }
+    /// Unwraps the adapter, returning the reader and the ancillary buffer. **Data which has been buffered but not yet
+    /// read is lost.**
+    #[inline]
+    pub fn into_inner(self) -> (RA, AB) {
+        (self.reader, self.abuf)
+    }
+
+    fn note_receive(&mut self, rslt: ReadAncillarySuccess) {
+        if rslt.ancillary == 0 {
+            return;
+        }
+        // The buffer is always drained before a receive operation, so the position is that of the new chunk.
+        let end = self.position + rslt.main as u64;
+        let start = self.anc_range.as_ref().map_or(self.position, |r| r.start);
+        self.anc_range = Some(start..end);
+    }
+}
+impl<RA, AB: CmsgMut> AncillaryBufReader<RA, AB> {
+    /// Borrows the control messages collected so far.
+    #[inline]
+    pub fn ancillary(&self) -> CmsgRef<'_> {
+        self.abuf.as_ref()
+    }
+    /// Discards the control messages collected so far, keeping the ancillary buffer itself for reuse.
+    ///
+    /// File descriptors among the discarded messages are leaked unless they have been decoded beforehand.
+    #[inline]
+    pub fn clear_ancillary(&mut self) {
+        self.anc_range = None;
+        self.abuf.clear();
+    }
+}
+impl<RA: ReadAncillary<AB>, AB: CmsgMut> Read for AncillaryBufReader<RA, AB> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        // Just like with `BufReader`, large reads bypass the buffer if it's empty.
+        if self.pos == self.filled && out.len() >= self.buf.len() {
+            let rslt = self.reader.read_ancillary(out, &mut self.abuf)?;
+            self.note_receive(rslt);
+            self.position += rslt.main as u64;
+            return Ok(rslt.main);
+        }
+        let n = self.fill_buf()?.read(out)?;
+        self.consume(n);
+        Ok(n)
+    }
+}
+impl<RA: ReadAncillary<AB>, AB: CmsgMut> BufRead for AncillaryBufReader<RA, AB> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos >= self.filled {
+            let rslt = self.reader.read_ancillary(&mut self.buf, &mut self.abuf)?;
+            self.note_receive(rslt);
+            self.filled = rslt.main;
+            self.pos = 0;
+        }
+        Ok(self.buffer())
+    }
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        let amt = amt.min(self.filled - self.pos);
+        self.pos += amt;
+        self.position += amt as u64;
+    }
+}
+impl<RA: Debug, AB: Debug> Debug for AncillaryBufReader<RA, AB> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AncillaryBufReader")
+            .field("reader", &self.reader)
+            .field("buffer", &format_args!("{}/{}", self.filled - self.pos, self.buf.len()))
+            .field("abuf", &self.abuf)
+            .field("position", &self.position)
+            .field("ancillary_range", &self.anc_range)
+            .finish()
+    }
+}
+impl<RA: AsFd, AB> AsFd for AncillaryBufReader<RA, AB> {
+    #[inline(always)]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.reader.as_fd()
+    }
+}
+impl<RA: UdSocket, AB> UdSocket for AncillaryBufReader<RA, AB> {}
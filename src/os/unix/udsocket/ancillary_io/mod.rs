@@ -16,12 +16,14 @@ pub(super) mod sync {
     mod write;
     pub use {read::*, write::*};
 }
+mod bufreader;
 mod partappl;
 
 #[cfg(feature = "async")]
 pub use poll::*;
-pub use {partappl::*, sync::*};
+pub use {bufreader::*, partappl::*, sync::*};
 
+use libc::c_int;
 use std::ops::{Add, AddAssign};
 
 /// The successful result of an ancillary-enabled read.
@@ -49,6 +51,29 @@ impl AddAssign for ReadAncillarySuccess {
     }
 }
 
+/// The successful result of an ancillary-enabled receive operation, along with the flags reported by the kernel.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ReadAncillaryWithFlags {
+    /// How many bytes were read to the main and ancillary buffers.
+    pub success: ReadAncillarySuccess,
+    /// The `msg_flags` field of the `msghdr` after the receive operation.
+    pub msg_flags: c_int,
+}
+impl ReadAncillaryWithFlags {
+    /// Returns `true` if some control messages were discarded because the ancillary buffer was too small to fit them
+    /// (`MSG_CTRUNC`). If those contained file descriptors, the ones which didn't fit were closed by the kernel.
+    #[inline]
+    pub const fn ancillary_truncated(&self) -> bool {
+        self.msg_flags & libc::MSG_CTRUNC != 0
+    }
+    /// Returns `true` if the datagram was larger than the provided buffers and the rest of it was discarded
+    /// (`MSG_TRUNC`). This never happens with byte streams.
+    #[inline]
+    pub const fn main_truncated(&self) -> bool {
+        self.msg_flags & libc::MSG_TRUNC != 0
+    }
+}
+
 fn devector<'a>(bufs: &'a [std::io::IoSlice<'_>]) -> &'a [u8] {
     bufs.iter().find(|b| !b.is_empty()).map_or(&[][..], |b| &**b)
 }
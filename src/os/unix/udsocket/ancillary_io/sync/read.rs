@@ -6,6 +6,10 @@ use std::io::{self, prelude::*, IoSliceMut};
 ///
 /// The generic parameter on the trait allows for trait objects to be constructed. Simply substitute [`DynCmsgMut`] or
 /// [`DynCmsgMutStatic`] for `AB` to obtain an object-safe `ReadAncillary`.
+///
+/// Receiving ancillary data requires the more involved `recvmsg` system call, which has more overhead than the plain
+/// `read` behind the [`Read`] implementations of the socket types of this crate. An empty ancillary buffer doesn't
+/// avoid that overhead, so the methods of this trait should only be used where control messages are actually expected.
 pub trait ReadAncillary<AB: CmsgMut + ?Sized>: Read {
     /// Analogous to [`Read::read()`], but also reads control messages into the given ancillary buffer.
     ///
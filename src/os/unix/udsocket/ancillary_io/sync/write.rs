@@ -9,8 +9,15 @@ use std::{
 pub trait WriteAncillary: Write {
     /// Analogous to [`Write::write()`], but also sends control messages from the given ancillary buffer.
     ///
-    /// The return value only the amount of main-band data sent from the given regular buffer – the entirety of the
-    /// given `abuf` is always sent in full.
+    /// The return value is only the amount of main-band data sent from the given regular buffer – the entirety of the
+    /// given `abuf` is always sent in full. There is thus no such thing as a partial send of control messages: the
+    /// kernel attaches all of them to the first byte of a successful write, and fails the whole operation if it can't.
+    /// This also means that `msg_flags` carries no information after sending, which is why it isn't reported.
+    ///
+    /// On byte streams, control messages need at least one byte of data to be attached to. Implementations for
+    /// streams return an [`InvalidInput`](io::ErrorKind::InvalidInput) error when asked to send a non-empty `abuf`
+    /// along with no data, since the kernel would discard the messages without reporting it. Datagram sockets don't
+    /// have this restriction, as an empty datagram is still a datagram.
     fn write_ancillary(&mut self, buf: &[u8], abuf: CmsgRef<'_>) -> io::Result<usize>;
 
     /// Same as [`.write_ancillary()`](WriteAncillary::write_ancillary), but performs a
@@ -1,9 +1,13 @@
 use super::{
     super::unixprelude::*,
     c_wrappers,
-    cmsg::{read::buf_to_msghdr, CmsgMut, CmsgMutExt, CmsgRef},
+    cmsg::{
+        context::{Collector, DummyCollector},
+        read::buf_to_msghdr,
+        CmsgMut, CmsgMutExt, CmsgRef,
+    },
     util::{make_msghdr, to_msghdr_iovlen},
-    ReadAncillarySuccess, UdSocketPath,
+    ReadAncillarySuccess, ReadAncillaryWithFlags, UdSocketPath,
 };
 use libc::{c_void, iovec, sockaddr_un};
 use std::{
@@ -11,12 +15,43 @@ use std::{
     mem::{size_of_val, zeroed},
 };
 
+/// Receives with `MSG_CMSG_CLOEXEC` added to `flags` where it's supported, so that received file descriptors don't
+/// leak into child processes.
+#[inline]
 pub(super) fn recvmsg<AB: CmsgMut + ?Sized>(
     fd: BorrowedFd<'_>,
     bufs: &mut [IoSliceMut<'_>],
     ancbuf: &mut AB,
-    addrbuf: Option<&mut UdSocketPath<'_>>,
-) -> io::Result<ReadAncillarySuccess> {
+    addrbuf: Option<&mut UdSocketPath<'static>>,
+    flags: c_int,
+) -> io::Result<ReadAncillaryWithFlags> {
+    recvmsg_with_collector(fd, bufs, ancbuf, addrbuf, flags, DummyCollector)
+}
+/// Same as `recvmsg()`, but calls the hooks of `collector` around the system call.
+#[allow(unused_mut)]
+pub(super) fn recvmsg_with_collector<AB: CmsgMut + ?Sized>(
+    fd: BorrowedFd<'_>,
+    bufs: &mut [IoSliceMut<'_>],
+    ancbuf: &mut AB,
+    addrbuf: Option<&mut UdSocketPath<'static>>,
+    mut flags: c_int,
+    collector: impl Collector,
+) -> io::Result<ReadAncillaryWithFlags> {
+    #[cfg(uds_msg_cmsg_cloexec)]
+    {
+        flags |= libc::MSG_CMSG_CLOEXEC;
+    }
+    recvmsg_inheritable(fd, bufs, ancbuf, addrbuf, flags, collector)
+}
+/// Same as `recvmsg_with_collector()`, but passes `flags` as-is.
+pub(super) fn recvmsg_inheritable<AB: CmsgMut + ?Sized>(
+    fd: BorrowedFd<'_>,
+    bufs: &mut [IoSliceMut<'_>],
+    ancbuf: &mut AB,
+    addrbuf: Option<&mut UdSocketPath<'static>>,
+    flags: c_int,
+    mut collector: impl Collector,
+) -> io::Result<ReadAncillaryWithFlags> {
     let iov = bufs.as_mut_ptr().cast::<iovec>();
     let iovlen = to_msghdr_iovlen(bufs.len())?;
     let mut hdr = make_msghdr(iov, iovlen);
@@ -32,10 +67,12 @@ pub(super) fn recvmsg<AB: CmsgMut + ?Sized>(
         }
     }
 
+    collector.pre_op_collect(fd);
     let bytes_read = unsafe {
         // SAFETY: make_msghdr_r is good at its job
-        c_wrappers::recvmsg(fd, &mut hdr, 0)?
+        c_wrappers::recvmsg(fd, &mut hdr, flags)?
     };
+    collector.post_op_collect(fd, hdr.msg_flags);
     ancbuf.set_truncation_flag(hdr.msg_flags & libc::MSG_CTRUNC != 0);
 
     let advanc = hdr.msg_controllen as _; // FIXME as casts are bad!!
@@ -45,23 +82,65 @@ pub(super) fn recvmsg<AB: CmsgMut + ?Sized>(
     }
 
     if let Some(addr_buf) = addrbuf {
-        addr_buf.write_sockaddr_un_to_self(&addr_buf_staging, hdr.msg_namelen as _);
+        *addr_buf = UdSocketPath::from_sockaddr_un(&addr_buf_staging, hdr.msg_namelen as _)?;
     }
 
-    Ok(ReadAncillarySuccess {
-        main: bytes_read,
-        ancillary: advanc,
+    Ok(ReadAncillaryWithFlags {
+        success: ReadAncillarySuccess {
+            main: bytes_read,
+            ancillary: advanc,
+        },
+        msg_flags: hdr.msg_flags,
     })
 }
 
-pub(super) fn sendmsg(fd: BorrowedFd<'_>, bufs: &[IoSlice<'_>], abuf: CmsgRef<'_>) -> io::Result<usize> {
+/// Rejects sending control messages on a stream without any main-band data to carry them, since the kernel silently
+/// discards the messages in that case instead of reporting an error.
+pub(super) fn check_stream_carrier(bufs: &[IoSlice<'_>], abuf: CmsgRef<'_>) -> io::Result<()> {
+    if !abuf.inner().is_empty() && bufs.iter().all(|b| b.is_empty()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "control messages cannot be sent on a stream without at least one byte of main-band data",
+        ));
+    }
+    Ok(())
+}
+
+#[inline]
+pub(super) fn sendmsg(
+    fd: BorrowedFd<'_>,
+    bufs: &[IoSlice<'_>],
+    abuf: CmsgRef<'_>,
+    addr: Option<&UdSocketPath<'_>>,
+    flags: c_int,
+) -> io::Result<usize> {
+    sendmsg_with_collector(fd, bufs, abuf, addr, flags, DummyCollector)
+}
+pub(super) fn sendmsg_with_collector(
+    fd: BorrowedFd<'_>,
+    bufs: &[IoSlice<'_>],
+    abuf: CmsgRef<'_>,
+    addr: Option<&UdSocketPath<'_>>,
+    flags: c_int,
+    mut collector: impl Collector,
+) -> io::Result<usize> {
     let iov = bufs.as_ptr().cast_mut().cast::<iovec>();
     let iovlen = to_msghdr_iovlen(bufs.len())?;
     let mut hdr = make_msghdr(iov, iovlen);
     abuf.fill_msghdr(&mut hdr)?;
 
-    unsafe {
-        // SAFETY: make_msghdr_w is good at its job
-        c_wrappers::sendmsg(fd, &hdr, 0)
+    let addr = addr.map(UdSocketPath::to_sockaddr_un).transpose()?;
+    if let Some((addr, addrlen)) = &addr {
+        // sendmsg doesn't actually write to msg_name, the pointer is only mutable for uniformity with recvmsg
+        hdr.msg_name = (addr as *const sockaddr_un).cast_mut().cast::<c_void>();
+        hdr.msg_namelen = *addrlen as _;
     }
+
+    collector.pre_op_collect(fd);
+    let bytes_written = unsafe {
+        // SAFETY: make_msghdr_w is good at its job
+        c_wrappers::sendmsg(fd, &hdr, flags)?
+    };
+    collector.post_op_collect(fd, 0);
+    Ok(bytes_written)
 }
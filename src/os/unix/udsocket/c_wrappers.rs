@@ -1,39 +1,54 @@
 use crate::os::unix::{unixprelude::*, FdOps};
-use libc::{msghdr, sockaddr, sockaddr_un, socklen_t, AF_UNIX, O_NONBLOCK, SHUT_RD, SHUT_RDWR, SHUT_WR};
+use libc::{
+    msghdr, sockaddr, sockaddr_un, socklen_t, suseconds_t, time_t, timeval, AF_UNIX, SHUT_RD, SHUT_RDWR, SHUT_WR,
+};
 use std::{
-    ffi::c_void,
+    ffi::{c_void, CStr},
     io,
-    mem::{size_of, size_of_val},
+    mem::{size_of, size_of_val, zeroed},
     net::Shutdown,
+    ptr,
+    time::{Duration, Instant},
 };
 
 #[cfg_attr(target_os = "linux", allow(unused))]
 pub(super) use crate::os::unix::c_wrappers::*;
 
 pub(super) fn create_uds(ty: c_int, nonblocking: bool) -> io::Result<FdOps> {
-    #[allow(unused_mut, clippy::let_and_return)]
-    let ty = {
-        let mut ty = ty;
-        #[cfg(uds_sock_cloexec)]
-        {
-            ty |= libc::SOCK_CLOEXEC;
-        }
-        #[cfg(uds_sock_nonblock)]
-        {
-            if nonblocking {
-                ty |= libc::SOCK_NONBLOCK;
-            }
+    let fd = create_uds_raw(with_creation_flags(ty, nonblocking))?;
+    apply_fallback_creation_flags(&fd, nonblocking)?;
+    Ok(fd)
+}
+/// Like `create_uds`, but creates a pair of sockets which are connected to each other.
+pub(super) fn create_uds_pair(ty: c_int, nonblocking: bool) -> io::Result<(FdOps, FdOps)> {
+    let (fd1, fd2) = create_uds_pair_raw(with_creation_flags(ty, nonblocking))?;
+    apply_fallback_creation_flags(&fd1, nonblocking)?;
+    apply_fallback_creation_flags(&fd2, nonblocking)?;
+    Ok((fd1, fd2))
+}
+#[allow(unused_mut, unused_variables)]
+fn with_creation_flags(mut ty: c_int, nonblocking: bool) -> c_int {
+    #[cfg(uds_sock_cloexec)]
+    {
+        ty |= libc::SOCK_CLOEXEC;
+    }
+    #[cfg(uds_sock_nonblock)]
+    {
+        if nonblocking {
+            ty |= libc::SOCK_NONBLOCK;
         }
-        ty
-    };
-    let fd = create_uds_raw(ty)?;
+    }
+    ty
+}
+/// Sets the flags which could not be set atomically at creation time on this platform.
+fn apply_fallback_creation_flags(fd: &FdOps, nonblocking: bool) -> io::Result<()> {
     if !cfg!(uds_sock_cloexec) {
-        set_cloexec(fd.0.as_fd())?;
+        set_cloexec(fd.0.as_fd(), true)?;
     }
     if !cfg!(uds_sock_nonblock) && nonblocking {
         set_nonblocking(fd.0.as_fd(), nonblocking)?;
     }
-    Ok(fd)
+    Ok(())
 }
 fn create_uds_raw(ty: c_int) -> io::Result<FdOps> {
     let (success, fd) = unsafe {
@@ -50,66 +65,209 @@ fn create_uds_raw(ty: c_int) -> io::Result<FdOps> {
         Err(io::Error::last_os_error())
     }
 }
+fn create_uds_pair_raw(ty: c_int) -> io::Result<(FdOps, FdOps)> {
+    let mut fds: [c_int; 2] = [-1; 2];
+    let success = unsafe { libc::socketpair(AF_UNIX, ty, 0, fds.as_mut_ptr()) != -1 };
+    if success {
+        let fdops = unsafe {
+            // SAFETY: we just created both of these descriptors
+            (FdOps::from_raw_fd(fds[0]), FdOps::from_raw_fd(fds[1]))
+        };
+        Ok(fdops)
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Reads data from the given socket without setting up a `msghdr`, for when neither ancillary data nor the source
+/// address are of interest but `flags` are needed.
+pub(super) fn recv(fd: BorrowedFd<'_>, buf: &mut [u8], flags: c_int) -> io::Result<usize> {
+    retry_on_eintr(fd, libc::SO_RCVTIMEO, || {
+        let (success, bytes_read) = unsafe {
+            let result = libc::recv(fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len(), flags);
+            (result != -1, result as usize)
+        };
+        ok_or_ret_errno!(success => bytes_read)
+    })
+}
 
-/// Reads stream data and ancillary data from the given socket. Pointers are supplied directly via the `msghdr`.
+/// Reads stream data and ancillary data from the given socket. Pointers are supplied directly via the `msghdr`, and
+/// `flags` are passed as-is.
 ///
 /// # Safety
 /// Pointers in `hdr` must not dangle, and ancillary data must be correct.
-#[allow(unused_mut)]
-pub(super) unsafe fn recvmsg(fd: BorrowedFd<'_>, hdr: &mut msghdr, mut flags: c_int) -> io::Result<usize> {
-    #[cfg(target_os = "linux")]
-    {
-        flags |= libc::MSG_CMSG_CLOEXEC;
-    }
-
-    let (success, bytes_read) = unsafe {
-        let result = libc::recvmsg(fd.as_raw_fd(), hdr, flags);
-        (result != -1, result as usize)
-    };
-
-    ok_or_ret_errno!(success => bytes_read)
+pub(super) unsafe fn recvmsg(fd: BorrowedFd<'_>, hdr: &mut msghdr, flags: c_int) -> io::Result<usize> {
+    retry_on_eintr(fd, libc::SO_RCVTIMEO, || {
+        let (success, bytes_read) = unsafe {
+            let result = libc::recvmsg(fd.as_raw_fd(), hdr, flags);
+            (result != -1, result as usize)
+        };
+        ok_or_ret_errno!(success => bytes_read)
+    })
 }
 /// Writes stream data and ancillary data from the given socket. Pointers are supplied directly via the `msghdr`.
 ///
 /// # Safety
 /// Pointers in `hdr` must not dangle, and ancillary data must be correct.
 pub(super) unsafe fn sendmsg(fd: BorrowedFd<'_>, hdr: &msghdr, flags: c_int) -> io::Result<usize> {
-    let (success, bytes_written) = unsafe {
-        let result = libc::sendmsg(fd.as_raw_fd(), hdr, flags);
-        (result != -1, result as usize)
-    };
-    ok_or_ret_errno!(success => bytes_written)
+    retry_on_eintr(fd, libc::SO_SNDTIMEO, || {
+        let (success, bytes_written) = unsafe {
+            let result = libc::sendmsg(fd.as_raw_fd(), hdr, flags);
+            (result != -1, result as usize)
+        };
+        ok_or_ret_errno!(success => bytes_written)
+    })
+}
+
+/// Calls `f` until it fails with an error other than [`Interrupted`](io::ErrorKind::Interrupted), for system calls
+/// which are meant to wait for something to happen rather than to be cut short by a signal.
+///
+/// Retrying the call restarts the kernel's timer, so a socket timeout (`option` names the one that applies to the call,
+/// either `SO_RCVTIMEO` or `SO_SNDTIMEO`) would never elapse if signals arrived often enough. It is thus only retried
+/// for as long as the timeout hasn't run out since the first attempt, and an `EAGAIN` error, the same one the kernel
+/// reports when the timeout elapses by itself, is returned once it has. The timeout is only looked up once the call
+/// has been interrupted. Since a retried call waits for the whole timeout again, the total wait may take up to twice
+/// as long as the timeout.
+fn retry_on_eintr<T>(fd: BorrowedFd<'_>, option: c_int, mut f: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    let start = Instant::now();
+    let mut timeout = None;
+    loop {
+        match f() {
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {
+                let timeout = match timeout {
+                    Some(timeout) => timeout,
+                    None => *timeout.insert(get_timeout(fd, option)?),
+                };
+                if timeout.is_some_and(|t| start.elapsed() >= t) {
+                    return Err(io::Error::from_raw_os_error(libc::EAGAIN));
+                }
+            }
+            els => return els,
+        }
+    }
 }
 
-/// Binds the specified Ud-socket file descriptor to the given address.
+/// Binds the specified Ud-socket file descriptor to the given address, of which the first `addrlen` bytes are used.
 ///
 /// # Safety
-/// `addr` must be properly null-terminated.
-pub(super) unsafe fn bind(fd: BorrowedFd<'_>, addr: &sockaddr_un) -> io::Result<()> {
+/// `addr` must be properly null-terminated and `addrlen` must not exceed the size of `sockaddr_un`.
+pub(super) unsafe fn bind(fd: BorrowedFd<'_>, addr: &sockaddr_un, addrlen: usize) -> io::Result<()> {
     let success = unsafe {
         libc::bind(
             fd.as_raw_fd(),
             // Double cast because you cannot cast a reference to a pointer of arbitrary type
             // but you can cast any narrow pointer to any other narrow pointer
             addr as *const _ as *const sockaddr,
-            size_of::<sockaddr_un>() as u32,
+            addrlen as socklen_t,
         ) != -1
     };
     ok_or_ret_errno!(success => ())
 }
 
-/// Connects the specified Ud-socket file descriptor to the given address.
+/// Connects the specified Ud-socket file descriptor to the given address, of which the first `addrlen` bytes are used.
 ///
 /// # Safety
-/// `addr` must be properly null-terminated.
-pub(super) unsafe fn connect(fd: BorrowedFd<'_>, addr: &sockaddr_un) -> io::Result<()> {
-    let success = unsafe {
-        libc::connect(
-            fd.as_raw_fd(),
-            (addr as *const sockaddr_un).cast(),
-            size_of::<sockaddr_un>() as _,
-        ) != -1
+/// Same as [`bind`].
+pub(super) unsafe fn connect(fd: BorrowedFd<'_>, addr: &sockaddr_un, addrlen: usize) -> io::Result<()> {
+    let mut retried = false;
+    let rslt = retry_on_eintr(fd, libc::SO_SNDTIMEO, || {
+        let success = unsafe {
+            libc::connect(
+                fd.as_raw_fd(),
+                (addr as *const sockaddr_un).cast(),
+                addrlen as socklen_t,
+            ) != -1
+        };
+        let rslt = ok_or_ret_errno!(success => ());
+        retried |= rslt.is_err();
+        rslt
+    });
+    match rslt {
+        // An interrupted connection attempt is allowed to complete in the background
+        Err(e) if retried && e.raw_os_error() == Some(libc::EISCONN) => Ok(()),
+        els => els,
+    }
+}
+
+/// Dissolves the association of a connectionless socket with its default peer by connecting it to an `AF_UNSPEC`
+/// address.
+pub(super) fn disconnect(fd: BorrowedFd<'_>) -> io::Result<()> {
+    let mut addr = unsafe { zeroed::<sockaddr>() };
+    addr.sa_family = libc::AF_UNSPEC as _;
+    let success = unsafe { libc::connect(fd.as_raw_fd(), &addr, size_of::<sockaddr>() as socklen_t) != -1 };
+    match ok_or_ret_errno!(success => ()) {
+        // The BSDs drop the existing association before figuring out that they can't connect to such an address
+        Err(e) if e.raw_os_error() == Some(libc::EAFNOSUPPORT) => Ok(()),
+        els => els,
+    }
+}
+
+/// Retrieves the address of the peer the specified Ud-socket file descriptor is connected to, along with the address
+/// length reported by the kernel.
+pub(super) fn get_peer_name(fd: BorrowedFd<'_>) -> io::Result<(sockaddr_un, usize)> {
+    get_address(fd, libc::getpeername)
+}
+/// Retrieves the address the specified Ud-socket file descriptor is bound to, along with the address length reported
+/// by the kernel.
+pub(super) fn get_socket_name(fd: BorrowedFd<'_>) -> io::Result<(sockaddr_un, usize)> {
+    get_address(fd, libc::getsockname)
+}
+fn get_address(
+    fd: BorrowedFd<'_>,
+    getter: unsafe extern "C" fn(c_int, *mut sockaddr, *mut socklen_t) -> c_int,
+) -> io::Result<(sockaddr_un, usize)> {
+    // SAFETY: sockaddr_un is POD
+    let mut addr = unsafe { zeroed::<sockaddr_un>() };
+    let mut addrlen = size_of::<sockaddr_un>() as socklen_t;
+    let success = unsafe { getter(fd.as_raw_fd(), (&mut addr as *mut sockaddr_un).cast(), &mut addrlen) != -1 };
+    ok_or_ret_errno!(success => (addr, addrlen as usize))
+}
+
+/// Accepts a connection on the given listening socket. If `addr` is `Some`, the address of the peer is written to it
+/// and the address length reported by the kernel is returned alongside the new descriptor; otherwise, the returned
+/// length is zero.
+///
+/// The new descriptor has the close-on-exec flag set, atomically where `accept4` is available.
+pub(super) fn accept(fd: BorrowedFd<'_>, addr: Option<&mut sockaddr_un>) -> io::Result<(FdOps, usize)> {
+    let mut addrlen = size_of::<sockaddr_un>() as socklen_t;
+    let (addrptr, addrlenptr) = match addr {
+        Some(addr) => (
+            (addr as *mut sockaddr_un).cast::<sockaddr>(),
+            &mut addrlen as *mut socklen_t,
+        ),
+        None => {
+            addrlen = 0;
+            (ptr::null_mut(), ptr::null_mut())
+        }
     };
+    let fd = retry_on_eintr(fd, libc::SO_RCVTIMEO, || {
+        let (success, fd) = unsafe {
+            #[cfg(uds_accept4)]
+            let result = libc::accept4(fd.as_raw_fd(), addrptr, addrlenptr, libc::SOCK_CLOEXEC);
+            #[cfg(not(uds_accept4))]
+            let result = libc::accept(fd.as_raw_fd(), addrptr, addrlenptr);
+            (result != -1, result)
+        };
+        ok_or_ret_errno!(success => fd)
+    })?;
+    let fdops = unsafe {
+        // SAFETY: we just created the file descriptor, meaning that it's guaranteeed not to be used elsewhere
+        FdOps::from_raw_fd(fd)
+    };
+    if !cfg!(uds_accept4) {
+        set_cloexec(fdops.0.as_fd(), true)?;
+    }
+    Ok((fdops, addrlen as usize))
+}
+
+/// Changes the permissions of the socket's inode. On Linux, those are inherited by the file created by a subsequent
+/// `bind`; elsewhere, this may have no observable effect.
+pub(super) fn set_socket_mode(fd: BorrowedFd<'_>, mode: mode_t) -> io::Result<()> {
+    let success = unsafe { libc::fchmod(fd.as_raw_fd(), mode) != -1 };
+    ok_or_ret_errno!(success => ())
+}
+pub(super) fn set_file_mode(path: &CStr, mode: mode_t) -> io::Result<()> {
+    let success = unsafe { libc::chmod(path.as_ptr(), mode) != -1 };
     ok_or_ret_errno!(success => ())
 }
 
@@ -134,6 +292,66 @@ pub(super) fn get_socket_option<T>(fd: BorrowedFd<'_>, level: c_int, option: c_i
     ok_or_ret_errno!(success => len.try_into().unwrap())
 }
 
+/// Sets the size of the send or receive buffer (`SO_SNDBUF` or `SO_RCVBUF` respectively) of the given socket.
+pub(super) fn set_buffer_size(fd: BorrowedFd<'_>, option: c_int, size: usize) -> io::Result<()> {
+    let size = c_int::try_from(size)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "buffer size does not fit into an int"))?;
+    unsafe { set_socket_option(fd, libc::SOL_SOCKET, option, &size) }
+}
+/// Retrieves the size of the send or receive buffer (`SO_SNDBUF` or `SO_RCVBUF` respectively) of the given socket.
+pub(super) fn get_buffer_size(fd: BorrowedFd<'_>, option: c_int) -> io::Result<usize> {
+    let mut size: c_int = 0;
+    get_socket_option(fd, libc::SOL_SOCKET, option, &mut size)?;
+    size.try_into()
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "kernel reported a negative buffer size"))
+}
+
+/// Sets the receive or send timeout (`SO_RCVTIMEO` or `SO_SNDTIMEO` respectively) of the given socket. `None` clears
+/// the timeout.
+pub(super) fn set_timeout(fd: BorrowedFd<'_>, option: c_int, timeout: Option<Duration>) -> io::Result<()> {
+    let tv = match timeout {
+        Some(dur) if dur.is_zero() => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot set a zero duration timeout (use None to clear the timeout instead)",
+            ))
+        }
+        Some(dur) => {
+            let mut tv = timeval {
+                tv_sec: dur.as_secs().try_into().unwrap_or(time_t::MAX),
+                tv_usec: dur.subsec_micros() as suseconds_t,
+            };
+            if tv.tv_sec == 0 && tv.tv_usec == 0 {
+                // A sub-microsecond timeout would otherwise round down to "no timeout"
+                tv.tv_usec = 1;
+            }
+            tv
+        }
+        None => timeval { tv_sec: 0, tv_usec: 0 },
+    };
+    unsafe { set_socket_option(fd, libc::SOL_SOCKET, option, &tv) }
+}
+/// Retrieves the receive or send timeout (`SO_RCVTIMEO` or `SO_SNDTIMEO` respectively) of the given socket.
+pub(super) fn get_timeout(fd: BorrowedFd<'_>, option: c_int) -> io::Result<Option<Duration>> {
+    let mut tv = timeval { tv_sec: 0, tv_usec: 0 };
+    get_socket_option(fd, libc::SOL_SOCKET, option, &mut tv)?;
+    if tv.tv_sec == 0 && tv.tv_usec == 0 {
+        return Ok(None);
+    }
+    let invalid = || {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "kernel reported an out-of-range timeout value",
+        )
+    };
+    let secs = u64::try_from(tv.tv_sec).map_err(|_| invalid())?;
+    let micros = u32::try_from(tv.tv_usec)
+        .ok()
+        .filter(|&us| us < 1_000_000)
+        .ok_or_else(invalid)?;
+    Ok(Some(Duration::new(secs, micros * 1000)))
+}
+
 #[cfg(uds_cont_credentials)]
 pub(super) fn set_continuous_ancillary_cred(fd: BorrowedFd<'_>, val: bool) -> io::Result<()> {
     #[cfg(uds_ucred)]
@@ -145,6 +363,192 @@ pub(super) fn set_continuous_ancillary_cred(fd: BorrowedFd<'_>, val: bool) -> io
         unsafe { set_socket_option(fd, super::OPTLEVEL, libc::LOCAL_CREDS_PERSISTENT, &c_int::from(val)) }
     }
 }
+#[cfg(uds_cont_credentials)]
+pub(super) fn get_continuous_ancillary_cred(fd: BorrowedFd<'_>) -> io::Result<bool> {
+    #[cfg(uds_ucred)]
+    let option = libc::SO_PASSCRED;
+    #[cfg(uds_sockcred2)]
+    let option = libc::LOCAL_CREDS_PERSISTENT;
+    let mut val: c_int = 0;
+    get_socket_option(fd, super::OPTLEVEL, option, &mut val)?;
+    Ok(val != 0)
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn set_mark(fd: BorrowedFd<'_>, mark: u32) -> io::Result<()> {
+    unsafe { set_socket_option(fd, libc::SOL_SOCKET, libc::SO_MARK, &mark) }
+}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn get_mark(fd: BorrowedFd<'_>) -> io::Result<u32> {
+    let mut mark: u32 = 0;
+    get_socket_option(fd, libc::SOL_SOCKET, libc::SO_MARK, &mut mark)?;
+    Ok(mark)
+}
+
+/// Not yet in the libc crate. Only SPARC deviates from the generic value among the architectures Rust supports.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SO_PEERPIDFD: c_int = if cfg!(any(target_arch = "sparc", target_arch = "sparc64")) {
+    0x56
+} else {
+    77
+};
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn get_peer_pidfd(fd: BorrowedFd<'_>) -> io::Result<OwnedFd> {
+    let mut pidfd: c_int = -1;
+    match get_socket_option(fd, libc::SOL_SOCKET, SO_PEERPIDFD, &mut pidfd) {
+        Err(e) if e.raw_os_error() == Some(libc::ENOPROTOOPT) => {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "SO_PEERPIDFD is not supported by the kernel (requires Linux 6.5 or newer)",
+            ))
+        }
+        els => els?,
+    };
+    // SAFETY: the kernel has just installed a new file descriptor for us
+    Ok(unsafe { OwnedFd::from_raw_fd(pidfd) })
+}
+
+/// Missing from the libc crate on Android. As with `SO_PEERPIDFD`, only SPARC deviates from the generic value.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const SO_PEERGROUPS: c_int = if cfg!(any(target_arch = "sparc", target_arch = "sparc64")) {
+    0x3d
+} else {
+    59
+};
+/// Retrieves the supplementary groups of the peer, growing the buffer for as long as the kernel reports it as too
+/// small.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn get_peer_groups(fd: BorrowedFd<'_>) -> io::Result<Vec<gid_t>> {
+    let mut groups = Vec::<gid_t>::with_capacity(16);
+    loop {
+        let mut len = socklen_t::try_from(groups.capacity() * size_of::<gid_t>()).unwrap_or(socklen_t::MAX);
+        let success = unsafe {
+            libc::getsockopt(
+                fd.as_raw_fd(),
+                libc::SOL_SOCKET,
+                SO_PEERGROUPS,
+                groups.as_mut_ptr().cast(),
+                &mut len,
+            ) != -1
+        };
+        let len = len as usize / size_of::<gid_t>();
+        if success {
+            // SAFETY: the kernel has initialized this many elements, none of which have drop glue
+            unsafe { groups.set_len(len) };
+            return Ok(groups);
+        }
+        match io::Error::last_os_error() {
+            // The kernel reports the required buffer size in `len`
+            e if e.raw_os_error() == Some(libc::ERANGE) && len > groups.capacity() => groups.reserve_exact(len),
+            e if e.raw_os_error() == Some(libc::ENOPROTOOPT) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "SO_PEERGROUPS is not supported by the kernel (requires Linux 4.13 or newer)",
+                ))
+            }
+            e => return Err(e),
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn set_continuous_ancillary_security(fd: BorrowedFd<'_>, val: bool) -> io::Result<()> {
+    unsafe { set_socket_option(fd, libc::SOL_SOCKET, libc::SO_PASSSEC, &c_int::from(val)) }
+}
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn set_receive_timestamps(fd: BorrowedFd<'_>, val: bool) -> io::Result<()> {
+    unsafe { set_socket_option(fd, libc::SOL_SOCKET, libc::SO_TIMESTAMPNS, &c_int::from(val)) }
+}
+
+/// Creates a close-on-exec pipe to be used as the intermediate buffer for `splice`.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn create_splice_pipe() -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds: [c_int; 2] = [-1; 2];
+    let success = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) != -1 };
+    ok_or_ret_errno!(success => unsafe {
+        // SAFETY: we just created both of these descriptors
+        (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1]))
+    })
+}
+/// Moves up to `len` bytes from `src` to `dst` without copying them to userspace. One of the two has to be a pipe.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn splice(src: BorrowedFd<'_>, dst: BorrowedFd<'_>, len: usize) -> io::Result<usize> {
+    let val = unsafe {
+        libc::splice(
+            src.as_raw_fd(),
+            ptr::null_mut(),
+            dst.as_raw_fd(),
+            ptr::null_mut(),
+            len,
+            libc::SPLICE_F_MOVE,
+        )
+    };
+    ok_or_ret_errno!(val != -1 => val as usize)
+}
+/// Queries the amount of memory taken up by sent data which the receiver has yet to read.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn send_queue_len(fd: BorrowedFd<'_>) -> io::Result<usize> {
+    let mut len: c_int = 0;
+    // SIOCOUTQ is the same ioctl as TIOCOUTQ, which is what the libc crate calls it
+    let success = unsafe { libc::ioctl(fd.as_raw_fd(), libc::TIOCOUTQ, &mut len) != -1 };
+    ok_or_ret_errno!(success => len as usize)
+}
+/// Waits until `fd` becomes writable, regardless of whether it is in nonblocking mode.
+pub(super) fn wait_writable(fd: BorrowedFd<'_>) -> io::Result<()> {
+    poll(&mut [pollfd_for(fd, libc::POLLOUT)], None).map(drop)
+}
+
+/// Waits until `fd` becomes writable or the timeout runs out, returning `false` in the latter case.
+pub(super) fn wait_writable_timeout(fd: BorrowedFd<'_>, timeout: Duration) -> io::Result<bool> {
+    poll(&mut [pollfd_for(fd, libc::POLLOUT)], Some(timeout)).map(|n| n != 0)
+}
+
+pub(super) fn pollfd_for(fd: BorrowedFd<'_>, events: c_short) -> libc::pollfd {
+    libc::pollfd {
+        fd: fd.as_raw_fd(),
+        events,
+        revents: 0,
+    }
+}
+/// Polls until at least one of the descriptors is ready or the timeout (if any) runs out, returning the amount of
+/// ready descriptors, which is zero in the latter case. Interrupted polls are resumed with the remaining time.
+pub(super) fn poll(pfds: &mut [libc::pollfd], timeout: Option<Duration>) -> io::Result<usize> {
+    let deadline = timeout.map(|t| Instant::now() + t);
+    loop {
+        let ms = match deadline {
+            Some(deadline) => {
+                let left = deadline.saturating_duration_since(Instant::now());
+                // Rounded up so that sub-millisecond remainders don't turn into a busy loop of zero-timeout polls.
+                ((left.as_nanos() + 999_999) / 1_000_000)
+                    .try_into()
+                    .unwrap_or(c_int::MAX)
+            }
+            None => -1,
+        };
+        let val = unsafe { libc::poll(pfds.as_mut_ptr(), pfds.len() as libc::nfds_t, ms) };
+        match ok_or_ret_errno!(val != -1 => val as usize) {
+            // Timeouts longer than c_int::MAX milliseconds take multiple polls
+            Ok(0) if deadline.is_some_and(|d| Instant::now() < d) => continue,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            els => return els,
+        }
+    }
+}
+/// Retrieves and clears the pending error of the given socket (`SO_ERROR`).
+pub(super) fn take_error(fd: BorrowedFd<'_>) -> io::Result<Option<io::Error>> {
+    let mut errno: c_int = 0;
+    get_socket_option(fd, libc::SOL_SOCKET, libc::SO_ERROR, &mut errno)?;
+    Ok((errno != 0).then(|| io::Error::from_raw_os_error(errno)))
+}
+
+/// Reads the `LOCAL_CREDS` option, which makes the next `SCM_CREDS` message carry a `sockcred` instead of a
+/// `cmsgcred`.
+#[cfg(uds_sockcred2)]
+pub(super) fn get_local_creds(fd: BorrowedFd<'_>) -> io::Result<bool> {
+    let mut val: c_int = 0;
+    get_socket_option(fd, super::OPTLEVEL, libc::LOCAL_CREDS, &mut val)?;
+    Ok(val != 0)
+}
 
 #[cfg(uds_sockcred)]
 pub(super) fn set_oneshot_ancillary_cred(fd: BorrowedFd<'_>, val: bool) -> io::Result<()> {
@@ -165,30 +569,25 @@ pub(super) fn get_peer_xucred(fd: BorrowedFd<'_>) -> io::Result<libc::xucred> {
     Ok(cred)
 }
 
-fn get_status_flags(fd: BorrowedFd<'_>) -> io::Result<c_int> {
-    unsafe { fcntl_noarg(fd, libc::F_GETFL) }
-}
-fn set_status_flags(fd: BorrowedFd<'_>, new_flags: c_int) -> io::Result<()> {
-    unsafe {
-        fcntl_int(fd, libc::F_SETFL, new_flags)?;
-    }
-    Ok(())
+#[cfg(uds_sockpeercred)]
+pub(super) fn get_peer_sockpeercred(fd: BorrowedFd<'_>) -> io::Result<libc::sockpeercred> {
+    let mut cred = libc::sockpeercred { uid: 0, gid: 0, pid: 0 };
+    get_socket_option(fd, super::OPTLEVEL, libc::SO_PEERCRED, &mut cred)?;
+    Ok(cred)
 }
-pub(super) fn set_nonblocking(fd: BorrowedFd<'_>, nonblocking: bool) -> io::Result<()> {
-    let old_flags = get_status_flags(fd)?;
-    let new_flags = if nonblocking {
-        old_flags | O_NONBLOCK
-    } else {
-        // Inverting the O_NONBLOCK value sets all the bits in the flag set to 1 except for the
-        // nonblocking flag, which clears the flag when ANDed.
-        old_flags & !O_NONBLOCK
+
+#[cfg(uds_unpcbid)]
+pub(super) fn get_peer_unpcbid(fd: BorrowedFd<'_>) -> io::Result<libc::unpcbid> {
+    let mut cred = libc::unpcbid {
+        unp_pid: 0,
+        unp_euid: 0,
+        unp_egid: 0,
     };
-    set_status_flags(fd, new_flags)
-}
-pub(super) fn get_nonblocking(fd: BorrowedFd<'_>) -> io::Result<bool> {
-    let flags = get_status_flags(fd)?;
-    Ok(flags & O_NONBLOCK != 0)
+    // Lives on SOL_LOCAL rather than SOL_SOCKET, the former having no name in the libc crate.
+    get_socket_option(fd, 0, libc::LOCAL_PEEREID, &mut cred)?;
+    Ok(cred)
 }
+
 pub(super) fn shutdown(fd: BorrowedFd<'_>, how: Shutdown) -> io::Result<()> {
     let how = match how {
         Shutdown::Read => SHUT_RD,
@@ -5,14 +5,19 @@
 
 pub use crate::os::unix::udsocket::credentials::*;
 
+#[cfg(uds_sockcred2)]
+use super::super::context::{LocalCredsCollector, ScmCredsFormat};
 use super::*;
 #[cfg(uds_cmsgcred)]
 use libc::cmsgcred;
-#[cfg(uds_sockcred2)]
-use libc::sockcred2;
 #[cfg(uds_ucred)]
 use libc::ucred;
-use std::{mem::size_of, slice};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    mem::size_of,
+    slice,
+};
 
 /// Functions for creating tables of credentials to be sent as ancillary messages.
 impl<'a> Credentials<'a> {
@@ -31,8 +36,9 @@ impl<'a> Credentials<'a> {
     /// The smallest possible ancillary *payload size* of the largest supported credentials structure on the current
     /// platform, as a [`c_uint`].
     ///
-    /// You can use [`Cmsg::cmsg_len_for_payload_size()`](crate::os::unix::udsocket::cmsg::Cmsg) to calculate the
-    /// smallest compatible buffer size.
+    /// You can use [`Cmsg::cmsg_space_for_payload_size()`] to calculate the smallest compatible buffer size.
+    ///
+    /// [`Cmsg::cmsg_space_for_payload_size()`]: crate::os::unix::udsocket::cmsg::Cmsg::cmsg_space_for_payload_size
     ///
     /// Note that this does not actually guarantee reception of certain types ancillary messages, with `sockcred2` on
     /// FreeBSD being the worst offender, since their dynamically-sized nature is often ignored by the code in the OS
@@ -218,17 +224,17 @@ impl ToCmsg for Credentials<'_> {
     )))
 )]
 impl<'a> FromCmsg<'a> for Credentials<'a> {
-    type MalformedPayloadError = SizeMismatch;
+    type MalformedPayloadError = CredentialsDecodeError;
     #[cfg(uds_ucred)]
     #[inline]
     fn try_parse(mut cmsg: Cmsg<'a>) -> ParseResult<'a, Self, Self::MalformedPayloadError> {
         cmsg = check_level_and_type(cmsg, Self::ANCTYPE1)?;
-        unsafe { into_fixed_size_contents::<ucred_packed>(cmsg) }
+        unsafe { into_fixed_size_contents::<ucred_packed, _>(cmsg) }
             .map(CredentialsInner::AncUcred)
             .map(Self)
     }
     #[cfg(uds_cmsgcred)]
-    fn try_parse(mut cmsg: Cmsg<'a>) -> ParseResult<'a, Self, SizeMismatch> {
+    fn try_parse(mut cmsg: Cmsg<'a>) -> ParseResult<'a, Self, Self::MalformedPayloadError> {
         cmsg = check_level(cmsg)?;
         let expected = if !cfg!(uds_sockcred2) {
             Some(libc::SCM_CREDS)
@@ -237,43 +243,138 @@ impl<'a> FromCmsg<'a> for Credentials<'a> {
         };
         let inner = match cmsg.cmsg_type() {
             libc::SCM_CREDS => {
-                unsafe { into_fixed_size_contents::<cmsgcred_packed>(cmsg) }.map(CredentialsInner::Cmsgcred)?
+                unsafe { into_fixed_size_contents::<cmsgcred_packed, _>(cmsg) }.map(CredentialsInner::Cmsgcred)?
             }
             #[cfg(uds_sockcred2)]
-            libc::SCM_CREDS2 => {
-                let min_expected = size_of::<sockcred2>();
-                let len = cmsg.data().len();
-                if len < min_expected {
-                    // If this is false, we can't even do the reinterpret and figure out the number
-                    // of supplementary groups; prioritize formal soundness over error reporting
-                    // precision in this niche case and claim to expect the base size of sockcred.
-                    return Err(ParseErrorKind::MalformedPayload(SizeMismatch {
-                        expected: min_expected,
-                        got: len,
-                    })
-                    .wrap(cmsg));
-                }
-
-                let creds = unsafe {
-                    // SAFETY: POD
-                    &*cmsg.data().as_ptr().cast::<sockcred2>()
-                };
-
-                let expected = unsafe { libc::SOCKCRED2SIZE(creds.sc_ngroups as _) };
-                // Be nice on the alignment here.
-                if len < expected {
-                    // The rest of the size error reporting process happens here.
-                    return Err(ParseErrorKind::MalformedPayload(SizeMismatch { expected, got: len }).wrap(cmsg));
-                }
-
-                CredentialsInner::Sockcred2(creds.as_ref())
-            }
+            libc::SCM_CREDS2 => unsafe { into_dynamic_size_contents::<sockcred2_packed>(cmsg, |c| c.sc_ngroups) }
+                .map(CredentialsInner::Sockcred2)?,
             els => return Err(ParseErrorKind::WrongType { expected, got: els }.wrap(cmsg)),
         };
         Ok(Self(inner))
     }
 }
 
+/// Context-aware parsing of `SCM_CREDS`.
+#[cfg_attr(feature = "doc_cfg", doc(cfg(target_os = "freebsd")))]
+#[cfg(uds_sockcred2)]
+impl<'a> Credentials<'a> {
+    /// Parses `cmsg` like [`try_parse()`](FromCmsg::try_parse) does, but decodes `SCM_CREDS` messages as `sockcred`
+    /// rather than `cmsgcred` if `ctx` has observed the `LOCAL_CREDS` option to be enabled before the receive
+    /// operation.
+    ///
+    /// If `ctx` was unable to read the option, `cmsgcred` is assumed.
+    pub fn try_parse_with_context(
+        cmsg: Cmsg<'a>,
+        ctx: &LocalCredsCollector,
+    ) -> ParseResult<'a, Self, CredentialsDecodeError> {
+        if ctx.scm_creds_format() != Some(ScmCredsFormat::Sockcred) {
+            return Self::try_parse(cmsg);
+        }
+        let cmsg = check_level_and_type(cmsg, libc::SCM_CREDS)?;
+        unsafe { into_dynamic_size_contents::<sockcred_packed>(cmsg, |c| c.sc_ngroups) }
+            .map(CredentialsInner::Sockcred)
+            .map(Self)
+    }
+}
+
+/// The [`MalformedPayload`](ParseErrorKind::MalformedPayload) error type for [`Credentials`], produced when the size
+/// of the payload doesn't add up with the credentials structure it's supposed to contain.
+///
+/// The decoder never reads the payload as a credentials structure before checking its size, which is why a truncated
+/// message produces this error rather than garbage.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CredentialsDecodeError {
+    /// The payload is too short to contain the structure, or, for structures which end in a variable-length group
+    /// list, too short to contain the amount of groups that the structure claims to have.
+    TooShort {
+        /// The size of the payload.
+        got: usize,
+        /// The smallest size that would have been accepted.
+        expected: usize,
+    },
+    /// The payload is longer than the fixed-size structure it's supposed to contain.
+    TooLong {
+        /// The size of the payload.
+        got: usize,
+        /// The size of the structure.
+        expected: usize,
+    },
+    /// The structure claims to have a negative amount of supplementary groups.
+    NegativeGroupCount(c_int),
+}
+impl From<SizeMismatch> for CredentialsDecodeError {
+    #[inline]
+    fn from(SizeMismatch { expected, got }: SizeMismatch) -> Self {
+        if got < expected {
+            Self::TooShort { got, expected }
+        } else {
+            Self::TooLong { got, expected }
+        }
+    }
+}
+impl Display for CredentialsDecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::TooShort { got, expected } => {
+                write!(
+                    f,
+                    "credentials payload too short (expected at least {expected}, got {got})"
+                )
+            }
+            Self::TooLong { got, expected } => {
+                write!(f, "credentials payload too long (expected {expected}, got {got})")
+            }
+            Self::NegativeGroupCount(n) => write!(f, "negative supplementary group count {n} in credentials"),
+        }
+    }
+}
+impl Error for CredentialsDecodeError {}
+
+/// Deserializes the given ancillary message's contents into a `sockcred`-style structure `T`, which ends in a
+/// variable-length group list whose length, as read by `ngroups`, is stored in the structure itself. Both the size of
+/// the structure and that of the whole group list are checked against the size of the payload.
+///
+/// # Safety
+/// `T` must be a plain-old-data structure with an alignment of 1 whose size accounts for exactly one group ID.
+#[cfg(uds_sockcred2)]
+unsafe fn into_dynamic_size_contents<T>(
+    cmsg: Cmsg<'_>,
+    ngroups: impl FnOnce(&T) -> c_int,
+) -> ParseResult<'_, &T, CredentialsDecodeError> {
+    let base = size_of::<T>();
+    let got = cmsg.data().len();
+    if got < base {
+        // We can't even do the reinterpret and figure out the number of supplementary groups, so we claim to expect
+        // the base size of the structure.
+        return Err(
+            ParseErrorKind::MalformedPayload(CredentialsDecodeError::TooShort { got, expected: base }).wrap(cmsg),
+        );
+    }
+
+    let creds = unsafe {
+        // SAFETY: POD with no alignment requirements, and the size has just been checked
+        &*cmsg.data().as_ptr().cast::<T>()
+    };
+
+    let n = ngroups(creds);
+    let Ok(n_usize) = usize::try_from(n) else {
+        return Err(ParseErrorKind::MalformedPayload(CredentialsDecodeError::NegativeGroupCount(n)).wrap(cmsg));
+    };
+    // Same as SOCKCREDSIZE() and SOCKCRED2SIZE(), but with overflow checking.
+    let expected = n_usize
+        .saturating_sub(1)
+        .checked_mul(size_of::<gid_t>())
+        .and_then(|extra| extra.checked_add(base))
+        .unwrap_or(usize::MAX);
+    // Be nice on the alignment here.
+    if got < expected {
+        return Err(ParseErrorKind::MalformedPayload(CredentialsDecodeError::TooShort { got, expected }).wrap(cmsg));
+    }
+
+    Ok(creds)
+}
+
 #[cfg(uds_cmsgcred)]
 pub(super) static ZEROED_CMSGCRED: cmsgcred = cmsgcred {
     cmcred_pid: 0,
@@ -3,6 +3,8 @@ use super::credentials::Credentials;
 use super::{
     file_descriptors::FileDescriptors, Cmsg, FromCmsg, ParseError, ParseErrorKind, ParseResult, SizeMismatch, LEVEL,
 };
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use super::{security_context::SecurityContext, timestamp::Timestamp};
 use std::{
     convert::Infallible,
     error::Error,
@@ -26,6 +28,18 @@ pub enum Ancillary<'a> {
     )]
     #[cfg(uds_ancillary_credentials)]
     Credentials(Credentials<'a>),
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    SecurityContext(SecurityContext<'a>),
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    Timestamp(Timestamp),
 }
 impl<'a> Ancillary<'a> {
     fn parse_fd(cmsg: Cmsg<'a>) -> ParseResult<'a, Self, MalformedPayload> {
@@ -39,6 +53,18 @@ impl<'a> Ancillary<'a> {
             .map(Self::Credentials)
             .map_err(|e| e.map_payload_err(MalformedPayload::Credentials))
     }
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn parse_security_context(cmsg: Cmsg<'a>) -> ParseResult<'a, Self, MalformedPayload> {
+        SecurityContext::try_parse(cmsg)
+            .map(Self::SecurityContext)
+            .map_err(|e| e.map_payload_err(MalformedPayload::from))
+    }
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn parse_timestamp(cmsg: Cmsg<'a>) -> ParseResult<'a, Self, MalformedPayload> {
+        Timestamp::try_parse(cmsg)
+            .map(Self::Timestamp)
+            .map_err(|e| e.map_payload_err(MalformedPayload::Timestamp))
+    }
 }
 impl<'a> FromCmsg<'a> for Ancillary<'a> {
     type MalformedPayloadError = MalformedPayload;
@@ -61,6 +87,10 @@ impl<'a> FromCmsg<'a> for Ancillary<'a> {
             Credentials::ANCTYPE1 => Self::parse_credentials(cmsg),
             #[cfg(uds_sockcred2)]
             Credentials::ANCTYPE2 => Self::parse_credentials(cmsg),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            SecurityContext::ANCTYPE => Self::parse_security_context(cmsg),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Timestamp::ANCTYPE => Self::parse_timestamp(cmsg),
             _ => Err(ParseError {
                 cmsg,
                 kind: ParseErrorKind::WrongType {
@@ -72,6 +102,78 @@ impl<'a> FromCmsg<'a> for Ancillary<'a> {
     }
 }
 
+/// A control message decoded by [`CmsgRef::decode_all()`](super::super::CmsgRef::decode_all).
+///
+/// Unlike [`Ancillary`], this type can represent control messages of levels and types unknown to this module, which
+/// allows for going through a buffer of mixed ancillary data in a single pass without any of the messages producing
+/// an error.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ControlMessage<'a> {
+    /// File descriptors sent via `SCM_RIGHTS`.
+    Fds(FileDescriptors<'a>),
+    /// Credentials of the process on the other end.
+    #[cfg_attr( // uds_ucred template
+        feature = "doc_cfg",
+        doc(cfg(any(
+            target_os = "linux",
+            target_os = "redox",
+            target_os = "android",
+            target_os = "fuchsia",
+        )))
+    )]
+    #[cfg(uds_ancillary_credentials)]
+    Credentials(Credentials<'a>),
+    /// Security context of the process on the other end.
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    SecurityContext(SecurityContext<'a>),
+    /// Time at which the data was received.
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    Timestamp(Timestamp),
+    /// A control message of a level or type not known to this module, left undecoded.
+    Other(Cmsg<'a>),
+    /// A control message of a known level and type which has a malformed payload. The error contains the original
+    /// control message.
+    Malformed(ParseError<'a, MalformedPayload>),
+}
+impl<'a> From<Ancillary<'a>> for ControlMessage<'a> {
+    #[inline]
+    fn from(anc: Ancillary<'a>) -> Self {
+        match anc {
+            Ancillary::FileDescriptors(fds) => Self::Fds(fds),
+            #[cfg(uds_ancillary_credentials)]
+            Ancillary::Credentials(creds) => Self::Credentials(creds),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Ancillary::SecurityContext(ctx) => Self::SecurityContext(ctx),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Ancillary::Timestamp(ts) => Self::Timestamp(ts),
+        }
+    }
+}
+/// Control messages which [`Ancillary`] cannot parse end up in the `Other` or `Malformed` variants.
+impl<'a> From<Cmsg<'a>> for ControlMessage<'a> {
+    fn from(cmsg: Cmsg<'a>) -> Self {
+        match Ancillary::try_parse(cmsg) {
+            Ok(anc) => anc.into(),
+            Err(
+                e @ ParseError {
+                    kind: ParseErrorKind::MalformedPayload(..),
+                    ..
+                },
+            ) => Self::Malformed(e),
+            Err(ParseError { cmsg, .. }) => Self::Other(cmsg),
+        }
+    }
+}
+
 /// Compound error type for [`Ancillary`]'s [`FromCmsg`] implementation.
 #[derive(Debug)]
 #[non_exhaustive]
@@ -88,7 +190,13 @@ pub enum MalformedPayload {
         )))
     )]
     #[cfg(uds_ancillary_credentials)]
-    Credentials(SizeMismatch),
+    Credentials(super::credentials::CredentialsDecodeError),
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    Timestamp(super::timestamp::TimestampDecodeError),
 }
 impl Display for MalformedPayload {
     fn fmt(&self, _f: &mut Formatter<'_>) -> fmt::Result {
@@ -96,6 +204,8 @@ impl Display for MalformedPayload {
             Self::FileDescriptors(e) => Display::fmt(&e, _f),
             #[cfg(uds_ancillary_credentials)]
             Self::Credentials(e) => Display::fmt(&e, _f),
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            Self::Timestamp(e) => Display::fmt(&e, _f),
         }
     }
 }
@@ -1,7 +1,8 @@
 //! [`FileDescriptors`] and associated helper types.
 use super::*;
 use std::{
-    mem::{align_of, size_of, transmute},
+    iter::FusedIterator,
+    mem::{size_of, transmute},
     os::fd::{BorrowedFd, FromRawFd, OwnedFd, RawFd},
     slice,
 };
@@ -9,6 +10,9 @@ use std::{
 /// Ancillary data message that allows sending ownership of file descriptors over to another process.
 ///
 /// The file descriptors are stored as a slice of [`OwnedFd`]s.
+///
+/// On platforms with `MSG_CMSG_CLOEXEC`, file descriptors received by this crate's receive operations have the
+/// close-on-exec flag set, unless one of the `recv_ancillary_vectored_inheritable()` methods was used.
 #[derive(Debug, Default)]
 pub struct FileDescriptors<'a>(UnalignedFdSlice<'a>);
 impl<'a> FileDescriptors<'a> {
@@ -30,6 +34,69 @@ impl<'a> FileDescriptors<'a> {
     pub const unsafe fn new_raw(descriptors: &'a [RawFd], owned: bool) -> Self {
         unsafe { Self(UnalignedFdSlice::from_raw_fd_slice(descriptors, owned)) }
     }
+
+    /// Returns the number of file descriptors in the message.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.0.fds.len()
+    }
+    /// Returns `true` if the message contains no file descriptors.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Transfers ownership of the contained file descriptors to an iterator which yields them as [`OwnedFd`]s. Those
+    /// which are not taken out of the iterator are closed when it's dropped.
+    ///
+    /// This is how file descriptors received from a socket are to be extracted. If the message does not own its file
+    /// descriptors (i.e. it was constructed for sending from borrowed ones), `Err(self)` is returned.
+    pub fn into_owned_fds(mut self) -> Result<IntoOwnedFds<'a>, Self> {
+        if !self.0.owned {
+            return Err(self);
+        }
+        // Disarms the destructor of the slice so that the iterator becomes the sole owner
+        self.0.owned = false;
+        Ok(IntoOwnedFds(self.0.fds.iter()))
+    }
+    /// Takes ownership of the file descriptors in all `SCM_RIGHTS` messages of the given received control data, as
+    /// long as there are no more than `max_fds` of them in total.
+    ///
+    /// File descriptors are counted as they are decoded. If the limit is exceeded, every file descriptor in the control
+    /// data, including those already taken, is closed and [`FdLimitExceeded`] is returned, so that a hostile peer
+    /// cannot make the receiver accumulate file descriptors by sending more of them than it expects. Control messages
+    /// of other types are ignored.
+    ///
+    /// Since the kernel installs received file descriptors into the file descriptor table of the process during the
+    /// receive operation itself, this must be combined with a control message buffer of bounded size – the kernel
+    /// closes the file descriptors that don't fit into the buffer and reports [truncation] instead.
+    ///
+    /// Like any other way of decoding file descriptors from a received buffer, this must only be done once per receive
+    /// operation, since the file descriptors would otherwise be closed twice.
+    ///
+    /// [truncation]: crate::os::unix::udsocket::ReadAncillaryWithFlags::ancillary_truncated
+    pub fn take_limited(cmsgs: CmsgRef<'_>, max_fds: usize) -> Result<Vec<OwnedFd>, FdLimitExceeded> {
+        let mut taken = Vec::new();
+        let mut received = 0_usize;
+        for msg in cmsgs.decode::<FileDescriptors<'_>>() {
+            let Ok(Ok(fds)) = msg.map(FileDescriptors::into_owned_fds) else {
+                continue;
+            };
+            received += fds.len();
+            if received > max_fds {
+                // Closes them right away
+                drop(fds);
+            } else {
+                taken.extend(fds);
+            }
+        }
+        if received > max_fds {
+            return Err(FdLimitExceeded {
+                limit: max_fds,
+                received,
+            });
+        }
+        Ok(taken)
+    }
 }
 impl ToCmsg for FileDescriptors<'_> {
     #[inline]
@@ -40,30 +107,70 @@ impl ToCmsg for FileDescriptors<'_> {
         }
     }
 }
-/// Only errors with `MalformedPayload` if the length isn't aligned to the size of a file descriptor.
+/// Never errors with `MalformedPayload`. If the payload does not consist of a whole number of file descriptors, which
+/// can happen if the control message was truncated, the trailing bytes are ignored and all whole file descriptors are
+/// kept, so that they can be closed instead of leaking.
 impl<'a> FromCmsg<'a> for FileDescriptors<'a> {
     type MalformedPayloadError = SizeMismatch;
 
     fn try_parse(mut cmsg: Cmsg<'a>) -> ParseResult<'a, Self, Self::MalformedPayloadError> {
         cmsg = check_level_and_type(cmsg, Self::ANCTYPE)?;
-        let unalign_mask = (1_usize << align_of::<c_int>()) - 1;
-        let len = cmsg.data().len();
-        if len & unalign_mask != 0 {
-            return Err(ParseErrorKind::MalformedPayload(SizeMismatch {
-                expected: len + 1,
-                got: len,
-            })
-            .wrap(cmsg));
-        }
-
         unsafe {
             // SAFETY: we trust the Linux kernel, don't we? Also, that Cmsg isn't `Copy` or `Clone` or anything, so we
-            // can safely own these descriptors.
+            // can safely own these descriptors. `from_byte_slice` rounds the length down to whole file descriptors.
             Ok(Self(UnalignedFdSlice::from_byte_slice(cmsg.data(), true)))
         }
     }
 }
 
+/// Error indicating that more file descriptors were received than the limit given to
+/// [`FileDescriptors::take_limited()`] allows. All of them have been closed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FdLimitExceeded {
+    /// The maximum amount of file descriptors that was allowed.
+    pub limit: usize,
+    /// The amount of file descriptors that were received.
+    pub received: usize,
+}
+impl Display for FdLimitExceeded {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Self { limit, received } = self;
+        write!(
+            f,
+            "received {received} file descriptors while only {limit} were allowed, all of them were closed"
+        )
+    }
+}
+impl Error for FdLimitExceeded {}
+
+/// Iterator over file descriptors taken out of a [`FileDescriptors`] message.
+///
+/// Created by the [`into_owned_fds()`](FileDescriptors::into_owned_fds) method. Dropping it closes the file
+/// descriptors which haven't been yielded yet.
+#[derive(Debug)]
+pub struct IntoOwnedFds<'a>(slice::Iter<'a, UnalignedFd>);
+impl Iterator for IntoOwnedFds<'_> {
+    type Item = OwnedFd;
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|fd| unsafe {
+            // SAFETY: ownership was transferred to the iterator, and each element is only yielded once
+            fd.into_owned_fd()
+        })
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+impl ExactSizeIterator for IntoOwnedFds<'_> {}
+impl FusedIterator for IntoOwnedFds<'_> {}
+impl Drop for IntoOwnedFds<'_> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
 type UnalignedFdBytes = [u8; size_of::<RawFd>()];
 /// Unaligned file descriptor with an initialization guarantee.
 #[derive(Copy, Clone, PartialEq, Eq)]
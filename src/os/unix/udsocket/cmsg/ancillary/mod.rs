@@ -4,7 +4,7 @@
 //! serialization without the use of unsafe code. It also includes parsers for those types of control messages and a
 //! catch-all parser that can parse all control message types that are known to this module.
 
-// TODO SCM_TIMESTAMP, also the one with nanosecond precision
+// TODO SCM_TIMESTAMP and its BSD counterparts
 
 #[cfg_attr( // uds_ancillary_credentials template
     feature = "doc_cfg",
@@ -20,6 +20,18 @@
 #[cfg(uds_ancillary_credentials)]
 pub mod credentials;
 pub mod file_descriptors;
+#[cfg_attr( // uds_linux_namespace template
+    feature = "doc_cfg",
+    doc(cfg(any(target_os = "linux", target_os = "android")))
+)]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub mod security_context;
+#[cfg_attr( // uds_linux_namespace template
+    feature = "doc_cfg",
+    doc(cfg(any(target_os = "linux", target_os = "android")))
+)]
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub mod timestamp;
 
 mod dispatcher;
 pub use dispatcher::*;
@@ -217,7 +229,7 @@ fn check_size<E: From<SizeMismatch>>(cmsg: Cmsg<'_>, expected: usize) -> ParseRe
 /// The control message must really contain a sufficiently initialized struct with that size and alignment. No level or
 /// type check is performed.
 #[cfg(uds_ancillary_credentials)]
-unsafe fn into_fixed_size_contents<T>(mut cmsg: Cmsg<'_>) -> ParseResult<'_, &T, SizeMismatch> {
+unsafe fn into_fixed_size_contents<T, E: From<SizeMismatch>>(mut cmsg: Cmsg<'_>) -> ParseResult<'_, &T, E> {
     cmsg = check_size(cmsg, std::mem::size_of::<T>())?;
 
     Ok(unsafe {
@@ -0,0 +1,49 @@
+//! [`SecurityContext`] and associated helper types.
+use super::*;
+use std::{ffi::CStr, str};
+
+/// Ancillary data message carrying the security context (such as the SELinux label) of the process on the other end.
+///
+/// Only received, never sent: the kernel attaches it to every receive operation on a socket which has had
+/// [`set_continuous_ancillary_security()`] enabled, provided that a Linux security module that assigns such labels is
+/// active. Without one, no `SCM_SECURITY` messages are produced at all, even with the option enabled.
+///
+/// The message can freely coexist with [`Credentials`](super::credentials::Credentials) and file descriptors in the
+/// same receive operation.
+///
+/// [`set_continuous_ancillary_security()`]: crate::os::unix::udsocket::UdSocket::set_continuous_ancillary_security
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SecurityContext<'a>(&'a [u8]);
+impl<'a> SecurityContext<'a> {
+    // Not exported by the libc crate. Identical on all architectures supported by Linux.
+    pub(super) const ANCTYPE: c_int = 0x03;
+
+    /// Returns the raw payload of the message, which may or may not include a terminating nul byte depending on the
+    /// security module that produced it.
+    #[inline(always)]
+    pub const fn as_bytes(&self) -> &'a [u8] {
+        self.0
+    }
+    /// Returns the label with the terminating nul byte, if any, removed.
+    #[inline]
+    pub fn label(&self) -> &'a [u8] {
+        match CStr::from_bytes_until_nul(self.0) {
+            Ok(cs) => cs.to_bytes(),
+            Err(..) => self.0,
+        }
+    }
+    /// Returns the [label](Self::label) as a string slice, or `None` if it isn't valid UTF-8.
+    #[inline]
+    pub fn label_str(&self) -> Option<&'a str> {
+        str::from_utf8(self.label()).ok()
+    }
+}
+impl<'a> FromCmsg<'a> for SecurityContext<'a> {
+    type MalformedPayloadError = Infallible;
+
+    #[inline]
+    fn try_parse(mut cmsg: Cmsg<'a>) -> ParseResult<'a, Self, Self::MalformedPayloadError> {
+        cmsg = check_level_and_type(cmsg, Self::ANCTYPE)?;
+        Ok(Self(cmsg.data()))
+    }
+}
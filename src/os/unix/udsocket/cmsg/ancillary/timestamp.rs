@@ -0,0 +1,80 @@
+//! [`Timestamp`] and associated helper types.
+use super::*;
+use std::{
+    mem::size_of,
+    time::{Duration, SystemTime},
+};
+
+/// Ancillary data message carrying the time at which the kernel received the data, with nanosecond precision.
+///
+/// Only received, never sent: the kernel attaches it to every receive operation on a datagram or sequenced packet
+/// socket which has had [`set_receive_timestamps()`] enabled. The message coexists with file descriptors, credentials
+/// and security contexts in the same receive operation.
+///
+/// The timestamp is taken from the system's realtime clock, which is subject to adjustment, and thus should be compared
+/// with [`SystemTime::now()`] rather than with a monotonic clock.
+///
+/// [`set_receive_timestamps()`]: crate::os::unix::udsocket::UdSocket::set_receive_timestamps
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(Duration);
+impl Timestamp {
+    // SCM_TIMESTAMPNS is defined to be SO_TIMESTAMPNS, but the libc crate only exports the latter on Android.
+    pub(super) const ANCTYPE: c_int = libc::SO_TIMESTAMPNS;
+
+    /// Returns the time elapsed between the Unix epoch and the moment when the data was received.
+    #[inline(always)]
+    pub const fn since_epoch(&self) -> Duration {
+        self.0
+    }
+    /// Returns the moment when the data was received as a [`SystemTime`].
+    #[inline]
+    pub fn system_time(&self) -> SystemTime {
+        SystemTime::UNIX_EPOCH + self.0
+    }
+}
+impl<'a> FromCmsg<'a> for Timestamp {
+    type MalformedPayloadError = TimestampDecodeError;
+
+    fn try_parse(mut cmsg: Cmsg<'a>) -> ParseResult<'a, Self, Self::MalformedPayloadError> {
+        cmsg = check_level_and_type(cmsg, Self::ANCTYPE)?;
+        let (expected, got) = (size_of::<libc::timespec>(), cmsg.data().len());
+        if got != expected {
+            let e = TimestampDecodeError::SizeMismatch(SizeMismatch { expected, got });
+            return Err(ParseErrorKind::MalformedPayload(e).wrap(cmsg));
+        }
+        let ts = unsafe {
+            // SAFETY: the size has just been checked, and timespec is POD
+            cmsg.data().as_ptr().cast::<libc::timespec>().read_unaligned()
+        };
+        match (u64::try_from(ts.tv_sec), u32::try_from(ts.tv_nsec)) {
+            (Ok(secs), Ok(nanos)) if nanos < 1_000_000_000 => Ok(Self(Duration::new(secs, nanos))),
+            _ => Err(ParseErrorKind::MalformedPayload(TimestampDecodeError::OutOfRange).wrap(cmsg)),
+        }
+    }
+}
+
+/// The [`MalformedPayload`](ParseErrorKind::MalformedPayload) error type for [`Timestamp`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TimestampDecodeError {
+    /// The payload is not the size of a `timespec`.
+    SizeMismatch(SizeMismatch),
+    /// The timestamp lies before the Unix epoch, which happens if the realtime clock of the system is set that far
+    /// back, or its nanosecond part is out of range.
+    OutOfRange,
+}
+impl From<SizeMismatch> for TimestampDecodeError {
+    #[inline]
+    fn from(e: SizeMismatch) -> Self {
+        Self::SizeMismatch(e)
+    }
+}
+impl Display for TimestampDecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SizeMismatch(e) => Display::fmt(e, f),
+            Self::OutOfRange => f.write_str("timestamp is out of range"),
+        }
+    }
+}
+impl Error for TimestampDecodeError {}
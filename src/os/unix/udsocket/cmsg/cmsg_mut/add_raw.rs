@@ -22,8 +22,9 @@ fn locate_next_cmsghdr_idx(buf: &[MUu8]) -> Option<usize> {
         return None;
     }
     let base_idx = unsafe {
-        // SAFETY: CMSG_NXTHDR never returns a pointer outside the buffer if the return value is non-null
-        base.offset_from(cur)
+        // SAFETY: CMSG_NXTHDR never returns a pointer outside the buffer if the return value is non-null. The offset
+        // is measured in bytes, not in units of cmsghdr.
+        base.cast::<u8>().offset_from(cur.cast::<u8>())
     };
     debug_assert!(base_idx >= 0);
     Some(base_idx as usize)
@@ -104,8 +105,9 @@ pub(super) fn add_raw_message(buf: &mut (impl CmsgMut + ?Sized), cmsg: Cmsg<'_>)
     data_range.copy_from_slice(weaken_buf_init(cmsg.data()));
     valid_incr += data_range.len();
 
-    // Get an offset to the end of the buffer if another control message wouldn't fit.
-    let next_cmsghdr_base_offset = locate_next_cmsghdr_idx(buf.uninit_part()).unwrap_or_else(|| buf.capacity());
+    // Fall back to the end of the padded message if another control message wouldn't fit. (The buffer itself cannot be
+    // used here, since its end lies beyond that of `uninit_part()` if it's already partially filled.)
+    let next_cmsghdr_base_offset = locate_next_cmsghdr_idx(buf.uninit_part()).unwrap_or(cmsg.space_occupied());
 
     // The spacer between the end of the control message body and the next cmsghdr.
     let post_data_spacer = &mut buf.uninit_part()[end_of_data_range..next_cmsghdr_base_offset];
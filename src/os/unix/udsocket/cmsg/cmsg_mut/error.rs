@@ -3,10 +3,11 @@ use std::{
     fmt::{self, Display, Formatter},
 };
 
-/// Error type returned by [`CmsgMut::reserve()`] and its variations.
+/// Error type returned by [`CmsgMut::reserve()`](super::CmsgMut::reserve) and its variations, as well as
+/// [`CmsgMut::shrink_to()`](super::CmsgMut::shrink_to).
 #[derive(Debug)]
 pub enum ReserveError {
-    /// `reserve()` is unsupported for the buffer type.
+    /// `reserve()` or `shrink_to()` is unsupported for the buffer type.
     Unsupported,
     /// Memory allocation failed.
     Failed(Box<dyn Error>),
@@ -14,12 +15,12 @@ pub enum ReserveError {
 impl Display for ReserveError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Unsupported => f.write_str("growing the buffer is not supported"),
+            Self::Unsupported => f.write_str("resizing the buffer is not supported"),
             Self::Failed(e) => Display::fmt(e, f),
         }
     }
 }
 impl Error for ReserveError {}
 
-/// Result type returned by [`CmsgMut::reserve()`] and its variations.
+/// Result type returned by [`CmsgMut::reserve()`](super::CmsgMut::reserve) and its variations.
 pub type ReserveResult = Result<(), ReserveError>;
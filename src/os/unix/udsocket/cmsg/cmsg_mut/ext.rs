@@ -1,6 +1,6 @@
 use super::{ancillary::ToCmsg, *};
 use crate::weaken_buf_init;
-use std::{mem::MaybeUninit, slice};
+use std::{cmp::Ordering, mem::MaybeUninit, slice};
 
 /// Methods derived from the interface of [`CmsgMut`].
 ///
@@ -34,16 +34,58 @@ pub trait CmsgMutExt: CmsgMut {
     fn capacity(&self) -> usize {
         self.as_bytes().len()
     }
+    /// Returns how many bytes of the uninitialized part of the buffer can actually be filled with control messages,
+    /// i.e. the amount of space which will be offered to the kernel by the next receive operation.
+    ///
+    /// This excludes the padding necessary to align the next `cmsghdr`, and is zero if not even a `cmsghdr` would fit
+    /// in the remaining space. Note that reserving more space (which resizable buffers do automatically when control
+    /// messages are added) can increase this value.
+    #[inline]
+    fn remaining_capacity(&self) -> usize {
+        let uninit = &self.as_bytes()[self.valid_len()..];
+        align_first(uninit).map_or(0, |idx| uninit.len() - idx)
+    }
+    /// Returns `true` if not even an empty control message would fit in the [remaining capacity] of the buffer, in
+    /// which case it should be cleared or grown before the next receive operation to avoid control messages being
+    /// truncated.
+    ///
+    /// [remaining capacity]: Self::remaining_capacity
+    #[inline]
+    fn is_full(&self) -> bool {
+        self.remaining_capacity() < Cmsg::cmsg_len_for_payload_size(0)
+    }
 
     /// Immutably borrows the part of the buffer which is already filled with valid ancillary data as a [`CmsgRef`].
     ///
     /// Use this method to deserialize the contents of a `CmsgMut` used for receiving control messages from a socket.
+    ///
+    /// # Forwarding
+    /// The returned `CmsgRef` covers exactly the control messages received by the last receive operation, and can be
+    /// passed to a send operation as-is to forward them to another socket without decoding and re-encoding them.
+    ///
+    /// File descriptors received via `SCM_RIGHTS` are owned by the receiving process from the moment the receive
+    /// operation completes, and the control message only holds their numbers. Sending it makes the kernel duplicate
+    /// the descriptors behind those numbers into the message, so they must remain open until the send operation has
+    /// returned – decoding them into owned file descriptors and dropping those beforehand makes the numbers dangling,
+    /// which results in either an error or the wrong files being sent. Forwarding does not transfer ownership of the
+    /// relay's own copies either: they still have to be closed afterwards (by decoding the buffer and dropping the
+    /// file descriptors, for example), or they will be leaked.
+    ///
+    /// Other kinds of control messages may be subject to restrictions on the sending side which do not apply to
+    /// receiving. For instance, Linux only allows a process to send credentials other than its own if it is
+    /// privileged, which makes forwarding received `SCM_CREDENTIALS` messages fail.
     #[inline(always)]
     fn as_ref(&self) -> CmsgRef<'_> {
         let vp = self.valid_part();
         let align = align_first(weaken_buf_init(vp)).unwrap_or(vp.len());
         unsafe { CmsgRef::new_unchecked(&vp[align..]) }
     }
+    /// Returns an iterator over the level, type and payload of every control message in the valid part of the buffer.
+    /// A shorthand for `.as_ref().iter_raw()`; see [`CmsgRef::iter_raw()`].
+    #[inline]
+    fn iter_raw(&self) -> RawCmsgs<'_> {
+        self.as_ref().iter_raw()
+    }
 
     /// Immutably borrows the part of the buffer which is already filled with valid ancillary data as a raw slice.
     #[inline(always)]
@@ -121,6 +163,25 @@ pub trait CmsgMutExt: CmsgMut {
             self.set_len(0);
         }
     }
+    /// Clears the buffer, including its truncation flag, and changes its capacity to `new_len`, growing it via
+    /// `reserve_exact()` or shrinking it via `shrink_to()` as needed. Nothing is reallocated if the capacity is already
+    /// `new_len`.
+    ///
+    /// This allows a long-lived buffer to be adapted to the sizes of the control messages actually being received, in
+    /// particular to give memory back after a burst of unusually large ones. Growable buffers may end up with somewhat
+    /// more capacity than requested, at their discretion. If the buffer cannot be resized, it is still cleared, and the
+    /// error is returned.
+    fn clear_and_resize(&mut self, new_len: usize) -> ReserveResult {
+        self.clear();
+        self.set_truncation_flag(false);
+        match new_len.cmp(&self.capacity()) {
+            // Requesting the whole amount is enough regardless of whether the implementation counts the additional
+            // space from the end of the valid part (like `Vec`) or from the current capacity.
+            Ordering::Greater => self.reserve_exact(new_len),
+            Ordering::Less => self.shrink_to(new_len),
+            Ordering::Equal => Ok(()),
+        }
+    }
     /// Alias for `set_len(valid_len() + incr)`.
     ///
     /// # Safety
@@ -14,9 +14,9 @@ use std::mem::MaybeUninit;
 /// The following invariants must be upheld by implementations:
 /// - The slices returned by [`.as_bytes()`] and [`.as_bytes_mut()`]:
 ///     - Must be pointer-wise equivalent (point to the same base address and have the same length)
-///     - Must not change base address and legnth without a call to [`.reserve()`].
-///         - No method from the `CmsgMut` trait in a conformant implementation of it may call [`.reserve()`] indirectly
-///           (other than [`.reserve()`] itself).
+///     - Must not change base address and legnth without a call to [`.reserve()`] or [`.shrink_to()`].
+///         - No method from the `CmsgMut` trait in a conformant implementation of it may call [`.reserve()`] or
+///           [`.shrink_to()`] indirectly (other than those methods themselves).
 /// - If [`.valid_len()`] returns some value 𝑛:
 ///     - It must not return any different 𝑛 until [`.set_len()`] is called with some value 𝑚 as its argument, after
 ///       which [`.valid_len()`] must return 𝑚.
@@ -35,6 +35,7 @@ use std::mem::MaybeUninit;
 /// [`.valid_len()`]: CmsgMut::valid_len
 /// [`.set_len()`]: CmsgMut::set_len
 /// [`.reserve()`]: CmsgMut::reserve
+/// [`.shrink_to()`]: CmsgMut::shrink_to
 pub unsafe trait CmsgMut {
     /// Returns the entire buffer, including both its initialized and uninitialized parts, as a single immutable slice.
     fn as_bytes(&self) -> &[MaybeUninit<u8>];
@@ -76,6 +77,16 @@ pub unsafe trait CmsgMut {
         let _ = additional;
         Err(ReserveError::Unsupported)
     }
+    /// Attempts to reduce the underlying buffer's capacity to the given amount of bytes, or as close to it as the
+    /// buffer data structure allows, but never below [`.valid_len()`](CmsgMut::valid_len). Returns `Err` if the
+    /// capacity was left unchanged because the buffer does not support shrinking.
+    ///
+    /// Like [`.reserve()`](CmsgMut::reserve), this is allowed to change the base pointer returned by the next call to
+    /// [`.as_bytes()`](CmsgMut::as_bytes)/[`.as_bytes_mut()`](CmsgMut::as_bytes_mut).
+    fn shrink_to(&mut self, capacity: usize) -> ReserveResult {
+        let _ = capacity;
+        Err(ReserveError::Unsupported)
+    }
 }
 
 #[cfg(debug_assertions)]
@@ -0,0 +1,262 @@
+//! Collection of context for ancillary data decoding.
+//!
+//! Not all control messages can be decoded from their payload alone – on some platforms, the format of a message
+//! depends on the state of the socket at the time the message was received, such as which socket options were enabled.
+//! Such state cannot be reliably queried after the fact, since another thread might change it in the meantime. The
+//! [`Collector`] trait provides hooks which are called right before and right after the system call which sends or
+//! receives ancillary data, allowing that state to be recorded for the decoder to consult later.
+//!
+//! The prime example is `SCM_CREDS` on FreeBSD, which carries a `cmsgcred` structure filled in by the sender's kernel
+//! if the receiving socket doesn't have the `LOCAL_CREDS` option enabled, but a `sockcred` structure if it does. The
+//! two have no common header which would allow telling them apart, and the option is cleared by the kernel once the
+//! credentials are delivered, so checking it after the receive operation can't possibly work. `LocalCredsCollector`
+//! reads the option right before the operation instead, and its verdict can then be passed to
+//! `Credentials::try_parse_with_context()`. (Both are only available on FreeBSD, and thus aren't linked here.)
+//!
+//! Collectors can be composed without allocations: tuples of up to three collectors call their members from left to
+//! right, and [`IterCollector`] does the same for homogeneous collections.
+
+#[cfg(uds_sockcred2)]
+use crate::os::unix::udsocket::c_wrappers;
+use crate::os::unix::unixprelude::*;
+use std::{
+    cell::Cell,
+    fmt::{self, Debug, Formatter},
+};
+
+/// A "hook" for collection of ancillary data context around a `recvmsg`/`sendmsg` call.
+///
+/// Both methods do nothing by default.
+pub trait Collector {
+    /// Called right before the system call is performed on `socket`.
+    #[inline(always)]
+    #[allow(unused_variables)]
+    fn pre_op_collect(&mut self, socket: BorrowedFd<'_>) {}
+    /// Called right after the system call is performed on `socket`, provided that it succeeded. `msghdr_flags` is the
+    /// `msg_flags` field of the `msghdr` structure after the call, which is always zero for `sendmsg`.
+    #[inline(always)]
+    #[allow(unused_variables)]
+    fn post_op_collect(&mut self, socket: BorrowedFd<'_>, msghdr_flags: c_int) {}
+}
+impl<T: Collector + ?Sized> Collector for &mut T {
+    #[inline(always)]
+    fn pre_op_collect(&mut self, socket: BorrowedFd<'_>) {
+        (**self).pre_op_collect(socket)
+    }
+    #[inline(always)]
+    fn post_op_collect(&mut self, socket: BorrowedFd<'_>, msghdr_flags: c_int) {
+        (**self).post_op_collect(socket, msghdr_flags)
+    }
+}
+impl<T: Collector + ?Sized> Collector for Box<T> {
+    #[inline(always)]
+    fn pre_op_collect(&mut self, socket: BorrowedFd<'_>) {
+        (**self).pre_op_collect(socket)
+    }
+    #[inline(always)]
+    fn post_op_collect(&mut self, socket: BorrowedFd<'_>, msghdr_flags: c_int) {
+        (**self).post_op_collect(socket, msghdr_flags)
+    }
+}
+
+/// A collector which doesn't collect anything. Used by operations which don't take a collector.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DummyCollector;
+impl Collector for DummyCollector {}
+
+/// A collector which calls the given closures.
+pub struct FnCollector<Pre, Post> {
+    pre: Pre,
+    post: Post,
+}
+impl<Pre: FnMut(BorrowedFd<'_>), Post: FnMut(BorrowedFd<'_>, c_int)> FnCollector<Pre, Post> {
+    /// Creates a collector which calls `pre` before the operation and `post` after it.
+    #[inline]
+    pub fn new(pre: Pre, post: Post) -> Self {
+        Self { pre, post }
+    }
+}
+impl<Pre: FnMut(BorrowedFd<'_>)> FnCollector<Pre, fn(BorrowedFd<'_>, c_int)> {
+    /// Creates a collector which only calls the given closure before the operation.
+    #[inline]
+    pub fn before(pre: Pre) -> Self {
+        Self { pre, post: |_, _| {} }
+    }
+}
+impl<Post: FnMut(BorrowedFd<'_>, c_int)> FnCollector<fn(BorrowedFd<'_>), Post> {
+    /// Creates a collector which only calls the given closure after the operation.
+    #[inline]
+    pub fn after(post: Post) -> Self {
+        Self { pre: |_| {}, post }
+    }
+}
+impl<Pre: FnMut(BorrowedFd<'_>), Post: FnMut(BorrowedFd<'_>, c_int)> Collector for FnCollector<Pre, Post> {
+    #[inline]
+    fn pre_op_collect(&mut self, socket: BorrowedFd<'_>) {
+        (self.pre)(socket)
+    }
+    #[inline]
+    fn post_op_collect(&mut self, socket: BorrowedFd<'_>, msghdr_flags: c_int) {
+        (self.post)(socket, msghdr_flags)
+    }
+}
+impl<Pre, Post> Debug for FnCollector<Pre, Post> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnCollector").finish_non_exhaustive()
+    }
+}
+
+/// A collector which records the `msg_flags` observed after the last successful operation.
+///
+/// The flags are stored in a [`Cell`], which means that the collector can be passed by shared reference, leaving the
+/// caller free to inspect it afterwards, e.g. to check for `MSG_CTRUNC`:
+/// ```no_run
+/// use interprocess::os::unix::udsocket::{
+///     cmsg::{context::FlagsCollector, CmsgVecBuf},
+///     UdStream,
+/// };
+/// use std::io::IoSliceMut;
+///
+/// let conn = UdStream::connect("/tmp/example.sock")?;
+/// let (mut buf, mut abuf) = ([0; 64], CmsgVecBuf::new(64));
+/// let flags = FlagsCollector::new();
+/// conn.recv_ancillary_vectored_with_collector(&mut [IoSliceMut::new(&mut buf)], &mut abuf, &flags)?;
+/// if flags.last_flags().map_or(false, |f| f & libc::MSG_CTRUNC != 0) {
+///     eprintln!("ancillary data was truncated");
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FlagsCollector(Cell<Option<c_int>>);
+impl FlagsCollector {
+    /// Creates a collector which hasn't observed any flags yet.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(Cell::new(None))
+    }
+    /// Returns the flags observed after the last successful operation, or `None` if there hasn't been one.
+    #[inline]
+    pub fn last_flags(&self) -> Option<c_int> {
+        self.0.get()
+    }
+}
+impl Collector for FlagsCollector {
+    #[inline]
+    fn post_op_collect(&mut self, _: BorrowedFd<'_>, msghdr_flags: c_int) {
+        self.0.set(Some(msghdr_flags));
+    }
+}
+impl Collector for &FlagsCollector {
+    #[inline]
+    fn post_op_collect(&mut self, _: BorrowedFd<'_>, msghdr_flags: c_int) {
+        self.0.set(Some(msghdr_flags));
+    }
+}
+
+/// The format of an `SCM_CREDS` control message, as decided by [`LocalCredsCollector`].
+#[cfg_attr(feature = "doc_cfg", doc(cfg(target_os = "freebsd")))]
+#[cfg(uds_sockcred2)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ScmCredsFormat {
+    /// `cmsgcred`, sent when the receiving socket does not have `LOCAL_CREDS` enabled. This is the structure that the
+    /// sender explicitly attaches to the message.
+    Cmsgcred,
+    /// `sockcred`, sent when the receiving socket has `LOCAL_CREDS` enabled.
+    Sockcred,
+}
+
+/// A collector which reads the `LOCAL_CREDS` socket option before the operation, deciding which structure an
+/// `SCM_CREDS` control message received by it carries.
+///
+/// The option is only read once per operation on a given socket, even if the collector ends up being called several
+/// times (by being a member of multiple composite collectors, for instance). The last decision remains available via
+/// [`.scm_creds_format()`](Self::scm_creds_format) after the operation completes.
+///
+/// # System calls
+/// - `getsockopt` (`LOCAL_CREDS`), in `pre_op_collect`
+#[cfg_attr(feature = "doc_cfg", doc(cfg(target_os = "freebsd")))]
+#[cfg(uds_sockcred2)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct LocalCredsCollector {
+    /// The socket for which the option has been read during the ongoing operation, if any.
+    cur_fd: Option<c_int>,
+    local_creds: Option<bool>,
+}
+#[cfg(uds_sockcred2)]
+impl LocalCredsCollector {
+    /// Creates a collector which hasn't read the option yet.
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            cur_fd: None,
+            local_creds: None,
+        }
+    }
+    /// Returns the format of `SCM_CREDS` messages received by the last operation, or `None` if the option couldn't be
+    /// read or there hasn't been an operation yet.
+    #[inline]
+    pub fn scm_creds_format(&self) -> Option<ScmCredsFormat> {
+        self.local_creds.map(|lc| {
+            if lc {
+                ScmCredsFormat::Sockcred
+            } else {
+                ScmCredsFormat::Cmsgcred
+            }
+        })
+    }
+}
+#[cfg(uds_sockcred2)]
+impl Collector for LocalCredsCollector {
+    fn pre_op_collect(&mut self, socket: BorrowedFd<'_>) {
+        let fd = socket.as_raw_fd();
+        if self.cur_fd == Some(fd) {
+            return;
+        }
+        self.cur_fd = Some(fd);
+        self.local_creds = c_wrappers::get_local_creds(socket).ok();
+    }
+    #[inline]
+    fn post_op_collect(&mut self, _: BorrowedFd<'_>, _: c_int) {
+        self.cur_fd = None;
+    }
+}
+
+/// A collector which calls every collector in a collection, in iteration order.
+///
+/// Works with any collection which can be mutably iterated over, such as `Vec<C>`, `[C; N]` and `&mut [C]`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct IterCollector<I>(pub I);
+impl<I> Collector for IterCollector<I>
+where
+    for<'a> &'a mut I: IntoIterator,
+    for<'a> <&'a mut I as IntoIterator>::Item: Collector,
+{
+    fn pre_op_collect(&mut self, socket: BorrowedFd<'_>) {
+        for mut c in &mut self.0 {
+            c.pre_op_collect(socket);
+        }
+    }
+    fn post_op_collect(&mut self, socket: BorrowedFd<'_>, msghdr_flags: c_int) {
+        for mut c in &mut self.0 {
+            c.post_op_collect(socket, msghdr_flags);
+        }
+    }
+}
+
+macro_rules! tuple_collector {
+    ($($nm:ident $idx:tt),+) => {
+        /// Calls the members' hooks in order, from left to right.
+        impl<$($nm: Collector),+> Collector for ($($nm,)+) {
+            #[inline]
+            fn pre_op_collect(&mut self, socket: BorrowedFd<'_>) {
+                $(self.$idx.pre_op_collect(socket);)+
+            }
+            #[inline]
+            fn post_op_collect(&mut self, socket: BorrowedFd<'_>, msghdr_flags: c_int) {
+                $(self.$idx.post_op_collect(socket, msghdr_flags);)+
+            }
+        }
+    };
+}
+tuple_collector!(A 0, B 1);
+tuple_collector!(A 0, B 1, C 2);
@@ -43,6 +43,7 @@
 // TODO parser
 
 pub mod ancillary;
+pub mod context;
 
 pub(super) mod cmsg_mut;
 mod mref;
@@ -93,6 +94,10 @@ impl<'a> Cmsg<'a> {
     }
     /// Returns the `cmsg_len` of a control message with a payload of the given size.
     ///
+    /// This is the length of one message as recorded in its header, which does not include the padding that separates
+    /// it from the next one. **Do not use it to size buffers** – use
+    /// [`cmsg_space_for_payload_size()`](Self::cmsg_space_for_payload_size) instead.
+    ///
     /// The type of the return value is platform-independent, but values will never overflow the actual type used in
     /// `cmsghdr` to store `cmsg_len`. The function simply panics if an offending size is encountered.
     ///
@@ -106,6 +111,17 @@ impl<'a> Cmsg<'a> {
         }
         len as usize
     }
+    /// Returns the amount of space a control message with a payload of the given size occupies in a control message
+    /// buffer, including the padding which aligns the message that follows it (`CMSG_SPACE`).
+    ///
+    /// This is the value to use when allocating a buffer for receiving ancillary data: a buffer which can hold `N`
+    /// messages must be at least as large as the sum of their `CMSG_SPACE` values. Summing
+    /// [`cmsg_len_for_payload_size()`](Self::cmsg_len_for_payload_size) values instead leaves out the padding, which
+    /// makes the kernel truncate the last message without any error being returned, save for the truncation flag.
+    #[inline(always)]
+    pub const fn cmsg_space_for_payload_size(payload_size: c_uint) -> usize {
+        unsafe { libc::CMSG_SPACE(payload_size) as usize }
+    }
     /// Returns the `cmsg_len` of the control message – an alias for
     /// `Self::cmsg_len_for_payload_size(self.data.len())`.
     ///
@@ -137,7 +153,7 @@ impl<'a> Cmsg<'a> {
     /// and all necessary padding.
     #[inline(always)]
     pub const fn space_occupied(&self) -> usize {
-        unsafe { libc::CMSG_SPACE(self.data.len() as c_uint) as usize }
+        Self::cmsg_space_for_payload_size(self.data.len() as c_uint)
     }
     /// Clones the control message. No special treatment of the contained data is performed, and the struct is simply
     /// copied bitwise, with the data slice pointing to the same memory.
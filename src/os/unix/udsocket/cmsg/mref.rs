@@ -1,14 +1,18 @@
 use super::{
     super::util::{to_msghdr_controllen, DUMMY_MSGHDR},
-    ancillary::{FromCmsg, ParseError},
+    ancillary::{ControlMessage, FromCmsg, ParseError},
     *,
 };
 use libc::{c_void, cmsghdr};
 use std::{
     cmp::min,
+    error::Error,
+    fmt::{self, Display, Formatter},
     io,
     iter::FusedIterator,
     marker::PhantomData,
+    mem::{align_of, size_of},
+    ptr,
     slice::{self, SliceIndex},
 };
 
@@ -42,6 +46,73 @@ impl<'buf> CmsgRef<'buf> {
     pub fn inner(&self) -> &[u8] {
         self.0
     }
+    /// Returns the raw bytes of the control messages, with the lifetime of the buffer rather than that of the
+    /// `CmsgRef`.
+    ///
+    /// The bytes can be copied (into a suitably aligned location) and turned back into a `CmsgRef` with
+    /// [`new_unchecked()`](Self::new_unchecked), e.g. to relay ancillary data between sockets without decoding it.
+    /// Keep in mind that file descriptors received from the kernel are owned by the receiving process: relaying them
+    /// this way sends new references to the same files, and the received ones still have to be closed, e.g. by
+    /// decoding the buffer afterwards.
+    #[inline(always)]
+    pub const fn as_bytes(&self) -> &'buf [u8] {
+        self.0
+    }
+    /// Returns the size of the buffer in bytes, which is what gets passed to the kernel as `msg_controllen` when the
+    /// buffer is sent.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+    /// Checks whether the buffer is empty, i.e. contains no control messages.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Checks the structure of the buffer, making sure that every `cmsghdr` in it is aligned and that its `cmsg_len`
+    /// is large enough to cover the header itself without reaching past the end of the buffer.
+    ///
+    /// This is primarily useful for buffers obtained through FFI, as those received from the kernel by this crate are
+    /// always well-formed. Once the check passes, [`cmsgs()`](Self::cmsgs) and [`decode()`](Self::decode) are
+    /// guaranteed to never step outside of the buffer. **The payloads are not inspected in any way** – their validity
+    /// is still up to whoever created the `CmsgRef`.
+    ///
+    /// Runs in linear time over the amount of control messages and doesn't allocate.
+    pub fn validate(&self) -> Result<(), CmsgError> {
+        let buf = self.0;
+        // Buffers too short to hold a header are empty as far as CMSG_FIRSTHDR is concerned, whatever their address.
+        if buf.len() >= size_of::<cmsghdr>() && buf.as_ptr() as usize % align_of::<cmsghdr>() != 0 {
+            return Err(CmsgErrorKind::Misaligned.at(0));
+        }
+        let hdr_len = Cmsg::cmsg_len_for_payload_size(0);
+
+        let mut offset = 0;
+        // Mirrors CMSG_NXTHDR, which treats trailing bytes that can't hold a whole cmsghdr as padding.
+        while buf.len() - offset >= size_of::<cmsghdr>() {
+            let cmsghdr = unsafe {
+                // SAFETY: bounds were checked by the loop condition
+                ptr::read_unaligned(buf.as_ptr().add(offset).cast::<cmsghdr>())
+            };
+            #[allow(clippy::unnecessary_cast)]
+            let cmsg_len = cmsghdr.cmsg_len as usize;
+            if cmsg_len < hdr_len {
+                return Err(CmsgErrorKind::TooShort(cmsg_len).at(offset));
+            }
+            let remaining = buf.len() - offset;
+            let payload_len =
+                c_uint::try_from(cmsg_len - hdr_len).map_err(|_| CmsgErrorKind::OutOfBounds(cmsg_len).at(offset))?;
+            if cmsg_len > remaining {
+                return Err(CmsgErrorKind::OutOfBounds(cmsg_len).at(offset));
+            }
+            let space = Cmsg::cmsg_space_for_payload_size(payload_len);
+            if space >= remaining {
+                break;
+            }
+            offset += space;
+        }
+        Ok(())
+    }
 
     /// Subslices the buffer to the given range. Inclusive and exclusive, closed, half-open and open ranges may be used
     /// here, as if you were slicing the `[u8]` directly.
@@ -101,6 +172,17 @@ impl<'buf> CmsgRef<'buf> {
     pub fn cmsgs(&self) -> Cmsgs<'buf> {
         Cmsgs::new(*self)
     }
+    /// Returns an iterator over the level, type and payload of every control message in the buffer, without any
+    /// interpretation of the messages by the crate.
+    ///
+    /// This is the escape hatch for inspecting control messages of types which the [`ancillary`](super::ancillary)
+    /// module does not model, such as `SCM_TIMESTAMP`. Since nothing is decoded, file descriptors received in
+    /// `SCM_RIGHTS` messages are not taken ownership of – the buffer still has to be decoded afterwards for them to be
+    /// closed.
+    #[inline]
+    pub fn iter_raw(&self) -> RawCmsgs<'buf> {
+        RawCmsgs(self.cmsgs())
+    }
     /// Returns an iterator that wraps [`cmsgs()`](Self::cmsgs) and decodes them into the ancillary type of your
     /// choosing. (A handy choice is [`Ancillary`](super::ancillary::Ancillary).)
     #[inline]
@@ -110,6 +192,15 @@ impl<'buf> CmsgRef<'buf> {
             _phantom: PhantomData,
         }
     }
+    /// Returns an iterator that decodes every control message in the buffer into a [`ControlMessage`], in the order in
+    /// which they appear in the buffer (i.e. the order in which they were sent).
+    ///
+    /// Control messages of unknown types are yielded undecoded rather than as errors, which makes this a convenient way
+    /// to handle mixed ancillary data, such as file descriptors accompanied by credentials, in a single pass.
+    #[inline]
+    pub fn decode_all(&self) -> DecodeAll<'buf> {
+        DecodeAll(self.cmsgs())
+    }
 
     pub(crate) fn fill_msghdr(&self, hdr: &mut msghdr) -> io::Result<()> {
         hdr.msg_control = self.0.as_ptr().cast::<c_void>().cast_mut();
@@ -124,6 +215,49 @@ impl Default for CmsgRef<'_> {
     }
 }
 
+/// Error type returned by [`CmsgRef::validate()`], describing the first malformed control message header.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CmsgError {
+    /// Offset of the offending `cmsghdr` from the beginning of the buffer, in bytes.
+    pub offset: usize,
+    /// What's wrong with the header.
+    pub kind: CmsgErrorKind,
+}
+impl Display for CmsgError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.kind, self.offset)
+    }
+}
+impl Error for CmsgError {}
+
+/// The specific error kind contained in [`CmsgError`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CmsgErrorKind {
+    /// The buffer does not start at an address suitably aligned for `cmsghdr`.
+    Misaligned,
+    /// The `cmsg_len` of the header, given here, is smaller than the header itself.
+    TooShort(usize),
+    /// The `cmsg_len` of the header, given here, reaches past the end of the buffer.
+    OutOfBounds(usize),
+}
+impl CmsgErrorKind {
+    #[inline]
+    fn at(self, offset: usize) -> CmsgError {
+        CmsgError { offset, kind: self }
+    }
+}
+impl Display for CmsgErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Misaligned => f.write_str("misaligned control message buffer"),
+            Self::TooShort(len) => write!(f, "cmsg_len of {len} is too short to hold the header"),
+            Self::OutOfBounds(len) => write!(f, "cmsg_len of {len} reaches past the end of the buffer"),
+        }
+    }
+}
+
 /// Iterator over the control messages in a [`CmsgRef`].
 ///
 /// Created by the [`cmsgs()`](CmsgRef::cmsgs) method.
@@ -207,12 +341,42 @@ impl<'buf> Iterator for Cmsgs<'buf> {
     }
 }
 impl<'buf> ExactSizeIterator for Cmsgs<'buf> {
+    /// Walks the rest of the buffer to count the remaining control messages.
     fn len(&self) -> usize {
-        todo!()
+        Self {
+            buf: self.buf,
+            cur: self.cur,
+            dummy: self.dummy,
+        }
+        .fold(0, |n, _| n + 1)
     }
 }
 impl FusedIterator for Cmsgs<'_> {}
 
+/// Iterator over the level, type and payload of the control messages in a [`CmsgRef`].
+///
+/// Created by the [`iter_raw()`](CmsgRef::iter_raw) method.
+pub struct RawCmsgs<'buf>(Cmsgs<'buf>);
+impl<'buf> Iterator for RawCmsgs<'buf> {
+    type Item = (c_int, c_int, &'buf [u8]);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|c| (c.cmsg_level(), c.cmsg_type(), c.data()))
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+impl ExactSizeIterator for RawCmsgs<'_> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+impl FusedIterator for RawCmsgs<'_> {}
+
 /// Iterator that zero-copy deserializes control messages from a [`CmsgRef`].
 ///
 /// Created by the [`decode()`](CmsgRef::decode) method.
@@ -238,3 +402,16 @@ impl<'buf, A: FromCmsg<'buf>> ExactSizeIterator for Decode<'buf, A> {
     }
 }
 impl<'buf, A: FromCmsg<'buf>> FusedIterator for Decode<'buf, A> {}
+
+/// Iterator that decodes all control messages from a [`CmsgRef`] into [`ControlMessage`]s, preserving their order.
+///
+/// Created by the [`decode_all()`](CmsgRef::decode_all) method.
+pub struct DecodeAll<'buf>(Cmsgs<'buf>);
+impl<'buf> Iterator for DecodeAll<'buf> {
+    type Item = ControlMessage<'buf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(ControlMessage::from)
+    }
+}
+impl FusedIterator for DecodeAll<'_> {}
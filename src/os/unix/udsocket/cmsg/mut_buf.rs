@@ -2,6 +2,11 @@ use super::*;
 use std::mem::MaybeUninit;
 
 /// A mutable reference to a control message buffer that allows for insertion of ancillary data messages.
+///
+/// The backing storage is never zeroed: it starts out entirely invalid, and only the part written to by
+/// [`add_message()`](super::CmsgMutExt::add_message) or by the kernel during a receive operation is considered valid
+/// and exposed via [`CmsgRef`]. This makes it possible to reuse one large uninitialized buffer, such as an array of
+/// [`MaybeUninit::uninit()`], for every receive operation without paying for initializing it each time.
 #[derive(Debug)]
 pub struct CmsgMutBuf<'buf> {
     buf: &'buf mut [MaybeUninit<u8>],
@@ -9,7 +14,7 @@ pub struct CmsgMutBuf<'buf> {
     trunc: bool,
 }
 impl<'buf> CmsgMutBuf<'buf> {
-    /// Creates a control message buffer from the given uninitialized slice.
+    /// Creates a control message buffer from the given uninitialized slice, with a valid length of zero.
     ///
     /// # Panics
     /// The buffer's length must not overflow `isize`.
@@ -2,6 +2,12 @@ use super::*;
 use std::{collections::TryReserveError, mem::MaybeUninit, slice};
 
 /// A **c**ontrol **m**e**s**sa**g**e buffer, used to store the encoded form of ancillary data.
+///
+/// Cloning copies the control messages in the buffer along with its truncation flag, which can be used to keep
+/// received ancillary data around while the original buffer is cleared and reused for the next receive operation. The
+/// clone is only as large as the messages it holds – its capacity is not inherited. **File descriptors are never
+/// duplicated by cloning**: received descriptors must only be decoded into owned ones from either the original or the
+/// clone, as they would otherwise be closed twice.
 #[derive(Clone, Debug, Default)]
 pub struct CmsgVecBuf {
     buf: Vec<u8>,
@@ -14,6 +20,11 @@ impl CmsgVecBuf {
     pub fn new(capacity: usize) -> Self {
         Self::from_buf(Vec::with_capacity(capacity))
     }
+    /// Same as [`new()`](Self::new). Provided for parity with the standard library's collections.
+    #[inline(always)]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::new(capacity)
+    }
     /// Converts a `Vec<u8>` to a `CmsgBuffer`, discarding all its data in the process.
     #[inline]
     pub fn from_buf(mut buf: Vec<u8>) -> Self {
@@ -28,6 +39,28 @@ impl CmsgVecBuf {
     pub unsafe fn from_buf_unchecked(buf: Vec<u8>) -> Self {
         Self { buf, trunc: false }
     }
+    /// Returns the total amount of bytes the buffer can hold without reallocating, including the part that's already
+    /// filled with control messages.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+    /// Grows the buffer such that it can hold at least `additional` more bytes of ancillary data on top of what it
+    /// already contains. Does nothing if the capacity is already sufficient.
+    ///
+    /// Control messages already in the buffer are kept intact, so this can be called both on a cleared buffer before a
+    /// receive operation and between operations which accumulate messages. With the buffer being reused, this allows
+    /// it to adapt to the largest batch of control messages seen so far instead of being reallocated every time.
+    ///
+    /// Unlike [`CmsgMut::reserve()`], this follows the conventions of [`Vec::reserve()`] and thus doesn't return an
+    /// error.
+    ///
+    /// # Panics
+    /// If the new capacity overflows `isize::MAX` bytes. Allocation failure aborts the process.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.buf.reserve(additional)
+    }
 }
 
 unsafe impl CmsgMut for CmsgVecBuf {
@@ -55,6 +88,11 @@ unsafe impl CmsgMut for CmsgVecBuf {
     fn reserve_exact(&mut self, additional: usize) -> ReserveResult {
         self.buf.try_reserve_exact(additional).map_err(mkerr)
     }
+    #[inline]
+    fn shrink_to(&mut self, capacity: usize) -> ReserveResult {
+        self.buf.shrink_to(capacity);
+        Ok(())
+    }
     fn is_truncated(&self) -> bool {
         self.trunc
     }
@@ -6,13 +6,22 @@
 use crate::os::unix::unixprelude::*;
 #[cfg(uds_cmsgcred)]
 use libc::cmsgcred;
-#[cfg(uds_sockcred2)]
-use libc::sockcred2;
+#[cfg(uds_sockpeercred)]
+use libc::sockpeercred;
 #[cfg(uds_ucred)]
 use libc::ucred;
+#[cfg(uds_unpcbid)]
+use libc::unpcbid;
 #[cfg(uds_xucred)]
 use libc::xucred;
-use std::{iter::FusedIterator, marker::PhantomData, mem::size_of};
+#[cfg(uds_sockcred2)]
+use libc::{sockcred, sockcred2};
+use std::{
+    borrow::Cow,
+    iter::FusedIterator,
+    marker::PhantomData,
+    mem::{align_of, size_of},
+};
 #[allow(unused_imports)]
 use {
     std::{cmp::min, ptr::addr_of},
@@ -34,7 +43,31 @@ use {
 /// The latter option is primarily useful with datagram sockets, which are connectionless.
 /// After one of those those types of options is enabled, either every receive operation that provides an ancillary data
 /// buffer, or just the next one, will receive an instance of this message.
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+///
+/// # Available fields
+/// Which of the IDs are present depends on the structure that the table was created from, which in turn depends on the
+/// platform and on how the table was obtained:
+///
+/// | Structure | Platforms | [`euid`] | [`ruid`] | [`egid`] | [`rgid`] | [`pid`] |
+/// |-|-|-|-|-|-|-|
+/// | `ucred` | Linux | ✓ | | ✓ | | ✓ |
+/// | `cmsgcred` | FreeBSD, DragonFly BSD | ✓ | ✓ | | ✓ | ✓ |
+/// | `sockcred` | FreeBSD (`LOCAL_CREDS`) | ✓ | ✓ | ✓ | ✓ | |
+/// | `sockcred2` | FreeBSD (`LOCAL_CREDS_PERSISTENT`) | ✓ | ✓ | ✓ | ✓ | ✓ |
+/// | `xucred` | FreeBSD, DragonFly BSD, Apple (peer credentials) | ✓ | | ✓ | | |
+/// | `unpcbid` | NetBSD (peer credentials) | ✓ | | ✓ | | ✓ |
+/// | `sockpeercred` | OpenBSD (peer credentials) | ✓ | | ✓ | | ✓ |
+///
+/// [`euid`]: Self::euid
+/// [`ruid`]: Self::ruid
+/// [`egid`]: Self::egid
+/// [`rgid`]: Self::rgid
+/// [`pid`]: Self::pid
+///
+/// # Equality
+/// Two tables are equal if they store the same IDs and supplementary groups, regardless of which structure each of
+/// them was created from. An ID that is present in one of them and absent from the other makes them unequal.
+#[derive(Copy, Clone, Debug)]
 pub struct Credentials<'a>(pub(super) CredentialsInner<'a>);
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub(super) enum CredentialsInner<'a> {
@@ -46,8 +79,14 @@ pub(super) enum CredentialsInner<'a> {
     Cmsgcred(&'a cmsgcred_packed),
     #[cfg(uds_sockcred2)]
     Sockcred2(&'a sockcred2_packed),
+    #[cfg(uds_sockcred2)]
+    Sockcred(&'a sockcred_packed),
     #[cfg(uds_xucred)]
     Xucred(xucred, PhantomData<&'a xucred>),
+    #[cfg(uds_unpcbid)]
+    Unpcbid(unpcbid, PhantomData<&'a unpcbid>),
+    #[cfg(uds_sockpeercred)]
+    Sockpeercred(sockpeercred, PhantomData<&'a sockpeercred>),
 }
 /// Methods that read the received/stored credentials.
 impl<'a> Credentials<'a> {
@@ -69,8 +108,14 @@ impl<'a> Credentials<'a> {
             CredentialsInner::Cmsgcred(c) => Some(c.cmcred_euid),
             #[cfg(uds_sockcred2)]
             CredentialsInner::Sockcred2(c) => Some(c.sc_euid),
+            #[cfg(uds_sockcred2)]
+            CredentialsInner::Sockcred(c) => Some(c.sc_euid),
             #[cfg(uds_xucred)]
             CredentialsInner::Xucred(c, _) => Some(c.cr_uid),
+            #[cfg(uds_unpcbid)]
+            CredentialsInner::Unpcbid(c, _) => Some(c.unp_euid),
+            #[cfg(uds_sockpeercred)]
+            CredentialsInner::Sockpeercred(c, _) => Some(c.uid),
         }
     }
     /// Returns the **real** user ID stored in the credentials table, or `None` if no such information is available.
@@ -88,8 +133,14 @@ impl<'a> Credentials<'a> {
             CredentialsInner::Cmsgcred(c) => Some(c.cmcred_uid),
             #[cfg(uds_sockcred2)]
             CredentialsInner::Sockcred2(c) => Some(c.sc_uid),
+            #[cfg(uds_sockcred2)]
+            CredentialsInner::Sockcred(c) => Some(c.sc_uid),
             #[cfg(uds_xucred)]
             CredentialsInner::Xucred(..) => None,
+            #[cfg(uds_unpcbid)]
+            CredentialsInner::Unpcbid(..) => None,
+            #[cfg(uds_sockpeercred)]
+            CredentialsInner::Sockpeercred(..) => None,
         }
     }
     /// Returns the **closest thing to the real user ID** among what's stored in the credentials table. If a real UID is
@@ -110,6 +161,10 @@ impl<'a> Credentials<'a> {
     /// ## `ucred` (Linux)
     /// Will always return the GID from `ucred` despite the Linux kernel allowing either the effective or the real GID
     /// to be sent.
+    ///
+    /// ## `xucred` (FreeBSD, DragonFly BSD, Apple)
+    /// The effective GID is the first entry of the group list, which is therefore also yielded by
+    /// [`.groups()`](Self::groups).
     #[inline]
     pub fn egid(&self) -> Option<gid_t> {
         match self.0 {
@@ -121,8 +176,14 @@ impl<'a> Credentials<'a> {
             CredentialsInner::Cmsgcred(..) => None,
             #[cfg(uds_sockcred2)]
             CredentialsInner::Sockcred2(c) => Some(c.sc_egid),
+            #[cfg(uds_sockcred2)]
+            CredentialsInner::Sockcred(c) => Some(c.sc_egid),
             #[cfg(uds_xucred)]
-            CredentialsInner::Xucred(..) => None,
+            CredentialsInner::Xucred(c, _) => (c.cr_ngroups > 0).then_some(c.cr_groups[0]),
+            #[cfg(uds_unpcbid)]
+            CredentialsInner::Unpcbid(c, _) => Some(c.unp_egid),
+            #[cfg(uds_sockpeercred)]
+            CredentialsInner::Sockpeercred(c, _) => Some(c.gid),
         }
     }
     /// Returns the **real** group ID stored in the credentials table, or `None` if no such information is available.
@@ -140,8 +201,14 @@ impl<'a> Credentials<'a> {
             CredentialsInner::Cmsgcred(c) => Some(c.cmcred_gid),
             #[cfg(uds_sockcred2)]
             CredentialsInner::Sockcred2(c) => Some(c.sc_gid),
+            #[cfg(uds_sockcred2)]
+            CredentialsInner::Sockcred(c) => Some(c.sc_gid),
             #[cfg(uds_xucred)]
             CredentialsInner::Xucred(..) => None,
+            #[cfg(uds_unpcbid)]
+            CredentialsInner::Unpcbid(..) => None,
+            #[cfg(uds_sockpeercred)]
+            CredentialsInner::Sockpeercred(..) => None,
         }
     }
     /// Returns the **closest thing to the real group ID** among what's stored in the credentials table. If a real GID
@@ -162,6 +229,9 @@ impl<'a> Credentials<'a> {
     /// Privileged processes (those with `CAP_SYS_ADMIN`) may send any PID, as long as it belongs to an existing
     /// process. Note that actually relying on the kernel's check for PID validity is a possible [TOCTOU] bug.
     ///
+    /// ## `sockcred` (FreeBSD)
+    /// Always `None`, since the structure received with `LOCAL_CREDS` enabled has no PID field.
+    ///
     /// [TOCTOU]: https://en.wikipedia.org/wiki/Time-of-check_to_time-of-use
     #[inline]
     pub fn pid(&self) -> Option<pid_t> {
@@ -174,8 +244,14 @@ impl<'a> Credentials<'a> {
             CredentialsInner::Cmsgcred(c) => Some(c.cmcred_pid),
             #[cfg(uds_sockcred2)]
             CredentialsInner::Sockcred2(c) => Some(c.sc_pid),
+            #[cfg(uds_sockcred2)]
+            CredentialsInner::Sockcred(..) => None,
             #[cfg(uds_xucred)]
             CredentialsInner::Xucred(..) => None, // TODO available on FreeBSD, but extremely scuffed
+            #[cfg(uds_unpcbid)]
+            CredentialsInner::Unpcbid(c, _) => Some(c.unp_pid),
+            #[cfg(uds_sockpeercred)]
+            CredentialsInner::Sockpeercred(c, _) => Some(c.pid),
         }
     }
     /// Returns an iterator over the supplementary groups in the credentials table.
@@ -187,7 +263,7 @@ impl<'a> Credentials<'a> {
     /// ## `ucred` (Linux)
     /// Always empty.
     #[inline]
-    pub fn groups(&self) -> Groups<'a> {
+    pub fn groups(&self) -> Groups<'_> {
         let cur = self.ptr_to_gids();
         let end = unsafe {
             // SAFETY: this puts us one byte past the last one
@@ -199,6 +275,54 @@ impl<'a> Credentials<'a> {
             _phantom: PhantomData,
         }
     }
+    /// Returns the supplementary groups in the credentials table as a slice.
+    ///
+    /// This is the same list that [`.groups()`](Self::groups) iterates over, including the variable-length one sent in
+    /// `sockcred` and `sockcred2` on FreeBSD.
+    ///
+    /// The slice borrows from the control message if its payload is aligned for `gid_t`, which is always the case for
+    /// control messages delivered by the kernel. A misaligned group list, as may be found in a control message
+    /// constructed by hand, is copied out instead.
+    ///
+    /// # Platform-specific behavior
+    /// ## `ucred` (Linux)
+    /// Always empty.
+    pub fn supplementary_groups(&self) -> Cow<'_, [gid_t]> {
+        let n_groups = self.n_groups();
+        if n_groups == 0 {
+            return Cow::Borrowed(&[]);
+        }
+        let ptr = self.ptr_to_gids().cast::<gid_t>();
+        if ptr as usize % align_of::<gid_t>() != 0 {
+            return Cow::Owned(self.groups().collect());
+        }
+        Cow::Borrowed(unsafe {
+            // SAFETY: the decoder has checked that the table is followed by `n_groups` group IDs, the alignment
+            // requirement has just been checked, and the borrow of `self` covers both the received control message and
+            // the by-value structures
+            std::slice::from_raw_parts(ptr, n_groups)
+        })
+    }
+    /// Checks whether the credentials table describes the calling process, as is the case when the peer of a socket is
+    /// the same process or has the same identity, comparing every ID that is present in the table against the
+    /// corresponding one of the current process. IDs that the platform did not supply are skipped, as are supplementary
+    /// groups.
+    ///
+    /// This encapsulates the common "is the peer me?" authorization check. Note that another process running as the
+    /// same user passes the check if the table doesn't include a PID.
+    pub fn matches_current_process(&self) -> bool {
+        fn ck<T: PartialEq>(val: Option<T>, cur: impl FnOnce() -> T) -> bool {
+            val.map_or(true, |v| v == cur())
+        }
+        unsafe {
+            // SAFETY: none of those can fail
+            ck(self.euid(), || libc::geteuid())
+                && ck(self.ruid(), || libc::getuid())
+                && ck(self.egid(), || libc::getegid())
+                && ck(self.rgid(), || libc::getgid())
+                && ck(self.pid(), || libc::getpid())
+        }
+    }
 
     fn n_groups(&self) -> usize {
         match self.0 {
@@ -208,25 +332,50 @@ impl<'a> Credentials<'a> {
             CredentialsInner::Cmsgcred(c) => min(c.cmcred_ngroups, libc::CMGROUP_MAX as _).to::<c_int>(),
             #[cfg(uds_sockcred2)]
             CredentialsInner::Sockcred2(c) => c.sc_ngroups,
+            #[cfg(uds_sockcred2)]
+            CredentialsInner::Sockcred(c) => c.sc_ngroups,
             #[cfg(uds_xucred)]
             CredentialsInner::Xucred(c, _) => c.cr_ngroups.to::<c_int>(),
+            #[cfg(uds_unpcbid)]
+            CredentialsInner::Unpcbid(..) => 0,
+            #[cfg(uds_sockpeercred)]
+            CredentialsInner::Sockpeercred(..) => 0,
         }
         .try_to::<usize>()
         .unwrap()
     }
     fn ptr_to_gids(&self) -> *const gid_packed {
-        match self.0 {
+        // Matching on a reference to make sure that pointers into by-value structures point into `self` rather than
+        // into a copy on the stack.
+        match &self.0 {
             #[cfg(uds_ucred)]
             CredentialsInner::AncUcred(..) | CredentialsInner::Ucred(..) => std::ptr::null(),
             #[cfg(uds_cmsgcred)]
             CredentialsInner::Cmsgcred(c) => addr_of!(c.cmcred_groups).cast::<gid_packed>(),
             #[cfg(uds_sockcred2)]
             CredentialsInner::Sockcred2(c) => addr_of!(c.sc_groups).cast::<gid_packed>(),
+            #[cfg(uds_sockcred2)]
+            CredentialsInner::Sockcred(c) => addr_of!(c.sc_groups).cast::<gid_packed>(),
             #[cfg(uds_xucred)]
             CredentialsInner::Xucred(c, _) => addr_of!(c.cr_groups).cast::<gid_packed>(),
+            #[cfg(uds_unpcbid)]
+            CredentialsInner::Unpcbid(..) => std::ptr::null(),
+            #[cfg(uds_sockpeercred)]
+            CredentialsInner::Sockpeercred(..) => std::ptr::null(),
         }
     }
 }
+impl<'b> PartialEq<Credentials<'b>> for Credentials<'_> {
+    fn eq(&self, other: &Credentials<'b>) -> bool {
+        self.euid() == other.euid()
+            && self.ruid() == other.ruid()
+            && self.egid() == other.egid()
+            && self.rgid() == other.rgid()
+            && self.pid() == other.pid()
+            && self.groups().eq(other.groups())
+    }
+}
+impl Eq for Credentials<'_> {}
 
 /// An iterator over supplementary groups stored in [`Credentials`].
 ///
@@ -350,3 +499,30 @@ impl AsRef<sockcred2_packed> for sockcred2 {
         }
     }
 }
+
+#[cfg(uds_sockcred2)]
+#[repr(C, packed)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub(crate) struct sockcred_packed {
+    pub sc_uid: uid_t,
+    pub sc_euid: uid_t,
+    pub sc_gid: gid_t,
+    pub sc_egid: gid_t,
+    pub sc_ngroups: c_int,
+    pub sc_groups: [gid_t; 1],
+}
+#[cfg(uds_sockcred2)]
+impl AsRef<sockcred_packed> for sockcred {
+    fn as_ref(&self) -> &sockcred_packed {
+        const _: () = {
+            if size_of::<sockcred_packed>() != size_of::<sockcred>() {
+                panic!("size of `sockcred_packed` did not match that of `sockcred`");
+            }
+        };
+        unsafe {
+            // SAFETY: the two types have the same layout, save for stricter padding of the input
+            &*<*const _>::cast(self)
+        }
+    }
+}
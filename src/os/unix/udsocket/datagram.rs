@@ -1,7 +1,10 @@
 use super::{
     ancwrap, c_wrappers,
-    cmsg::{CmsgMut, CmsgMutBuf, CmsgRef},
-    PathDropGuard, ReadAncillarySuccess, ToUdSocketPath, UdSocketPath,
+    cmsg::{
+        context::{Collector, DummyCollector},
+        CmsgMut, CmsgMutBuf, CmsgRef,
+    },
+    PathDropGuard, ReadAncillarySuccess, ReadAncillaryWithFlags, ToUdSocketPath, UdSocket, UdSocketPath,
 };
 use crate::{
     os::unix::{unixprelude::*, FdOps},
@@ -12,14 +15,14 @@ use crate::{
     reliable_recv_msg::{ReliableRecvMsg, TryRecvResult},
     Sealed,
 };
-use libc::sockaddr_un;
-use std::io::{self, prelude::*, IoSlice, IoSliceMut};
-use to_method::To;
+use std::{
+    fmt::{self, Debug, Formatter},
+    io::{self, prelude::*, IoSlice, IoSliceMut},
+};
 
 /// A datagram socket in the Unix domain.
 ///
 /// All such sockets have the `SOCK_DGRAM` socket type; in other words, this is the Unix domain version of a UDP socket.
-#[derive(Debug)]
 pub struct UdDatagram {
     // TODO make this not 'static
     _drop_guard: PathDropGuard<'static>,
@@ -37,6 +40,23 @@ impl UdDatagram {
             fd,
         })
     }
+    /// Creates a pair of unnamed datagram sockets which are connected to each other, without involving the filesystem
+    /// or the socket namespace.
+    ///
+    /// Unlike with [`UdStream::pair()`](super::UdStream::pair), message boundaries are preserved: every datagram sent
+    /// on one end is received whole by a single receive call on the other end. Both ends have the close-on-exec flag
+    /// set.
+    ///
+    /// # System calls
+    /// - `socketpair`
+    pub fn pair() -> io::Result<(Self, Self)> {
+        let (fd1, fd2) = c_wrappers::create_uds_pair(libc::SOCK_DGRAM, false)?;
+        let wrap = |fd| Self {
+            _drop_guard: PathDropGuard::dummy(),
+            fd,
+        };
+        Ok((wrap(fd1), wrap(fd2)))
+    }
     /// Binds an existing socket created by [`unbound()`](Self::unbound) to the specified path.
     ///
     /// If the socket path exceeds the [maximum socket path length][mspl] (which includes the first 0 byte when using
@@ -59,10 +79,10 @@ impl UdDatagram {
         self._bind(path.to_socket_path()?)
     }
     fn _bind(&self, path: UdSocketPath<'_>) -> io::Result<()> {
-        let addr = path.borrow().try_to::<sockaddr_un>()?;
+        let (addr, addrlen) = path.to_sockaddr_un()?;
         unsafe {
             // SAFETY: addr is well-constructed
-            c_wrappers::bind(self.as_fd(), &addr)
+            c_wrappers::bind(self.as_fd(), &addr, addrlen)
         }
     }
     /// Binds an existing socket created by [`unbound()`](Self::unbound) to the specified path, remembers the address,
@@ -143,12 +163,25 @@ impl UdDatagram {
         self._set_destination(&path)
     }
     fn _set_destination(&self, path: &UdSocketPath<'_>) -> io::Result<()> {
-        let addr = path.borrow().try_to::<sockaddr_un>()?;
+        let (addr, addrlen) = path.to_sockaddr_un()?;
         unsafe {
             // SAFETY: addr is well-constructed
-            c_wrappers::connect(self.fd.0.as_fd(), &addr)
+            c_wrappers::connect(self.fd.0.as_fd(), &addr, addrlen)
         }
     }
+    /// Removes the destination previously selected with [`.set_destination()`](Self::set_destination), returning the
+    /// socket to the unconnected state in which it accepts datagrams from any socket and sending requires an address
+    /// to be specified via [`.send_to()`](Self::send_to). Does nothing if no destination is set.
+    ///
+    /// Some platforms report an `EAFNOSUPPORT` error in response to the system call even though the socket has been
+    /// disconnected; that error is not propagated by this method.
+    ///
+    /// # System calls
+    /// - `connect` (`AF_UNSPEC`)
+    #[inline]
+    pub fn disconnect(&self) -> io::Result<()> {
+        c_wrappers::disconnect(self.fd.0.as_fd())
+    }
 
     /// Receives a single datagram from the socket, returning the size of the received datagram.
     ///
@@ -196,21 +229,86 @@ impl UdDatagram {
         bufs: &mut [IoSliceMut<'_>],
         abuf: &mut impl CmsgMut,
     ) -> io::Result<ReadAncillarySuccess> {
-        ancwrap::recvmsg(self.as_fd(), bufs, abuf, None)
+        ancwrap::recvmsg(self.as_fd(), bufs, abuf, None, 0).map(|r| r.success)
+    }
+
+    /// Receives a single datagram from the socket along with the control messages attached to it, passing the given
+    /// flags to `recvmsg` and reporting the flags returned by it.
+    ///
+    /// Check [`main_truncated()`](ReadAncillaryWithFlags::main_truncated) and
+    /// [`ancillary_truncated()`](ReadAncillaryWithFlags::ancillary_truncated) on the return value to find out whether
+    /// part of the datagram or some of the control messages had to be discarded because the buffers were too small.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    #[inline]
+    pub fn recv_ancillary_with_flags(
+        &self,
+        buf: &mut [u8],
+        abuf: &mut impl CmsgMut,
+        flags: c_int,
+    ) -> io::Result<ReadAncillaryWithFlags> {
+        self.recv_ancillary_vectored_with_flags(&mut [IoSliceMut::new(buf)], abuf, flags)
+    }
+
+    /// Same as [`recv_ancillary_with_flags()`](Self::recv_ancillary_with_flags), but makes use of [scatter input].
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    #[inline]
+    pub fn recv_ancillary_vectored_with_flags(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut impl CmsgMut,
+        flags: c_int,
+    ) -> io::Result<ReadAncillaryWithFlags> {
+        ancwrap::recvmsg(self.as_fd(), bufs, abuf, None, flags)
+    }
+    /// Same as [`recv_ancillary_vectored_with_flags()`](Self::recv_ancillary_vectored_with_flags), but doesn't add
+    /// `MSG_CMSG_CLOEXEC` to `flags` on platforms which support it, making file descriptors received along with the
+    /// datagram inheritable by child processes.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    #[inline]
+    pub fn recv_ancillary_vectored_inheritable(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut impl CmsgMut,
+        flags: c_int,
+    ) -> io::Result<ReadAncillaryWithFlags> {
+        ancwrap::recvmsg_inheritable(self.as_fd(), bufs, abuf, None, flags, DummyCollector)
+    }
+    /// Same as [`recv_ancillary_vectored()`](Self::recv_ancillary_vectored), but calls the hooks of the given
+    /// [collector](super::cmsg::context) right before and right after the `recvmsg` call, allowing the state of the
+    /// socket needed to decode the received control messages to be recorded.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    /// - Whatever `collector` performs
+    #[inline]
+    pub fn recv_ancillary_vectored_with_collector(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut impl CmsgMut,
+        collector: impl Collector,
+    ) -> io::Result<ReadAncillarySuccess> {
+        ancwrap::recvmsg_with_collector(self.as_fd(), bufs, abuf, None, 0, collector).map(|r| r.success)
     }
 
     /// Receives a single datagram and the source address from the socket, returning how much of the buffer was filled
     /// out.
     ///
+    /// Datagrams sent from sockets which aren't bound to any path, such as ones created with
+    /// [`unbound()`](Self::unbound), yield [`UdSocketPath::Unnamed`] as the source address.
+    ///
     /// # System calls
     /// - `recvmsg`
-    ///     - Future versions of `interprocess` may use `recvfrom` instead; for now, this method is a wrapper around
-    /// [`recv_from_vectored`].
-    ///
-    /// [`recv_from_vectored`]: #method.recv_from_vectored " "
-    // TODO use recvfrom
-    pub fn recv_from<'a: 'b, 'b>(&self, buf: &mut [u8], addr_buf: &'b mut UdSocketPath<'a>) -> io::Result<usize> {
-        self.recv_from_vectored(&mut [IoSliceMut::new(buf)], addr_buf)
+    #[inline]
+    pub fn recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, UdSocketPath<'static>)> {
+        self.recv_from_vectored(&mut [IoSliceMut::new(buf)])
     }
 
     /// Receives a single datagram and the source address from the socket, making use of [scatter input] and returning
@@ -220,17 +318,13 @@ impl UdDatagram {
     /// - `recvmsg`
     ///
     /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
-    pub fn recv_from_vectored<'a: 'b, 'b>(
-        &self,
-        bufs: &mut [IoSliceMut<'_>],
-        addr_buf: &'b mut UdSocketPath<'a>,
-    ) -> io::Result<usize> {
-        self.recv_from_ancillary_vectored(bufs, &mut CmsgMutBuf::new(&mut []), addr_buf)
-            .map(|x| x.main)
+    #[inline]
+    pub fn recv_from_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<(usize, UdSocketPath<'static>)> {
+        self.recv_from_ancillary_vectored(bufs, &mut CmsgMutBuf::new(&mut []))
+            .map(|(x, addr)| (x.main, addr))
     }
 
-    /// Receives a single datagram, ancillary data and the source address from the socket. The first element of the
-    /// return value represents the read amount of the former, while the second element represents that of the latter.
+    /// Receives a single datagram, ancillary data and the source address from the socket.
     ///
     /// # System calls
     /// - `recvmsg`
@@ -239,14 +333,15 @@ impl UdDatagram {
         &self,
         buf: &mut [u8],
         abuf: &mut impl CmsgMut,
-        addr_buf: &mut UdSocketPath<'_>,
-    ) -> io::Result<ReadAncillarySuccess> {
-        self.recv_from_ancillary_vectored(&mut [IoSliceMut::new(buf)], abuf, addr_buf)
+    ) -> io::Result<(ReadAncillarySuccess, UdSocketPath<'static>)> {
+        self.recv_from_ancillary_vectored(&mut [IoSliceMut::new(buf)], abuf)
     }
 
     /// Receives a single datagram, ancillary data and the source address from the socket, making use of
-    /// [scatter input]. The first element of the return value represents the read amount of the former, while the
-    /// second element represents that of the latter.
+    /// [scatter input].
+    ///
+    /// Use [`recv_from_ancillary_vectored_with_flags()`](Self::recv_from_ancillary_vectored_with_flags) to find out
+    /// whether the datagram fit into the buffers.
     ///
     /// # System calls
     /// - `recvmsg`
@@ -257,9 +352,46 @@ impl UdDatagram {
         &self,
         bufs: &mut [IoSliceMut<'_>],
         abuf: &mut impl CmsgMut,
-        addr_buf: &mut UdSocketPath<'_>,
-    ) -> io::Result<ReadAncillarySuccess> {
-        ancwrap::recvmsg(self.as_fd(), bufs, abuf, Some(addr_buf))
+    ) -> io::Result<(ReadAncillarySuccess, UdSocketPath<'static>)> {
+        self.recv_from_ancillary_vectored_with_flags(bufs, abuf, 0)
+            .map(|(r, addr)| (r.success, addr))
+    }
+
+    /// Receives a single datagram, ancillary data and the source address from the socket, passing the given flags to
+    /// `recvmsg` and reporting the flags returned by it.
+    ///
+    /// If the datagram was larger than `buf`, [`main_truncated()`](ReadAncillaryWithFlags::main_truncated) is `true`
+    /// on the return value, and the rest of the datagram is lost.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    #[inline]
+    pub fn recv_from_ancillary_with_flags(
+        &self,
+        buf: &mut [u8],
+        abuf: &mut impl CmsgMut,
+        flags: c_int,
+    ) -> io::Result<(ReadAncillaryWithFlags, UdSocketPath<'static>)> {
+        self.recv_from_ancillary_vectored_with_flags(&mut [IoSliceMut::new(buf)], abuf, flags)
+    }
+
+    /// Same as [`recv_from_ancillary_with_flags()`](Self::recv_from_ancillary_with_flags), but makes use of
+    /// [scatter input]. Truncation is reported if the datagram was larger than all of the buffers combined.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    #[inline]
+    pub fn recv_from_ancillary_vectored_with_flags(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut impl CmsgMut,
+        flags: c_int,
+    ) -> io::Result<(ReadAncillaryWithFlags, UdSocketPath<'static>)> {
+        let mut addr = UdSocketPath::Unnamed;
+        let r = ancwrap::recvmsg(self.as_fd(), bufs, abuf, Some(&mut addr), flags)?;
+        Ok((r, addr))
     }
 
     /// Returns the size of the next datagram available on the socket without discarding it.
@@ -293,7 +425,6 @@ impl UdDatagram {
     pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
         (&self.fd).write(buf)
     }
-    // TODO sendto
     /// Sends a datagram into the socket, making use of [gather output] for the main data.
     ///
     ///
@@ -321,7 +452,73 @@ impl UdDatagram {
     /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
     #[inline]
     pub fn send_ancillary_vectored(&self, bufs: &[IoSlice<'_>], abuf: CmsgRef<'_>) -> io::Result<usize> {
-        ancwrap::sendmsg(self.as_fd(), bufs, abuf)
+        ancwrap::sendmsg(self.as_fd(), bufs, abuf, None, 0)
+    }
+    /// Same as [`send_ancillary_vectored()`](Self::send_ancillary_vectored), but calls the hooks of the given
+    /// [collector](super::cmsg::context) right before and right after the `sendmsg` call.
+    ///
+    /// # System calls
+    /// - `sendmsg`
+    /// - Whatever `collector` performs
+    #[inline]
+    pub fn send_ancillary_vectored_with_collector(
+        &self,
+        bufs: &[IoSlice<'_>],
+        abuf: CmsgRef<'_>,
+        collector: impl Collector,
+    ) -> io::Result<usize> {
+        ancwrap::sendmsg_with_collector(self.as_fd(), bufs, abuf, None, 0, collector)
+    }
+
+    /// Sends a datagram to the socket at the specified path, regardless of the destination set with
+    /// [`set_destination()`](Self::set_destination).
+    ///
+    /// See [`ToUdSocketPath`] for an example of using various string types to specify socket paths.
+    ///
+    /// # System calls
+    /// - `sendmsg`
+    #[inline]
+    pub fn send_to<'a>(&self, buf: &[u8], path: impl ToUdSocketPath<'a>) -> io::Result<usize> {
+        self.send_to_ancillary_vectored(&[IoSlice::new(buf)], CmsgRef::empty(), path)
+    }
+    /// Sends a datagram to the socket at the specified path, making use of [gather output] for the main data.
+    ///
+    /// # System calls
+    /// - `sendmsg`
+    ///
+    /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    #[inline]
+    pub fn send_to_vectored<'a>(&self, bufs: &[IoSlice<'_>], path: impl ToUdSocketPath<'a>) -> io::Result<usize> {
+        self.send_to_ancillary_vectored(bufs, CmsgRef::empty(), path)
+    }
+    /// Sends a datagram and ancillary data to the socket at the specified path.
+    ///
+    /// # System calls
+    /// - `sendmsg`
+    #[inline]
+    pub fn send_to_ancillary<'a>(
+        &self,
+        buf: &[u8],
+        abuf: CmsgRef<'_>,
+        path: impl ToUdSocketPath<'a>,
+    ) -> io::Result<usize> {
+        self.send_to_ancillary_vectored(&[IoSlice::new(buf)], abuf, path)
+    }
+    /// Sends a datagram and ancillary data to the socket at the specified path, making use of [gather output] for the
+    /// main data.
+    ///
+    /// # System calls
+    /// - `sendmsg`
+    ///
+    /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    pub fn send_to_ancillary_vectored<'a>(
+        &self,
+        bufs: &[IoSlice<'_>],
+        abuf: CmsgRef<'_>,
+        path: impl ToUdSocketPath<'a>,
+    ) -> io::Result<usize> {
+        let path = path.to_socket_path()?;
+        ancwrap::sendmsg(self.as_fd(), bufs, abuf, Some(&path), 0)
     }
 }
 
@@ -349,6 +546,39 @@ impl TryClone for UdDatagram {
     }
 }
 
+impl Debug for UdDatagram {
+    /// The bound path and the destination are queried from the kernel. Fields which cannot be queried are omitted.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        struct Unnamed;
+        impl Debug for Unnamed {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("<unnamed>")
+            }
+        }
+        fn fmt_path<'a>(path: &'a UdSocketPath<'_>) -> &'a dyn Debug {
+            match path {
+                UdSocketPath::Unnamed => &Unnamed,
+                path => path,
+            }
+        }
+        let mut dbs = f.debug_struct("UdDatagram");
+        dbs.field("fd", &self.as_raw_fd())
+            .field("has_drop_guard", &self._drop_guard.enabled);
+        if let Ok(local) = self.local_addr() {
+            dbs.field("local_path", fmt_path(&local));
+        }
+        match self.peer_addr() {
+            Ok(peer) => {
+                dbs.field("connected", &true).field("peer_path", fmt_path(&peer));
+            }
+            Err(e) if e.raw_os_error() == Some(libc::ENOTCONN) => {
+                dbs.field("connected", &false);
+            }
+            Err(..) => {}
+        }
+        dbs.finish()
+    }
+}
 impl AsFd for UdDatagram {
     #[inline]
     fn as_fd(&self) -> BorrowedFd<'_> {
@@ -0,0 +1,130 @@
+//! Length-prefixed message framing over [`UdStream`].
+//!
+//! A byte stream has no notion of message boundaries, so a protocol which exchanges messages over one has to delimit
+//! them somehow. [`FramedUdStream`] does so by sending the length of each message as a 32-bit big-endian integer
+//! before its contents, and refuses to receive messages larger than a configurable limit, so that a misbehaving peer
+//! cannot make the receiver allocate arbitrary amounts of memory.
+//!
+//! ```no_run
+//! use interprocess::os::unix::udsocket::{framed::FramedUdStream, UdStream};
+//!
+//! let conn = FramedUdStream::new(UdStream::connect("/tmp/example.sock")?);
+//! conn.send_msg(b"Hello from client!")?;
+//! let reply = conn.recv_msg()?;
+//! println!("Server answered: {}", String::from_utf8_lossy(&reply));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use super::UdStream;
+use std::{
+    io::{self, IoSlice, Read},
+    mem::size_of,
+};
+
+/// The default maximum frame size of [`FramedUdStream`], 16 MiB.
+pub const DEFAULT_MAX_FRAME_SIZE: u32 = 16 * 1024 * 1024;
+
+/// A [`UdStream`] which sends and receives whole messages, each preceded by its length.
+///
+/// The length prefix is a `u32` in big-endian byte order, which makes the format easy to implement on the other end in
+/// any language. Both ends must use this format for the communication to work; there is no handshake of any kind.
+///
+/// Since messages are sent with a single call to
+/// [`.send_all_vectored()`](UdStream::send_all_vectored), concurrent senders are only kept from interleaving for as
+/// long as the kernel accepts each message in one piece – threads that share a framed stream should serialize their
+/// sends with a lock.
+#[derive(Debug)]
+pub struct FramedUdStream {
+    stream: UdStream,
+    max_frame_size: u32,
+}
+impl FramedUdStream {
+    /// Wraps the given stream, using [`DEFAULT_MAX_FRAME_SIZE`] as the limit on the size of received messages.
+    #[inline]
+    pub fn new(stream: UdStream) -> Self {
+        Self::with_max_frame_size(stream, DEFAULT_MAX_FRAME_SIZE)
+    }
+    /// Wraps the given stream, using the specified limit on the size of received messages.
+    #[inline]
+    pub fn with_max_frame_size(stream: UdStream, max_frame_size: u32) -> Self {
+        Self { stream, max_frame_size }
+    }
+    /// Returns the limit on the size of received messages, in bytes, not including the length prefix.
+    #[inline]
+    pub fn max_frame_size(&self) -> u32 {
+        self.max_frame_size
+    }
+    /// Sets the limit on the size of received messages, in bytes, not including the length prefix.
+    #[inline]
+    pub fn set_max_frame_size(&mut self, max_frame_size: u32) {
+        self.max_frame_size = max_frame_size;
+    }
+
+    /// Sends a message, preceded by its length.
+    ///
+    /// Messages which are longer than `u32::MAX` bytes cannot be represented and are rejected with an
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) error. The maximum frame size only applies to receiving, since
+    /// it's there to protect the receiver; a message which is larger than that of the other side will be rejected by
+    /// it.
+    ///
+    /// # System calls
+    /// - `writev`, repeatedly
+    /// - `write` (if a `writev` call stops in the middle of a buffer)
+    pub fn send_msg(&self, msg: &[u8]) -> io::Result<()> {
+        let len = u32::try_from(msg.len()).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "message is too long for its length to fit into the 32-bit prefix",
+            )
+        })?;
+        let prefix = len.to_be_bytes();
+        self.stream
+            .send_all_vectored(&mut [IoSlice::new(&prefix), IoSlice::new(msg)])
+    }
+    /// Receives a whole message, waiting until all of it arrives.
+    ///
+    /// The following errors are returned in addition to those of the underlying reads:
+    /// - [`InvalidData`](io::ErrorKind::InvalidData) if the length prefix exceeds the [maximum frame
+    ///   size](Self::max_frame_size). The message is not consumed, which leaves the stream in the middle of a frame;
+    ///   the connection should be closed, since there is no way of finding the start of the next message anymore.
+    /// - [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) if the stream reaches end of file before a whole message is
+    ///   received, including when no part of one has been received at all, which is how the peer closing the
+    ///   connection at a message boundary is reported.
+    ///
+    /// # System calls
+    /// - `read`, repeatedly
+    pub fn recv_msg(&self) -> io::Result<Vec<u8>> {
+        let mut prefix = [0; size_of::<u32>()];
+        (&self.stream).read_exact(&mut prefix)?;
+        let len = u32::from_be_bytes(prefix);
+        if len > self.max_frame_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "received frame of {len} bytes exceeds the maximum frame size of {} bytes",
+                    self.max_frame_size
+                ),
+            ));
+        }
+        let mut msg = vec![0; len as usize];
+        (&self.stream).read_exact(&mut msg)?;
+        Ok(msg)
+    }
+
+    /// Borrows the underlying stream.
+    #[inline]
+    pub fn get_ref(&self) -> &UdStream {
+        &self.stream
+    }
+    /// Unwraps the underlying stream. Messages which have been partially received are lost.
+    #[inline]
+    pub fn into_inner(self) -> UdStream {
+        self.stream
+    }
+}
+impl From<UdStream> for FramedUdStream {
+    #[inline]
+    fn from(stream: UdStream) -> Self {
+        Self::new(stream)
+    }
+}
@@ -9,8 +9,171 @@ use std::{
     io,
     iter::FusedIterator,
     mem::zeroed,
+    os::unix::net::UnixListener as StdUdStreamListener,
+    time::Duration,
 };
-use to_method::To;
+
+/// The value of 128 used by default is the typical one for `SOMAXCONN`, but that constant is unavailable at least on
+/// Redox (and possibly on other systems too), so it's hardcoded here. The standard library does the same thing.
+pub(crate) const DEFAULT_BACKLOG: c_int = 128;
+
+/// Builder used to create [`UdStreamListener`]s while supplying options which have to be applied before the socket is
+/// bound or starts listening.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::os::unix::udsocket::UdStreamListenerBuilder;
+///
+/// let listener = UdStreamListenerBuilder::new()
+///     .backlog(512)
+///     .mode(0o600)
+///     .drop_guard(true)
+///     .bind("/tmp/example.sock")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UdStreamListenerBuilder {
+    /// Specifies whether the listener is to be created in nonblocking mode, in which
+    /// [`accept()`](UdStreamListener::accept) returns a [`WouldBlock`](io::ErrorKind::WouldBlock) error instead of
+    /// waiting for a client to connect. On platforms which support it, the flag is set atomically during socket
+    /// creation.
+    ///
+    /// The default value is `false`.
+    pub nonblocking: bool,
+    /// The maximum length of the queue of pending connections. See
+    /// [`bind_with_backlog()`](UdStreamListener::bind_with_backlog) for how the kernel treats this value.
+    ///
+    /// The default value is 128.
+    pub backlog: c_int,
+    /// The permission bits of the socket file, or `None` to leave them up to the process's umask. Connecting to a
+    /// socket requires write permission on its file, so this can be used to restrict which users can connect to the
+    /// server. Has no effect on sockets in the [socket namespace].
+    ///
+    /// On Linux and Android, the mode is applied to the socket before it's bound, which means that the socket file
+    /// never has more permissions than requested (the umask still gets subtracted from it at that point, and the exact
+    /// bits are restored right after `bind`). On other platforms, the file is created according to the umask and
+    /// changed afterwards, so there's a brief window during which it may be more permissive than requested – set a
+    /// restrictive umask or place the socket in a directory with appropriate permissions if that's a concern.
+    ///
+    /// The default value is `None`.
+    ///
+    /// [socket namespace]: enum.UdSocketPath.html#namespaced " "
+    pub mode: Option<mode_t>,
+    /// Specifies whether a drop guard, which deletes the socket file once the listener is dropped, is to be installed.
+    /// See [`bind_with_drop_guard()`](UdStreamListener::bind_with_drop_guard).
+    ///
+    /// The default value is `false`.
+    pub drop_guard: bool,
+}
+impl UdStreamListenerBuilder {
+    /// Starts with the default parameters for the listener. Identical to `Default::default()`.
+    pub const fn new() -> Self {
+        Self {
+            nonblocking: false,
+            backlog: DEFAULT_BACKLOG,
+            mode: None,
+            drop_guard: false,
+        }
+    }
+    /// Specifies whether the listener is to be created in nonblocking mode.
+    ///
+    /// See the [associated field] for more.
+    ///
+    /// [associated field]: #structfield.nonblocking " "
+    #[must_use = "this is not an in-place operation"]
+    pub fn nonblocking(mut self, nonblocking: bool) -> Self {
+        self.nonblocking = nonblocking;
+        self
+    }
+    /// Specifies the maximum length of the queue of pending connections.
+    ///
+    /// See the [associated field] for more.
+    ///
+    /// [associated field]: #structfield.backlog " "
+    #[must_use = "this is not an in-place operation"]
+    pub fn backlog(mut self, backlog: c_int) -> Self {
+        self.backlog = backlog;
+        self
+    }
+    /// Specifies the permission bits of the socket file.
+    ///
+    /// See the [associated field] for more.
+    ///
+    /// [associated field]: #structfield.mode " "
+    #[must_use = "this is not an in-place operation"]
+    pub fn mode(mut self, mode: impl Into<Option<mode_t>>) -> Self {
+        self.mode = mode.into();
+        self
+    }
+    /// Specifies whether a drop guard is to be installed.
+    ///
+    /// See the [associated field] for more.
+    ///
+    /// [associated field]: #structfield.drop_guard " "
+    #[must_use = "this is not an in-place operation"]
+    pub fn drop_guard(mut self, drop_guard: bool) -> Self {
+        self.drop_guard = drop_guard;
+        self
+    }
+
+    /// Creates a new listener socket at the specified address using the options from the builder.
+    ///
+    /// See [`UdStreamListener::bind()`] for more on how the path is handled. If setting the mode or starting to listen
+    /// fails after the socket file has been created, the file is deleted again, regardless of whether a drop guard was
+    /// requested.
+    ///
+    /// # System calls
+    /// - `socket`
+    /// - `fchmod` (Linux and Android, if [`mode`](#structfield.mode) is set)
+    /// - `bind`
+    /// - `chmod` (if [`mode`](#structfield.mode) is set and the path is a file path)
+    /// - `listen`
+    /// - `unlink` (if `chmod` or `listen` fails)
+    pub fn bind<'a>(&self, path: impl ToUdSocketPath<'a>) -> io::Result<UdStreamListener> {
+        self._bind(path.to_socket_path()?)
+    }
+    pub(crate) fn _bind(&self, path: UdSocketPath<'_>) -> io::Result<UdStreamListener> {
+        let (fd, drop_guard) = self.bind_socket(path, SOCK_STREAM)?;
+        Ok(UdStreamListener { fd, drop_guard })
+    }
+    /// Creates, binds and starts listening on a socket of the given type, for use by listeners of connection-oriented
+    /// socket types other than `SOCK_STREAM`.
+    pub(super) fn bind_socket(&self, path: UdSocketPath<'_>, ty: c_int) -> io::Result<(FdOps, PathDropGuard<'static>)> {
+        let (addr, addrlen) = path.to_sockaddr_un()?;
+
+        let fd = c_wrappers::create_uds(ty, self.nonblocking)?;
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        if let Some(mode) = self.mode {
+            c_wrappers::set_socket_mode(fd.0.as_fd(), mode)?;
+        }
+        unsafe {
+            // SAFETY: addr is well-constructed
+            c_wrappers::bind(fd.0.as_fd(), &addr, addrlen)?;
+        }
+
+        // Created right after bind and kept enabled until the remaining steps succeed, so that the file gets cleaned up
+        // if any of them fail, even without a drop guard being requested. The path is recorded even if the guard ends
+        // up disabled, so that it could be enabled later on.
+        let mut dg = PathDropGuard {
+            path: path.borrow().upgrade(),
+            enabled: true,
+        };
+
+        if let (Some(mode), UdSocketPath::File(file)) = (self.mode, &path) {
+            c_wrappers::set_file_mode(file, mode)?;
+        }
+        c_wrappers::listen(fd.0.as_fd(), self.backlog)?;
+
+        dg.enabled = self.drop_guard;
+        Ok((fd, dg))
+    }
+}
+impl Default for UdStreamListenerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 /// A Unix domain byte stream socket server, listening for connections.
 ///
@@ -52,7 +215,7 @@ use to_method::To;
 // TODO update..?
 pub struct UdStreamListener {
     // TODO make this not 'static
-    _drop_guard: PathDropGuard<'static>,
+    drop_guard: PathDropGuard<'static>,
     fd: FdOps,
 }
 impl UdStreamListener {
@@ -77,48 +240,86 @@ impl UdStreamListener {
     /// [socket namespace]: enum.UdSocketPath.html#namespaced " "
     /// [`ToUdSocketPath`]: trait.ToUdSocketPath.html " "
     pub fn bind<'a>(path: impl ToUdSocketPath<'a>) -> io::Result<Self> {
-        Self::_bind(path.to_socket_path()?, false, false)
+        UdStreamListenerBuilder::new().bind(path)
+    }
+    /// Like [`bind()`](Self::bind), but allows specifying the maximum length of the queue of pending connections
+    /// instead of using the default of 128.
+    ///
+    /// The kernel is free to silently reduce the value, and most platforms do so: Linux, for example, caps it at the
+    /// value of `/proc/sys/net/core/somaxconn`, which also happens to be what negative values are treated as.
+    ///
+    /// # System calls
+    /// - `socket`
+    /// - `bind`
+    /// - `listen`
+    pub fn bind_with_backlog<'a>(path: impl ToUdSocketPath<'a>, backlog: c_int) -> io::Result<Self> {
+        UdStreamListenerBuilder::new().backlog(backlog).bind(path)
     }
     /// Creates a new listener socket at the specified address, remembers the address, and installs a drop guard that
     /// will delete the socket file once the socket is dropped.
     ///
     /// See the documentation of [`bind()`](Self::bind).
     pub fn bind_with_drop_guard<'a>(path: impl ToUdSocketPath<'a>) -> io::Result<Self> {
-        Self::_bind(path.to_socket_path()?, true, false)
+        UdStreamListenerBuilder::new().drop_guard(true).bind(path)
     }
-    pub(crate) fn _bind(path: UdSocketPath<'_>, keep_drop_guard: bool, nonblocking: bool) -> io::Result<Self> {
-        let addr = path.borrow().try_to::<sockaddr_un>()?;
-
-        let fd = c_wrappers::create_uds(SOCK_STREAM, nonblocking)?;
-        unsafe {
-            // SAFETY: addr is well-constructed
-            c_wrappers::bind(fd.0.as_fd(), &addr)?;
-        }
-        // FIXME the standard library uses 128 here without an option to change this
-        // number, why? If std has solid reasons to do this, remove this notice and
-        // document the method's behavior on this matter explicitly; otherwise, add
-        // an option to change this value.
-        // UPD: the value of 128 is actually the typical one for SOMAXCONN, but that
-        // constant is unavailable at least on Redox (and possibly on other systems
-        // too). TODO add a conditional-compilation-powered way to set this to the
-        // absolute highest possible value, or maybe provide a method with a parameter
-        // to customize it.
-        c_wrappers::listen(fd.0.as_fd(), 128)?;
-
-        let dg = if keep_drop_guard {
-            PathDropGuard {
-                path: path.upgrade(),
-                enabled: true,
-            }
-        } else {
-            PathDropGuard::dummy()
-        };
-
-        Ok(Self { fd, _drop_guard: dg })
+    /// Takes ownership of a listening socket passed to the process by systemd socket activation, `index` being the
+    /// position of the socket among those passed (`0` being the first one, with the file descriptor
+    /// `SD_LISTEN_FDS_START`, i.e. 3).
+    ///
+    /// The socket activation protocol is followed the same way as by `sd_listen_fds()`: the `LISTEN_PID` environment
+    /// variable has to name the current process and `LISTEN_FDS` has to include the requested socket, which must be a
+    /// listening Unix domain stream socket. On success, the close-on-exec flag is set on the file descriptor. The
+    /// environment variables are left in place, since modifying the environment is not thread-safe.
+    ///
+    /// # Errors
+    /// - [`NotFound`](io::ErrorKind::NotFound) if the process was not started through socket activation, i.e. either
+    ///   of the environment variables is missing or `LISTEN_PID` names a different process
+    /// - [`InvalidData`](io::ErrorKind::InvalidData) if either of the environment variables is malformed
+    /// - [`InvalidInput`](io::ErrorKind::InvalidInput) if `index` is out of range or the file descriptor is not a
+    ///   listening Unix domain stream socket
+    /// - [`AlreadyExists`](io::ErrorKind::AlreadyExists) if the socket has already been taken by an earlier call with
+    ///   the same index, since creating two owners for the same file descriptor would cause it to be closed twice
+    ///
+    /// # System calls
+    /// - `getpid`
+    /// - `getsockopt` (3 times)
+    /// - `fcntl`
+    #[cfg(target_os = "linux")]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(target_os = "linux")))]
+    pub fn from_systemd(index: usize) -> io::Result<Self> {
+        let fd = systemd::claim_listen_fd(index)?;
+        Ok(Self::from(fd))
+    }
+    /// Enables or disables deletion of the socket file once the listener is dropped, as done by the drop guard
+    /// installed by [`bind_with_drop_guard()`](Self::bind_with_drop_guard). Returns whether the file will actually be
+    /// deleted.
+    ///
+    /// The return value is always `false` for listeners whose path is not known – those which were created from a file
+    /// descriptor via `From<OwnedFd>` – and for sockets in the
+    /// [socket namespace], since the latter don't have a file and disappear on their own once the listener is closed.
+    ///
+    /// # Race condition
+    /// The file is deleted by its path, since there is no way to delete a socket file via the socket itself. If some
+    /// other process deletes the file and replaces it with a different one (such as the socket file of another
+    /// instance of the same server) while this listener is alive, that file will be deleted instead. The OS doesn't
+    /// offer a way to detect this, so it's up to the application to ensure that only one process uses a given path at
+    /// a time, e.g. via a lock file.
+    ///
+    /// Clones made with [`TryClone`] never delete the file on their own, regardless of the setting of the listener
+    /// they were cloned from, so that dropping a worker's clone doesn't pull the name out from under the others. This
+    /// method can be used to hand the responsibility over to a clone that is known to outlive the rest.
+    ///
+    /// [socket namespace]: enum.UdSocketPath.html#namespaced " "
+    pub fn set_reclaim_name_on_drop(&mut self, reclaim: bool) -> bool {
+        self.drop_guard.enabled = reclaim;
+        reclaim && matches!(self.drop_guard.path, UdSocketPath::File(..))
     }
 
     /// Listens for incoming connections to the socket, blocking until a client is connected.
     ///
+    /// The accepted stream has the close-on-exec flag set. Where possible, this happens atomically with the accept
+    /// operation, so that a child process forked by another thread at the same time cannot inherit it.
+    ///
     /// See [`incoming`] for a convenient way to create a main loop for a server.
     ///
     /// # Example
@@ -140,43 +341,85 @@ impl UdStreamListener {
     /// ```
     ///
     /// # System calls
-    /// - `accept`
+    /// - `accept4` (`accept` followed by `fcntl` on platforms without it)
     ///
     /// [`incoming`]: #method.incoming " "
     pub fn accept(&self) -> io::Result<UdStream> {
-        let (success, fd) = unsafe {
-            let result = libc::accept(self.as_raw_fd(), zeroed(), zeroed());
-            (result != -1, result)
-        };
-        if success {
-            Ok(unsafe {
-                // SAFETY: we just created the file descriptor, meaning that it's guaranteeed
-                // not to be used elsewhere
-                UdStream::from_raw_fd(fd)
-            })
-        } else {
-            Err(io::Error::last_os_error())
+        let (fd, _) = c_wrappers::accept(self.as_fd(), None)?;
+        Ok(UdStream::from(fd.0))
+    }
+
+    /// Accepts a pending connection if there is one, returning `Ok(None)` instead of waiting or failing with
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) otherwise.
+    ///
+    /// In [nonblocking mode](Self::set_nonblocking), this is a single `accept` with `WouldBlock` turned into
+    /// `Ok(None)`, which never waits, even if the listener is also being accepted from elsewhere, such as another
+    /// thread, another process or a clone. **Blocking mode is not changed by this method**, so in that mode, the
+    /// listener is polled before accepting. If it's being accepted from elsewhere too, the pending connection may be
+    /// taken in between the two calls, in which case this call blocks until the next one arrives – shared listeners
+    /// should be put in nonblocking mode to rule that out.
+    ///
+    /// # System calls
+    /// - `fcntl` (`F_GETFL`)
+    /// - `poll` (in blocking mode)
+    /// - `accept4` or `accept` and `fcntl` (in blocking mode, only if a connection is pending)
+    pub fn accept_nonblocking(&self) -> io::Result<Option<UdStream>> {
+        if !self.is_nonblocking()?
+            && c_wrappers::poll(
+                &mut [c_wrappers::pollfd_for(self.as_fd(), libc::POLLIN)],
+                Some(Duration::ZERO),
+            )? == 0
+        {
+            return Ok(None);
+        }
+        match self.accept() {
+            Ok(stream) => Ok(Some(stream)),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(None),
+            Err(e) => Err(e),
         }
     }
 
+    /// Like [`accept()`](Self::accept), but also returns the path to which the client's socket is bound.
+    ///
+    /// Clients which didn't bind their socket before connecting (which is the usual case) yield
+    /// [`UdSocketPath::Unnamed`].
+    ///
+    /// # System calls
+    /// - `accept4` (`accept` followed by `fcntl` on platforms without it)
+    pub fn accept_with_addr(&self) -> io::Result<(UdStream, UdSocketPath<'static>)> {
+        // SAFETY: sockaddr_un is POD
+        let mut addr = unsafe { zeroed::<sockaddr_un>() };
+        let (fd, addrlen) = c_wrappers::accept(self.as_fd(), Some(&mut addr))?;
+        let stream = UdStream::from(fd.0);
+        Ok((stream, UdSocketPath::from_sockaddr_un(&addr, addrlen)?))
+    }
+
     /// Creates an infinite iterator which calls `accept()` with each iteration. Used together with `for` loops to
     /// conveniently create a main loop for a socket server.
     ///
+    /// The iterator never returns `None`. Errors produced by `accept()` are yielded as-is instead of ending the
+    /// iteration, so that the server loop can decide whether to skip the failed connection or bail out.
+    ///
     /// # Example
     /// ```no_run
     /// use interprocess::os::unix::udsocket::UdStreamListener;
     ///
     /// let listener = UdStreamListener::bind("/tmp/example.sock")?;
     /// // Thanks to incoming(), you get a simple self-documenting infinite server loop
-    /// for connection in listener.incoming()
-    ///     .map(|conn| if let Err(error) = conn {
-    ///         eprintln!("Incoming connection failed: {}", error);
-    ///     }) {
-    ///     eprintln!("New client!");
-    /// #   drop(connection);
+    /// for connection in listener.incoming() {
+    ///     match connection {
+    ///         Ok(connection) => {
+    ///             eprintln!("New client!");
+    /// #           drop(connection);
+    ///         }
+    ///         Err(error) => eprintln!("Incoming connection failed: {}", error),
+    ///     }
     /// }
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
+    ///
+    /// # System calls
+    /// - `accept4` or `accept` and `fcntl` (on every iteration)
     pub fn incoming(&self) -> Incoming<'_> {
         Incoming::from(self)
     }
@@ -188,21 +431,48 @@ impl UdStreamListener {
     /// error immediately, allowing the thread to perform other useful operations while there are no new client
     /// connections to accept.
     ///
+    /// This is what makes it possible to drive the listener from a custom event loop, such as one built on
+    /// [`poll_read_ready()`](super::poll_read_ready), which reports a listener as readable when a connection is
+    /// pending.
+    ///
+    /// # System calls
+    /// - `fcntl` (`F_GETFL`)
+    /// - `fcntl` (`F_SETFL`)
+    ///
     /// [`accept`]: #method.accept " "
     /// [`incoming`]: #method.incoming " "
     pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
         c_wrappers::set_nonblocking(self.fd.0.as_fd(), nonblocking)
     }
     /// Checks whether the socket is currently in nonblocking mode or not.
+    ///
+    /// # System calls
+    /// - `fcntl` (`F_GETFL`)
     pub fn is_nonblocking(&self) -> io::Result<bool> {
         c_wrappers::get_nonblocking(self.fd.0.as_fd())
     }
+    /// Sets or clears the close-on-exec flag of the file descriptor. Listeners adopted from a file descriptor may have
+    /// it cleared, unlike those created by this crate; see [`UdStream::set_cloexec()`].
+    ///
+    /// # System calls
+    /// - `fcntl` (`F_GETFD`)
+    /// - `fcntl` (`F_SETFD`, only if the flag has to be changed)
+    pub fn set_cloexec(&self, cloexec: bool) -> io::Result<()> {
+        self.fd.set_cloexec(cloexec)
+    }
+    /// Checks whether the close-on-exec flag of the file descriptor is set.
+    ///
+    /// # System calls
+    /// - `fcntl` (`F_GETFD`)
+    pub fn get_cloexec(&self) -> io::Result<bool> {
+        self.fd.get_cloexec()
+    }
 }
 impl Debug for UdStreamListener {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         f.debug_struct("UdStreamListener")
             .field("fd", &self.as_raw_fd())
-            .field("has_drop_guard", &self._drop_guard.enabled)
+            .field("has_drop_guard", &self.drop_guard.enabled)
             .finish()
     }
 }
@@ -214,7 +484,9 @@ impl AsFd for UdStreamListener {
 }
 impl From<UdStreamListener> for OwnedFd {
     #[inline]
-    fn from(x: UdStreamListener) -> Self {
+    fn from(mut x: UdStreamListener) -> Self {
+        // The socket outlives the listener object, so its file must too
+        x.drop_guard.enabled = false;
         x.fd.0
     }
 }
@@ -222,15 +494,44 @@ impl From<OwnedFd> for UdStreamListener {
     #[inline]
     fn from(fd: OwnedFd) -> Self {
         UdStreamListener {
-            _drop_guard: PathDropGuard::dummy(),
+            drop_guard: PathDropGuard::dummy(),
             fd: FdOps(fd),
         }
     }
 }
+/// Converts into the standard library's Unix domain socket listener by transferring ownership of the file descriptor.
+///
+/// The standard library never removes socket files, so a listener [reclaiming its name on
+/// drop](UdStreamListener::set_reclaim_name_on_drop) stops doing so – the socket file stays around after the resulting
+/// listener is dropped.
+impl From<UdStreamListener> for StdUdStreamListener {
+    #[inline]
+    fn from(x: UdStreamListener) -> Self {
+        OwnedFd::from(x).into()
+    }
+}
+/// Adopts the file descriptor of the standard library's Unix domain socket listener.
+///
+/// The socket stays bound and listening with whatever backlog and options it had, which allows listening sockets
+/// inherited through mechanisms such as systemd socket activation to be used with the features of this crate. Since
+/// its path is not known, the resulting listener does not [reclaim](UdStreamListener::set_reclaim_name_on_drop) it.
+impl From<StdUdStreamListener> for UdStreamListener {
+    #[inline]
+    fn from(x: StdUdStreamListener) -> Self {
+        OwnedFd::from(x).into()
+    }
+}
+/// Duplicates the listening socket with the close-on-exec flag set, allowing multiple threads or processes to accept
+/// connections from it, with the kernel distributing incoming connections between them.
+///
+/// The clone remembers the path of the original, but does not [reclaim it on drop](Self::set_reclaim_name_on_drop).
 impl TryClone for UdStreamListener {
     fn try_clone(&self) -> io::Result<Self> {
         let s = Self {
-            _drop_guard: self._drop_guard.clone(),
+            drop_guard: PathDropGuard {
+                path: self.drop_guard.path.clone(),
+                enabled: false,
+            },
             fd: self.fd.try_clone()?,
         };
         Ok(s)
@@ -238,11 +539,92 @@ impl TryClone for UdStreamListener {
 }
 derive_raw!(UdStreamListener, unix);
 
+#[cfg(target_os = "linux")]
+mod systemd {
+    use super::c_wrappers;
+    use crate::os::unix::{c_wrappers::set_cloexec, unixprelude::*};
+    use libc::{AF_UNIX, SOCK_STREAM, SO_ACCEPTCONN, SO_DOMAIN, SO_TYPE};
+    use std::{env, io, sync::Mutex};
+
+    const SD_LISTEN_FDS_START: usize = 3;
+
+    /// Indices of the sockets which have already been turned into owned file descriptors.
+    static CLAIMED: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+    fn env_number<T: std::str::FromStr>(name: &str) -> io::Result<T> {
+        let val = env::var(name).map_err(|e| match e {
+            env::VarError::NotPresent => io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("{name} is not set, the process was not started through socket activation"),
+            ),
+            env::VarError::NotUnicode(..) => {
+                io::Error::new(io::ErrorKind::InvalidData, format!("{name} is not a number"))
+            }
+        })?;
+        val.parse()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, format!("{name} is not a number")))
+    }
+
+    fn check_socket(fd: BorrowedFd<'_>) -> io::Result<()> {
+        let mut val: c_int = 0;
+        let mut check = |option, expected, msg| {
+            c_wrappers::get_socket_option(fd, libc::SOL_SOCKET, option, &mut val)?;
+            if val == expected {
+                Ok(())
+            } else {
+                Err(io::Error::new(io::ErrorKind::InvalidInput, msg))
+            }
+        };
+        check(
+            SO_DOMAIN,
+            AF_UNIX,
+            "socket passed by systemd is not a Unix domain socket",
+        )?;
+        check(SO_TYPE, SOCK_STREAM, "socket passed by systemd is not a stream socket")?;
+        check(SO_ACCEPTCONN, 1, "socket passed by systemd is not listening")
+    }
+
+    pub(super) fn claim_listen_fd(index: usize) -> io::Result<OwnedFd> {
+        let pid: pid_t = env_number("LISTEN_PID")?;
+        if pid != unsafe { libc::getpid() } {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "LISTEN_PID names a different process, the sockets were not passed to this one",
+            ));
+        }
+        let count: usize = env_number("LISTEN_FDS")?;
+        if index >= count {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("socket index {index} is out of range, systemd passed {count} sockets"),
+            ));
+        }
+        let raw = c_int::try_from(SD_LISTEN_FDS_START + index)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "socket index is out of range"))?;
+
+        let mut claimed = CLAIMED.lock().unwrap_or_else(|e| e.into_inner());
+        if claimed.contains(&index) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("socket {index} passed by systemd has already been taken"),
+            ));
+        }
+        // SAFETY: the socket activation protocol hands these file descriptors over to the process, and the check above
+        // ensures that each of them is only borrowed or owned by this crate through a single object.
+        let fd = unsafe { BorrowedFd::borrow_raw(raw) };
+        check_socket(fd)?;
+        set_cloexec(fd, true)?;
+        claimed.push(index);
+        Ok(unsafe { OwnedFd::from_raw_fd(raw) })
+    }
+}
+
 /// An infinite iterator over incoming client connections of a [`UdStreamListener`].
 ///
 /// This iterator is created by the [`incoming`] method on [`UdStreamListener`] – see its documentation for more.
 ///
 /// [`incoming`]: struct.UdStreamListener.html#method.incoming " "
+#[derive(Debug)]
 pub struct Incoming<'a> {
     listener: &'a UdStreamListener,
 }
@@ -16,9 +16,8 @@
 //! The [`UdStreamListener`] and [`UdDatagram`] types are two starting points, depending on whether you intend to use
 //! UDP-like datagrams or TCP-like byte streams.
 
-// TODO sync split
-
 pub mod cmsg;
+pub mod framed;
 
 #[cfg_attr( // uds_credentials template
     feature = "doc_cfg",
@@ -53,10 +52,12 @@ mod ancillary_io;
 mod datagram;
 mod listener;
 mod path;
+mod readiness;
+mod seqpacket;
 mod socket_trait;
 mod stream;
 
-pub use {ancillary_io::*, datagram::*, listener::*, path::*, socket_trait::*, stream::*};
+pub use {ancillary_io::*, datagram::*, listener::*, path::*, readiness::*, seqpacket::*, socket_trait::*, stream::*};
 
 mod path_drop_guard;
 use path_drop_guard::*;
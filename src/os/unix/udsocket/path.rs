@@ -3,17 +3,23 @@ use super::{
     util::{empty_cstr, empty_cstring, eunreachable},
     MAX_UDSOCKET_PATH_LEN,
 };
-use crate::os::unix::unixprelude::*;
+use crate::{error::ConversionError, os::unix::unixprelude::*};
 use libc::{sockaddr_un, AF_UNIX};
 use std::{
     borrow::{Cow, ToOwned},
+    collections::hash_map::RandomState,
     convert::TryFrom,
+    error::Error,
     ffi::{CStr, CString, NulError, OsStr, OsString},
+    fmt::{self, Display, Formatter},
+    hash::{BuildHasher, Hasher},
     io,
-    mem::{replace, size_of_val, zeroed},
+    mem::{replace, size_of, zeroed},
     ops::Deref,
     path::{Path, PathBuf},
-    ptr,
+    ptr, slice,
+    sync::atomic::{AtomicU64, Ordering},
+    time::SystemTime,
 };
 
 /// Represents a name for a Unix domain socket.
@@ -32,7 +38,14 @@ use std::{
 /// All sockets identified this way are located on the main filesystem and exist as persistent files until deletion,
 /// preventing servers from using the same socket without deleting it from the filesystem first. This variant is
 /// available on all POSIX-compilant systems.
-#[derive(Clone, Debug, PartialEq, Eq)]
+///
+/// # Equality and hashing
+/// Paths compare and hash by their logical value: the kind of path and the bytes of the name, excluding the nul
+/// terminator. Whether the name is borrowed or owned doesn't matter, and neither does the way it was obtained – a
+/// name decoded from an address returned by the kernel is equal to the one it was bound with. A filesystem path and a
+/// namespaced name are never equal, even if their bytes are. This makes paths usable as keys in hash maps, such as a
+/// registry of connections by peer address.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum UdSocketPath<'a> {
     /// An unnamed socket, identified only by its file descriptor. This is an invalid path value for creating sockets –
     /// all attempts to use such a value will result in an error.
@@ -53,6 +66,25 @@ pub enum UdSocketPath<'a> {
     )]
     Namespaced(Cow<'a, CStr>),
 }
+/// The kind of a [`UdSocketPath`], as returned by [`.namespace()`](UdSocketPath::namespace).
+///
+/// Together with [`.as_bytes()`](UdSocketPath::as_bytes), this fully describes a path, allowing it to be stored or
+/// transmitted and later reconstructed with [`UdSocketPath::file_from_vec()`] or [`UdSocketPath::abstract_name()`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Namespace {
+    /// The [`Unnamed`](UdSocketPath::Unnamed) variant.
+    Unnamed,
+    /// A path in the filesystem tree, i.e. the [`File`](UdSocketPath::File) variant.
+    Pathname,
+    /// A name in Linux's abstract namespace, i.e. the [`Namespaced`](UdSocketPath::Namespaced) variant.
+    #[cfg(uds_linux_namespace)]
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    Abstract,
+}
+
 impl<'a> UdSocketPath<'a> {
     /// Attempts to upgrade lifetime to `'static` (which is only possible when the path is owned). Borrowed paths return
     /// `Err(…)`, allowing you to get the original path back.
@@ -69,9 +101,13 @@ impl<'a> UdSocketPath<'a> {
         Ok(ok)
     }
     /// Upgrades lifetime to `'static` by ensuring that the path is owned. May require an allocation.
-    pub fn upgrade(mut self) -> UdSocketPath<'static> {
-        self.make_owned();
-        self.try_upgrade().unwrap()
+    pub fn upgrade(self) -> UdSocketPath<'static> {
+        match self {
+            Self::Unnamed => UdSocketPath::Unnamed,
+            Self::File(cow) => UdSocketPath::File(Cow::Owned(cow.into_owned())),
+            #[cfg(uds_linux_namespace)]
+            Self::Namespaced(cow) => UdSocketPath::Namespaced(Cow::Owned(cow.into_owned())),
+        }
     }
     /// Returns the path as a [`CStr`]. The resulting value does not include any indication of whether it's a namespaced
     /// socket name or a filesystem path.
@@ -83,6 +119,26 @@ impl<'a> UdSocketPath<'a> {
             Self::Unnamed => empty_cstr(),
         }
     }
+    /// Returns the raw bytes of the path, without the nul terminator. For names in the abstract namespace, the leading
+    /// nul byte that marks them as such at the system call level is not included either, and neither is any kind of
+    /// indication of the namespace – use [`.namespace()`](Self::namespace) for that. Unnamed paths have no bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::File(cow) => cow.to_bytes(),
+            #[cfg(uds_linux_namespace)]
+            Self::Namespaced(cow) => cow.to_bytes(),
+            Self::Unnamed => &[],
+        }
+    }
+    /// Returns which kind of path this is.
+    pub const fn namespace(&self) -> Namespace {
+        match self {
+            Self::File(..) => Namespace::Pathname,
+            #[cfg(uds_linux_namespace)]
+            Self::Namespaced(..) => Namespace::Abstract,
+            Self::Unnamed => Namespace::Unnamed,
+        }
+    }
     /// Returns the path as an [`OsStr`]. The resulting value does not include any indication of whether it's a
     /// namespaced socket name or a filesystem path.
     pub fn as_osstr(&'a self) -> &'a OsStr {
@@ -112,7 +168,13 @@ impl<'a> UdSocketPath<'a> {
     /// [`File`]: #file " "
     pub fn make_owned(&mut self) -> bool {
         let required_cloning = !self.is_owned();
-        *self = self.to_owned();
+        if required_cloning {
+            *self = match replace(self, Self::Unnamed) {
+                #[cfg(uds_linux_namespace)]
+                Self::Namespaced(cow) => Self::Namespaced(Cow::Owned(cow.into_owned())),
+                other => Self::File(Cow::Owned(other.into_cstring())),
+            };
+        }
         required_cloning
     }
     /// Borrows into another `UdSocketPath<'_>` instance. If borrowed here, reborrows; if owned here, returns a fresh
@@ -154,114 +216,56 @@ impl<'a> UdSocketPath<'a> {
     #[allow(clippy::match_like_matches_macro)]
     pub const fn is_owned(&self) -> bool {
         match self {
-            Self::File(Cow::Borrowed(..)) => true,
+            Self::File(Cow::Owned(..)) => true,
             #[cfg(uds_linux_namespace)]
-            Self::Namespaced(Cow::Borrowed(..)) => true,
+            Self::Namespaced(Cow::Owned(..)) => true,
             _ => false,
         }
     }
 
-    pub(super) fn write_sockaddr_un_to_self(&mut self, addr: &sockaddr_un, addrlen: usize) {
-        let sun_path_length = (addrlen as isize) - (size_of_val(&addr.sun_family) as isize);
-        let sun_path_length = match usize::try_from(sun_path_length) {
-            Ok(val) => val,
-            Err(..) => {
-                *self = Self::Unnamed;
-                return;
-            }
-        };
-        if let Some(cstring) = self.try_get_cstring_mut() {
-            let cstring = replace(cstring, empty_cstring());
-            let mut vec = cstring.into_bytes_with_nul();
-            let mut _namespaced = false;
-            unsafe {
-                #[cfg(uds_linux_namespace)]
-                let (src_ptr, path_length) = if addr.sun_path[0] == 0 {
-                    _namespaced = true;
-                    (addr.sun_path.as_ptr().offset(1) as *const u8, sun_path_length - 1)
-                } else {
-                    (addr.sun_path.as_ptr() as *const u8, sun_path_length)
-                };
-                #[cfg(not(uds_linux_namespace))]
-                let (src_ptr, path_length) = { (addr.sun_path.as_ptr() as *const u8, sun_path_length) };
-                // Fill the space for the name and the nul terminator with nuls
-                vec.resize(path_length, 0);
-                ptr::copy_nonoverlapping(src_ptr, vec.as_mut_ptr(), path_length);
-            };
-            // If the system added a nul byte as part of the length, remove the one we added ourselves.
-            if vec.last() == Some(&0) && vec[vec.len() - 2] == 0 {
-                vec.pop();
-            }
-            let new_cstring = CString::new(vec).unwrap_or_else(eunreachable);
-            #[cfg(uds_linux_namespace)]
-            let path_to_write = if _namespaced {
-                UdSocketPath::Namespaced(Cow::Owned(new_cstring))
-            } else {
-                UdSocketPath::File(Cow::Owned(new_cstring))
-            };
-            #[cfg(not(uds_linux_namespace))]
-            let path_to_write = UdSocketPath::File(Cow::Owned(new_cstring));
-            *self = path_to_write;
-            // Implicitly drops the empty CString we wrote in the beginning
-        } else {
-            let mut _namespaced = false;
-            let mut vec = unsafe {
-                let (src_ptr, path_length) = if addr.sun_path[0] == 0 {
-                    (addr.sun_path.as_ptr().offset(1) as *const u8, sun_path_length - 1)
-                } else {
-                    (addr.sun_path.as_ptr() as *const u8, sun_path_length)
-                };
-                let mut vec = vec![0; path_length];
-                ptr::copy_nonoverlapping(src_ptr, vec.as_mut_ptr(), path_length);
-                vec
-            };
-            // If the system added a nul byte as part of the length, remove it.
-            if vec.last() == Some(&0) {
-                vec.pop();
-            }
-            let cstring = CString::new(vec).unwrap_or_else(eunreachable);
+    /// Returns `true` if the path refers to a socket in the [socket namespace], which Linux calls the *abstract
+    /// namespace*; `false` otherwise, and always `false` on platforms which don't support it.
+    ///
+    /// [socket namespace]: #namespaced " "
+    // Cannot use `matches!` due to #[cfg(...)]
+    #[allow(clippy::match_like_matches_macro)]
+    pub const fn is_abstract(&self) -> bool {
+        match self {
             #[cfg(uds_linux_namespace)]
-            let path_to_write = if _namespaced {
-                UdSocketPath::Namespaced(Cow::Owned(cstring))
-            } else {
-                UdSocketPath::File(Cow::Owned(cstring))
-            };
-            #[cfg(not(uds_linux_namespace))]
-            let path_to_write = UdSocketPath::File(Cow::Owned(cstring));
-            *self = path_to_write;
+            Self::Namespaced(..) => true,
+            _ => false,
         }
     }
+
     /// Returns `addr_len` to pass to `bind`/`connect`.
-    pub(super) fn write_self_to_sockaddr_un(&self, addr: &mut sockaddr_un) -> io::Result<()> {
+    ///
+    /// For filesystem paths, that's the offset of `sun_path` plus the length of the path with its nul terminator. For
+    /// namespaced names, which Linux treats as raw byte strings of the exact length given by `addr_len` rather than as
+    /// nul-terminated strings, it's the offset of `sun_path` plus the leading nul byte and the name itself, without a
+    /// terminator; otherwise, the name seen by other programs would have nul padding on its end.
+    pub(super) fn write_self_to_sockaddr_un(&self, addr: &mut sockaddr_un) -> io::Result<usize> {
         let is_namespaced;
-        let len_of_self = self.as_cstr().to_bytes_with_nul().len();
-        match self {
+        let mut len_of_self = self.as_cstr().to_bytes_with_nul().len();
+        let max_len = match self {
             UdSocketPath::File(..) => {
                 is_namespaced = false;
-                if len_of_self > MAX_UDSOCKET_PATH_LEN {
-                    return Err(io::Error::new(
-                        io::ErrorKind::InvalidInput,
-                        format!("socket path should not be longer than {MAX_UDSOCKET_PATH_LEN} bytes"),
-                    ));
-                }
+                MAX_UDSOCKET_PATH_LEN
             }
             #[cfg(uds_linux_namespace)]
             UdSocketPath::Namespaced(..) => {
                 is_namespaced = true;
-                const MAX_NAMESPACED_LEN: usize = MAX_UDSOCKET_PATH_LEN - 1;
-                if len_of_self > MAX_NAMESPACED_LEN {
-                    return Err(io::Error::new(
-                        io::ErrorKind::InvalidInput,
-                        format!("namespaced socket name should not be longer than {MAX_NAMESPACED_LEN} bytes"),
-                    ));
-                }
-            }
-            UdSocketPath::Unnamed => {
-                return Err(io::Error::new(
-                    io::ErrorKind::InvalidInput,
-                    "must provide a name for the socket",
-                ))
+                len_of_self -= 1;
+                // One byte is taken up by the nul that marks the name as namespaced
+                MAX_UDSOCKET_PATH_LEN - 1
             }
+            UdSocketPath::Unnamed => return Err(self.conversion_error(PathConversionErrorKind::Unnamed)),
+        };
+        if len_of_self > max_len {
+            return Err(self.conversion_error(PathConversionErrorKind::TooLong {
+                provided_len: len_of_self,
+                max_len,
+                namespaced: is_namespaced,
+            }));
         }
 
         unsafe {
@@ -275,42 +279,29 @@ impl<'a> UdSocketPath<'a> {
                 len_of_self,
             );
         }
-        Ok(())
+        let path_offset = addr.sun_path.as_ptr() as usize - (addr as *const sockaddr_un as usize);
+        let addrlen = path_offset + usize::from(is_namespaced) + len_of_self;
+        #[cfg(uds_sun_len)]
+        {
+            // Cannot overflow, since the whole structure is shorter than 256 bytes on platforms which have this field
+            addr.sun_len = addrlen as u8;
+        }
+        Ok(addrlen)
+    }
+    fn conversion_error(&self, details: PathConversionErrorKind) -> io::Error {
+        let e = PathConversionError::from_source_and_details(self.borrow().upgrade(), details);
+        io::Error::new(io::ErrorKind::InvalidInput, e)
+    }
+    /// Converts the path into a `sockaddr_un` and the address length to go with it.
+    pub(super) fn to_sockaddr_un(&self) -> io::Result<(sockaddr_un, usize)> {
+        // SAFETY: sockaddr_un is POD
+        let mut addr = unsafe { zeroed::<sockaddr_un>() };
+        addr.sun_family = AF_UNIX as _;
+        let addrlen = self.write_self_to_sockaddr_un(&mut addr)?;
+        Ok((addr, addrlen))
     }
 }
 impl UdSocketPath<'static> {
-    /// Creates a buffer suitable for usage with [`recv_from`] ([`_ancillary`]/[`_vectored`]/[`_ancillary_vectored`]).
-    /// The capacity is equal to the [`MAX_UDSOCKET_PATH_LEN`] constant (the nul terminator in the `CString` is
-    /// included). **The contained value is unspecified – results of reading from the buffer should not be relied
-    /// upon.**
-    ///
-    /// # Example
-    /// ```
-    /// use interprocess::os::unix::udsocket::{UdSocketPath, MAX_UDSOCKET_PATH_LEN};
-    /// use std::borrow::Cow;
-    ///
-    /// let path_buffer = UdSocketPath::buffer();
-    /// match path_buffer {
-    ///     UdSocketPath::File(cow) => match cow {
-    ///         Cow::Owned(cstring)
-    ///     => assert_eq!(cstring.into_bytes_with_nul().capacity(), MAX_UDSOCKET_PATH_LEN),
-    ///         Cow::Borrowed(..) => unreachable!(),
-    ///     }
-    ///     _ => unreachable!(),
-    /// }
-    /// ```
-    ///
-    /// [`recv_from`]: super::UdDatagram::recv_from
-    /// [`_ancillary`]: super::UdDatagram::recv_from_ancillary
-    /// [`_vectored`]: super::UdDatagram::recv_from_vectored
-    /// [`_ancillary_vectored`]: super::UdDatagram::recv_from_ancillary_vectored
-    pub fn buffer() -> Self {
-        Self::File(Cow::Owned(
-            CString::new(vec![0x2F; MAX_UDSOCKET_PATH_LEN - 1])
-                .expect("unexpected nul in newly created Vec, possible heap corruption"),
-        ))
-    }
-
     /// Constructs a `UdSocketPath::File` value from a `Vec` of bytes, wrapping `CString::new`.
     pub fn file_from_vec(vec: Vec<u8>) -> Result<Self, NulError> {
         Ok(Self::File(Cow::Owned(CString::new(vec)?)))
@@ -324,7 +315,103 @@ impl UdSocketPath<'static> {
     pub fn namespaced_from_vec(vec: Vec<u8>) -> Result<Self, NulError> {
         Ok(Self::Namespaced(Cow::Owned(CString::new(vec)?)))
     }
+    /// Constructs a `UdSocketPath::Namespaced` value referring to the given name in Linux's abstract namespace.
+    ///
+    /// The name is to be specified without the leading nul byte that marks it as abstract at the system call level –
+    /// that byte is added automatically when the address is passed to the kernel. The name cannot contain nul bytes,
+    /// since the other end would be unable to refer to it using this crate.
+    #[cfg(uds_linux_namespace)]
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    pub fn abstract_name(name: &[u8]) -> Result<Self, NulError> {
+        Self::namespaced_from_vec(name.to_vec())
+    }
+
+    /// Generates a filesystem path for a throwaway socket in the temporary directory of the system, as returned by
+    /// [`std::env::temp_dir()`].
+    ///
+    /// The file name consists of `prefix`, the ID of the current process, a counter which is incremented on every call
+    /// and a random number, so paths generated concurrently by different threads or processes never collide. No file
+    /// is created: the path is merely likely to be free by the time it's bound to, and the socket has to be deleted
+    /// afterwards as usual, e.g. by binding with a drop guard. Keep `prefix` short, since a long temporary directory
+    /// path might already take up a considerable part of the [length limit](super::MAX_UDSOCKET_PATH_LEN).
+    ///
+    /// # Panics
+    /// If `prefix` contains a nul byte.
+    pub fn generate_temp(prefix: &str) -> Self {
+        let mut path = std::env::temp_dir();
+        path.push(unique_name(prefix));
+        Self::file_from_vec(path.into_os_string().into_vec()).expect("socket name prefix contains a nul byte")
+    }
+    /// Same as [`generate_temp()`](Self::generate_temp), but generates a name in Linux's abstract namespace, which
+    /// needs no cleanup.
+    ///
+    /// # Panics
+    /// If `prefix` contains a nul byte.
+    #[cfg(uds_linux_namespace)]
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    pub fn generate_temp_abstract(prefix: &str) -> Self {
+        Self::namespaced_from_vec(unique_name(prefix).into_bytes()).expect("socket name prefix contains a nul byte")
+    }
+
+    /// Decodes an address returned by the kernel into an owned path, never reading past `addrlen` bytes of `addr`.
+    ///
+    /// An address with no path bytes or a path consisting solely of nul bytes decodes to `Unnamed`. The name is cut off
+    /// at the first nul byte after the namespace marker, since that's where the padding starts for names bound with a
+    /// full-size address (and since `CStr` cannot hold nuls anyway).
+    pub(super) fn from_sockaddr_un(addr: &sockaddr_un, addrlen: usize) -> io::Result<Self> {
+        if addrlen > size_of::<sockaddr_un>() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "address length reported by the kernel ({addrlen}) exceeds the size of sockaddr_un ({})",
+                    size_of::<sockaddr_un>()
+                ),
+            ));
+        }
+        let path_offset = addr.sun_path.as_ptr() as usize - (addr as *const sockaddr_un as usize);
+        let sun_path = match addrlen.checked_sub(path_offset) {
+            Some(len) => &addr.sun_path[..len],
+            None => return Ok(Self::Unnamed),
+        };
+        let sun_path = unsafe {
+            // SAFETY: c_char has the same size and alignment as u8
+            slice::from_raw_parts(sun_path.as_ptr().cast::<u8>(), sun_path.len())
+        };
+
+        let namespaced = cfg!(uds_linux_namespace) && sun_path.first() == Some(&0);
+        let name = if namespaced { &sun_path[1..] } else { sun_path };
+        let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+        if name.is_empty() {
+            return Ok(Self::Unnamed);
+        }
+        let cstring = CString::new(name.to_vec()).unwrap_or_else(eunreachable);
+        #[cfg(uds_linux_namespace)]
+        if namespaced {
+            return Ok(Self::Namespaced(Cow::Owned(cstring)));
+        }
+        Ok(Self::File(Cow::Owned(cstring)))
+    }
+}
+/// Makes a socket file name that no other call in this or any other process will return.
+fn unique_name(prefix: &str) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    // Guards against collisions with processes that had the same ID earlier, e.g. in another container
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(count);
+    if let Ok(since_epoch) = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+        hasher.write_u128(since_epoch.as_nanos());
+    }
+    let random = hasher.finish() as u32;
+    format!("{prefix}-{}-{count}-{random:08x}.sock", std::process::id())
 }
+
 impl From<UdSocketPath<'_>> for CString {
     fn from(path: UdSocketPath<'_>) -> Self {
         path.into_cstring()
@@ -345,17 +432,94 @@ impl AsRef<OsStr> for UdSocketPath<'_> {
         self.as_osstr()
     }
 }
+/// Filesystem paths are displayed as they are, with invalid UTF-8 replaced as per [`Path::display()`]. Names in the
+/// abstract namespace are prefixed with `@`, the way tools like `ss` and `netstat` show them, and have every byte
+/// outside of printable ASCII, as well as quotes and backslashes, escaped as per [`<[u8]>::escape_ascii()`], so that
+/// different names are always displayed differently. Unnamed paths are displayed as `<unnamed>`.
+impl Display for UdSocketPath<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unnamed => f.write_str("<unnamed>"),
+            Self::File(cow) => Display::fmt(&Path::new(OsStr::from_bytes(cow.to_bytes())).display(), f),
+            #[cfg(uds_linux_namespace)]
+            Self::Namespaced(cow) => write!(f, "@{}", cow.to_bytes().escape_ascii()),
+        }
+    }
+}
 impl TryFrom<UdSocketPath<'_>> for sockaddr_un {
     type Error = io::Error;
     fn try_from(path: UdSocketPath<'_>) -> io::Result<Self> {
-        unsafe {
-            let mut addr: sockaddr_un = zeroed();
-            addr.sun_family = AF_UNIX as _;
-            path.write_self_to_sockaddr_un(&mut addr)?;
-            Ok(addr)
+        path.to_sockaddr_un().map(|(addr, _)| addr)
+    }
+}
+
+/// Additional contextual information for failed conversions of a [`UdSocketPath`] into a socket address.
+///
+/// Errors of this kind are returned by all functions which bind or connect sockets, wrapped in a
+/// [`PathConversionError`] inside an [`io::Error`] of kind [`InvalidInput`](io::ErrorKind::InvalidInput). Use
+/// [`get_ref()`](io::Error::get_ref) and [`downcast_ref()`](std::error::Error) to retrieve it:
+/// ```
+/// use interprocess::os::unix::udsocket::{
+///     PathConversionError, PathConversionErrorKind, UdStream, MAX_UDSOCKET_PATH_LEN,
+/// };
+///
+/// let path = format!("/tmp/{}.sock", "a".repeat(MAX_UDSOCKET_PATH_LEN));
+/// let err = UdStream::connect(path.as_str()).unwrap_err();
+/// let details = err.get_ref().and_then(|e| e.downcast_ref::<PathConversionError>()).unwrap().details;
+/// assert_eq!(
+///     details,
+///     PathConversionErrorKind::TooLong {
+///         provided_len: path.len() + 1,
+///         max_len: MAX_UDSOCKET_PATH_LEN,
+///         namespaced: false,
+///     },
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PathConversionErrorKind {
+    /// The path doesn't fit into `sun_path`, whose size differs by platform and is given by
+    /// [`MAX_UDSOCKET_PATH_LEN`].
+    TooLong {
+        /// The length of the path in bytes. For filesystem paths, this includes the nul terminator; for namespaced
+        /// names, it doesn't, since they are not nul-terminated at the system call level.
+        provided_len: usize,
+        /// The maximum length for the kind of path in question, in the same units as `provided_len`. For namespaced
+        /// names, this is one less than for filesystem paths, since one byte is taken up by the leading nul.
+        max_len: usize,
+        /// Whether the path is a namespaced name rather than a filesystem path.
+        namespaced: bool,
+    },
+    /// The path was [`Unnamed`](UdSocketPath::Unnamed), which cannot be used to create or connect to a socket.
+    Unnamed,
+}
+impl Display for PathConversionErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::TooLong {
+                provided_len,
+                max_len,
+                namespaced,
+            } => {
+                let what = if namespaced {
+                    "namespaced socket name"
+                } else {
+                    "socket path (including nul terminator)"
+                };
+                write!(
+                    f,
+                    "{what} is {provided_len} bytes long, which exceeds the limit of {max_len} bytes"
+                )
+            }
+            Self::Unnamed => f.write_str("must provide a name for the socket"),
         }
     }
 }
+impl Error for PathConversionErrorKind {}
+
+/// Error type for conversions of a [`UdSocketPath`] into a socket address. The rejected path is returned in the
+/// `source` field.
+pub type PathConversionError = ConversionError<UdSocketPath<'static>, PathConversionErrorKind>;
 
 /// Trait for types which can be converted to a [path to a Unix domain socket][`UdSocketPath`].
 ///
@@ -495,7 +659,7 @@ impl<'a> ToUdSocketPath<'a> for &'a OsStr {
                 owned.remove(0);
                 return Ok(UdSocketPath::Namespaced(Cow::Owned(CString::new(owned)?)));
             } else {
-                let without_at_sign = self.as_bytes().split_at(1).0;
+                let without_at_sign = self.as_bytes().split_at(1).1;
                 let cstr = CStr::from_bytes_with_nul(without_at_sign)
                     .map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))?;
                 return Ok(UdSocketPath::Namespaced(Cow::Borrowed(cstr)));
@@ -575,7 +739,7 @@ impl<'a> ToUdSocketPath<'a> for &'a str {
                 owned.remove(0);
                 return Ok(UdSocketPath::Namespaced(Cow::Owned(CString::new(owned)?)));
             } else {
-                let without_at_sign = self.split_at(1).0;
+                let without_at_sign = self.split_at(1).1;
                 let cstr = CStr::from_bytes_with_nul(without_at_sign.as_bytes())
                     .map_err(|x| io::Error::new(io::ErrorKind::InvalidInput, x))?;
                 return Ok(UdSocketPath::Namespaced(Cow::Borrowed(cstr)));
@@ -0,0 +1,30 @@
+use super::c_wrappers;
+use crate::os::unix::unixprelude::*;
+use std::{io, time::Duration};
+
+/// Waits for any of the given file descriptors to become readable, returning whether each of them is, in the same
+/// order.
+///
+/// This is a lightweight way of serving several [`UdStream`](super::UdStream)s (or listeners, for which readability
+/// means that a connection is waiting to be accepted) from one thread without pulling in an async runtime.
+/// Descriptors which have been hung up on or have a pending error are reported as readable too, since reading from
+/// them will not block either – it'll return end of file or the error.
+///
+/// With a timeout of `None`, waits indefinitely. If the timeout runs out before anything becomes readable, a list of
+/// `false` values is returned rather than an error. Interrupted waits are resumed with the remaining time. An empty
+/// list of descriptors is returned immediately, without waiting.
+///
+/// # System calls
+/// - `poll` (repeatedly if interrupted)
+pub fn poll_read_ready(fds: &[BorrowedFd<'_>], timeout: Option<Duration>) -> io::Result<Vec<bool>> {
+    if fds.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut pfds = fds
+        .iter()
+        .map(|fd| c_wrappers::pollfd_for(*fd, libc::POLLIN))
+        .collect::<Vec<_>>();
+    c_wrappers::poll(&mut pfds, timeout)?;
+    // POLLHUP and POLLERR are reported regardless of the requested events
+    Ok(pfds.iter().map(|pfd| pfd.revents != 0).collect())
+}
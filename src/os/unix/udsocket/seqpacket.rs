@@ -0,0 +1,386 @@
+use super::{
+    ancwrap, c_wrappers,
+    cmsg::{context::DummyCollector, CmsgMut, CmsgMutBuf, CmsgRef},
+    PathDropGuard, ReadAncillarySuccess, ReadAncillaryWithFlags, ToUdSocketPath, UdSocketPath, UdStreamListenerBuilder,
+};
+use crate::{
+    os::unix::{unixprelude::*, FdOps},
+    TryClone,
+};
+use libc::{sockaddr_un, SOCK_SEQPACKET};
+use std::{
+    fmt::{self, Debug, Formatter},
+    io::{self, prelude::*, IoSlice, IoSliceMut},
+    mem::zeroed,
+};
+
+/// A Unix domain sequenced packet socket, connected to a peer.
+///
+/// All such sockets have the `SOCK_SEQPACKET` socket type, which combines the connection-oriented nature of
+/// [`UdStream`](super::UdStream) with the preserved message boundaries of [`UdDatagram`](super::UdDatagram): every
+/// message sent on one end is received whole by a single receive call on the other end, in the order in which the
+/// messages were sent. If the receive buffer is too small for a message, the rest of it is discarded, which the
+/// `_with_flags` family of receive methods reports via
+/// [`main_truncated()`](ReadAncillaryWithFlags::main_truncated).
+///
+/// Connections are accepted by [`UdSeqpacketListener`]. Apple platforms do not support this socket type in the Unix
+/// domain, and creating such sockets fails there at runtime.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::os::unix::udsocket::UdSeqpacket;
+///
+/// let conn = UdSeqpacket::connect("/tmp/example_seqpacket.sock")?;
+/// conn.send(b"Hello")?;
+/// conn.send(b"from client!")?;
+/// let mut buf = [0; 128];
+/// let len = conn.recv(&mut buf)?;
+/// println!("Server answered: {}", String::from_utf8_lossy(&buf[..len]));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct UdSeqpacket(FdOps);
+impl UdSeqpacket {
+    /// Connects to a Unix domain sequenced packet socket server at the specified path.
+    ///
+    /// # System calls
+    /// - `socket`
+    /// - `connect`
+    pub fn connect<'a>(path: impl ToUdSocketPath<'a>) -> io::Result<Self> {
+        let (addr, addrlen) = path.to_socket_path()?.to_sockaddr_un()?;
+        let fd = c_wrappers::create_uds(SOCK_SEQPACKET, false)?;
+        unsafe {
+            // SAFETY: addr is well-constructed
+            c_wrappers::connect(fd.0.as_fd(), &addr, addrlen)?;
+        }
+        Ok(Self(fd))
+    }
+    /// Creates a pair of unnamed sequenced packet sockets which are connected to each other, without involving the
+    /// filesystem or the socket namespace. Both ends have the close-on-exec flag set.
+    ///
+    /// # System calls
+    /// - `socketpair`
+    pub fn pair() -> io::Result<(Self, Self)> {
+        let (fd1, fd2) = c_wrappers::create_uds_pair(SOCK_SEQPACKET, false)?;
+        Ok((Self(fd1), Self(fd2)))
+    }
+
+    /// Receives a single message from the socket, returning how much of the buffer was filled out. If the message
+    /// doesn't fit, the rest of it is discarded.
+    ///
+    /// A return value of zero can either mean that the peer has closed the connection or that it sent an empty
+    /// message; use [`recv_ancillary_with_flags()`](Self::recv_ancillary_with_flags) to find out whether the message
+    /// was truncated.
+    ///
+    /// # System calls
+    /// - `read`
+    #[inline]
+    pub fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        (&self.0).read(buf)
+    }
+    /// Receives a single message from the socket, making use of [scatter input].
+    ///
+    /// # System calls
+    /// - `readv`
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    #[inline]
+    pub fn recv_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        (&self.0).read_vectored(bufs)
+    }
+    /// Receives a single message from the socket along with the control messages attached to it.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    #[inline]
+    pub fn recv_ancillary(&self, buf: &mut [u8], abuf: &mut impl CmsgMut) -> io::Result<ReadAncillarySuccess> {
+        self.recv_ancillary_vectored(&mut [IoSliceMut::new(buf)], abuf)
+    }
+    /// Same as [`recv_ancillary()`](Self::recv_ancillary), but makes use of [scatter input].
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    #[inline]
+    pub fn recv_ancillary_vectored(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut impl CmsgMut,
+    ) -> io::Result<ReadAncillarySuccess> {
+        self.recv_ancillary_vectored_with_flags(bufs, abuf, 0)
+            .map(|r| r.success)
+    }
+    /// Receives a single message from the socket along with the control messages attached to it, passing the given
+    /// flags to `recvmsg` and reporting the flags returned by it.
+    ///
+    /// Check [`main_truncated()`](ReadAncillaryWithFlags::main_truncated) on the return value to find out whether the
+    /// message was larger than `buf`.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    #[inline]
+    pub fn recv_ancillary_with_flags(
+        &self,
+        buf: &mut [u8],
+        abuf: &mut impl CmsgMut,
+        flags: c_int,
+    ) -> io::Result<ReadAncillaryWithFlags> {
+        self.recv_ancillary_vectored_with_flags(&mut [IoSliceMut::new(buf)], abuf, flags)
+    }
+    /// Same as [`recv_ancillary_with_flags()`](Self::recv_ancillary_with_flags), but makes use of [scatter input].
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    #[inline]
+    pub fn recv_ancillary_vectored_with_flags(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut impl CmsgMut,
+        flags: c_int,
+    ) -> io::Result<ReadAncillaryWithFlags> {
+        ancwrap::recvmsg(self.as_fd(), bufs, abuf, None, flags)
+    }
+    /// Same as [`recv_ancillary_vectored_with_flags()`](Self::recv_ancillary_vectored_with_flags), but doesn't add
+    /// `MSG_CMSG_CLOEXEC` to `flags` on platforms which support it, making file descriptors received along with the
+    /// message inheritable by child processes.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    #[inline]
+    pub fn recv_ancillary_vectored_inheritable(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut impl CmsgMut,
+        flags: c_int,
+    ) -> io::Result<ReadAncillaryWithFlags> {
+        ancwrap::recvmsg_inheritable(self.as_fd(), bufs, abuf, None, flags, DummyCollector)
+    }
+    /// Same as [`recv_ancillary_with_flags()`](Self::recv_ancillary_with_flags), but without receiving control
+    /// messages.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    #[inline]
+    pub fn recv_with_flags(&self, buf: &mut [u8], flags: c_int) -> io::Result<ReadAncillaryWithFlags> {
+        self.recv_ancillary_with_flags(buf, &mut CmsgMutBuf::new(&mut []), flags)
+    }
+
+    /// Sends a message into the socket. Messages are sent whole or not at all.
+    ///
+    /// # System calls
+    /// - `write`
+    #[inline]
+    pub fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        (&self.0).write(buf)
+    }
+    /// Sends a message into the socket, making use of [gather output].
+    ///
+    /// # System calls
+    /// - `writev`
+    ///
+    /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    #[inline]
+    pub fn send_vectored(&self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        (&self.0).write_vectored(bufs)
+    }
+    /// Sends a message and ancillary data into the socket.
+    ///
+    /// Unlike with streams, control messages can be sent along with an empty message.
+    ///
+    /// # System calls
+    /// - `sendmsg`
+    #[inline]
+    pub fn send_ancillary(&self, buf: &[u8], abuf: CmsgRef<'_>) -> io::Result<usize> {
+        self.send_ancillary_vectored(&[IoSlice::new(buf)], abuf)
+    }
+    /// Same as [`send_ancillary()`](Self::send_ancillary), but makes use of [gather output].
+    ///
+    /// # System calls
+    /// - `sendmsg`
+    ///
+    /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    #[inline]
+    pub fn send_ancillary_vectored(&self, bufs: &[IoSlice<'_>], abuf: CmsgRef<'_>) -> io::Result<usize> {
+        ancwrap::sendmsg(self.as_fd(), bufs, abuf, None, 0)
+    }
+}
+impl AsFd for UdSeqpacket {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0 .0.as_fd()
+    }
+}
+impl From<UdSeqpacket> for OwnedFd {
+    #[inline]
+    fn from(x: UdSeqpacket) -> Self {
+        x.0 .0
+    }
+}
+impl From<OwnedFd> for UdSeqpacket {
+    #[inline]
+    fn from(fd: OwnedFd) -> Self {
+        UdSeqpacket(FdOps(fd))
+    }
+}
+multimacro! {
+    UdSeqpacket,
+    forward_try_clone,
+    derive_raw(unix),
+}
+
+impl UdStreamListenerBuilder {
+    /// Creates a [sequenced packet listener](UdSeqpacketListener) at the specified address, applying the options in
+    /// the same way as [`bind()`](Self::bind) does for stream listeners.
+    ///
+    /// # System calls
+    /// - `socket`
+    /// - `fchmod` (Linux and Android, if [`mode`](#structfield.mode) is set)
+    /// - `bind`
+    /// - `chmod` (if [`mode`](#structfield.mode) is set and the path is a file path)
+    /// - `listen`
+    pub fn bind_seqpacket<'a>(&self, path: impl ToUdSocketPath<'a>) -> io::Result<UdSeqpacketListener> {
+        let (fd, drop_guard) = self.bind_socket(path.to_socket_path()?, SOCK_SEQPACKET)?;
+        Ok(UdSeqpacketListener { drop_guard, fd })
+    }
+}
+
+/// A Unix domain sequenced packet socket server, listening for connections.
+///
+/// Created with the same options and in the same way as [`UdStreamListener`](super::UdStreamListener), but accepts
+/// [`UdSeqpacket`] connections instead. Use [`UdStreamListenerBuilder::bind_seqpacket()`] to supply options other than
+/// the ones covered by the constructors.
+///
+/// # Example
+/// ```no_run
+/// use interprocess::os::unix::udsocket::UdSeqpacketListener;
+///
+/// let listener = UdSeqpacketListener::bind("/tmp/example_seqpacket.sock")?;
+/// let mut buf = [0; 128];
+/// loop {
+///     let conn = listener.accept()?;
+///     let len = conn.recv(&mut buf)?;
+///     conn.send(&buf[..len])?;
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct UdSeqpacketListener {
+    // TODO make this not 'static
+    drop_guard: PathDropGuard<'static>,
+    fd: FdOps,
+}
+impl UdSeqpacketListener {
+    /// Creates a new listener socket at the specified address. See
+    /// [`UdStreamListener::bind()`](super::UdStreamListener::bind) for more on how the path is handled.
+    ///
+    /// # System calls
+    /// - `socket`
+    /// - `bind`
+    /// - `listen`
+    pub fn bind<'a>(path: impl ToUdSocketPath<'a>) -> io::Result<Self> {
+        UdStreamListenerBuilder::new().bind_seqpacket(path)
+    }
+    /// Like [`bind()`](Self::bind), but allows specifying the maximum length of the queue of pending connections
+    /// instead of using the default of 128.
+    ///
+    /// # System calls
+    /// - `socket`
+    /// - `bind`
+    /// - `listen`
+    pub fn bind_with_backlog<'a>(path: impl ToUdSocketPath<'a>, backlog: c_int) -> io::Result<Self> {
+        UdStreamListenerBuilder::new().backlog(backlog).bind_seqpacket(path)
+    }
+    /// Creates a new listener socket at the specified address, remembers the address, and installs a drop guard that
+    /// will delete the socket file once the socket is dropped.
+    ///
+    /// See the documentation of [`bind()`](Self::bind).
+    pub fn bind_with_drop_guard<'a>(path: impl ToUdSocketPath<'a>) -> io::Result<Self> {
+        UdStreamListenerBuilder::new().drop_guard(true).bind_seqpacket(path)
+    }
+    /// Enables or disables deletion of the socket file once the listener is dropped. See
+    /// [`UdStreamListener::set_reclaim_name_on_drop()`](super::UdStreamListener::set_reclaim_name_on_drop), which this
+    /// behaves identically to, clones included.
+    pub fn set_reclaim_name_on_drop(&mut self, reclaim: bool) -> bool {
+        self.drop_guard.enabled = reclaim;
+        reclaim && matches!(self.drop_guard.path, UdSocketPath::File(..))
+    }
+
+    /// Listens for incoming connections to the socket, blocking until a client is connected.
+    ///
+    /// # System calls
+    /// - `accept4` (`accept` followed by `fcntl` on platforms without it)
+    pub fn accept(&self) -> io::Result<UdSeqpacket> {
+        let (fd, _) = c_wrappers::accept(self.as_fd(), None)?;
+        Ok(UdSeqpacket(fd))
+    }
+    /// Like [`accept()`](Self::accept), but also returns the path to which the client's socket is bound.
+    ///
+    /// # System calls
+    /// - `accept4` (`accept` followed by `fcntl` on platforms without it)
+    pub fn accept_with_addr(&self) -> io::Result<(UdSeqpacket, UdSocketPath<'static>)> {
+        // SAFETY: sockaddr_un is POD
+        let mut addr = unsafe { zeroed::<sockaddr_un>() };
+        let (fd, addrlen) = c_wrappers::accept(self.as_fd(), Some(&mut addr))?;
+        Ok((UdSeqpacket(fd), UdSocketPath::from_sockaddr_un(&addr, addrlen)?))
+    }
+
+    /// Enables or disables the nonblocking mode for the listener. By default, it is disabled.
+    ///
+    /// In nonblocking mode, [`accept()`](Self::accept) returns a [`WouldBlock`](io::ErrorKind::WouldBlock) error
+    /// instead of waiting for a client to connect.
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        c_wrappers::set_nonblocking(self.fd.0.as_fd(), nonblocking)
+    }
+    /// Checks whether the socket is currently in nonblocking mode or not.
+    pub fn is_nonblocking(&self) -> io::Result<bool> {
+        c_wrappers::get_nonblocking(self.fd.0.as_fd())
+    }
+}
+impl Debug for UdSeqpacketListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UdSeqpacketListener")
+            .field("fd", &self.as_raw_fd())
+            .field("has_drop_guard", &self.drop_guard.enabled)
+            .finish()
+    }
+}
+impl AsFd for UdSeqpacketListener {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.fd.0.as_fd()
+    }
+}
+impl From<UdSeqpacketListener> for OwnedFd {
+    #[inline]
+    fn from(mut x: UdSeqpacketListener) -> Self {
+        // The socket outlives the listener object, so its file must too
+        x.drop_guard.enabled = false;
+        x.fd.0
+    }
+}
+impl From<OwnedFd> for UdSeqpacketListener {
+    #[inline]
+    fn from(fd: OwnedFd) -> Self {
+        UdSeqpacketListener {
+            drop_guard: PathDropGuard::dummy(),
+            fd: FdOps(fd),
+        }
+    }
+}
+/// Duplicates the listening socket with the close-on-exec flag set. Just like with
+/// [`UdStreamListener`](super::UdStreamListener), the clone does not
+/// [reclaim the path on drop](Self::set_reclaim_name_on_drop).
+impl TryClone for UdSeqpacketListener {
+    fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self {
+            drop_guard: PathDropGuard {
+                path: self.drop_guard.path.clone(),
+                enabled: false,
+            },
+            fd: self.fd.try_clone()?,
+        })
+    }
+}
+derive_raw!(UdSeqpacketListener, unix);
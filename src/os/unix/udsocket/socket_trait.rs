@@ -1,6 +1,10 @@
 use super::*;
 use crate::os::unix::unixprelude::*;
-use std::{io, net::Shutdown};
+use std::{
+    io::{self, IoSlice},
+    net::Shutdown,
+    time::Duration,
+};
 
 /// Common methods for non-listener Ud-sockets.
 pub trait UdSocket: AsFd {
@@ -29,6 +33,191 @@ pub trait UdSocket: AsFd {
     fn is_nonblocking(&self) -> io::Result<bool> {
         c_wrappers::get_nonblocking(self.as_fd())
     }
+    /// Retrieves and clears the pending error of the socket, returning `None` if there is none.
+    ///
+    /// Errors which occur asynchronously, such as the connection being reset by the peer, are stored by the kernel
+    /// until the next operation on the socket reports them. This allows for checking for such an error without
+    /// performing an operation, e.g. to find out whether a nonblocking `connect` has succeeded. Since the error is
+    /// cleared, the next operation won't report it anymore.
+    ///
+    /// # System calls
+    /// - `getsockopt` (`SO_ERROR`)
+    #[inline]
+    fn take_error(&self) -> io::Result<Option<io::Error>> {
+        c_wrappers::take_error(self.as_fd())
+    }
+    /// Receives data from the socket without blocking, regardless of whether the socket is in nonblocking mode.
+    ///
+    /// Unlike toggling [`set_nonblocking()`](Self::set_nonblocking) around a read, this does not change the state of
+    /// the socket, and thus does not race with other threads using the same socket. If no data is available, a
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) error is returned.
+    ///
+    /// # System calls
+    /// - `recv` (`MSG_DONTWAIT`)
+    #[inline]
+    fn try_recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        c_wrappers::recv(self.as_fd(), buf, libc::MSG_DONTWAIT)
+    }
+    /// Sends data through the socket without blocking, regardless of whether the socket is in nonblocking mode.
+    ///
+    /// If the data cannot be sent without waiting for the receiver to free up buffer space, a
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) error is returned. See [`try_recv()`](Self::try_recv) for the
+    /// advantages over toggling nonblocking mode.
+    ///
+    /// # System calls
+    /// - `sendmsg` (`MSG_DONTWAIT`)
+    #[inline]
+    fn try_send(&self, buf: &[u8]) -> io::Result<usize> {
+        ancwrap::sendmsg(
+            self.as_fd(),
+            &[IoSlice::new(buf)],
+            cmsg::CmsgRef::empty(),
+            None,
+            libc::MSG_DONTWAIT,
+        )
+    }
+    /// Sets the size of the kernel's send buffer for the socket, in bytes.
+    ///
+    /// The kernel is free to adjust the requested value – Linux, for instance, doubles it to leave room for
+    /// bookkeeping overhead and enforces a minimum. Use [`send_buffer_size()`](Self::send_buffer_size) to find out what
+    /// value was actually applied.
+    ///
+    /// # System calls
+    /// - `setsockopt` (`SO_SNDBUF`)
+    #[inline]
+    fn set_send_buffer_size(&self, size: usize) -> io::Result<()> {
+        c_wrappers::set_buffer_size(self.as_fd(), libc::SO_SNDBUF, size)
+    }
+    /// Returns the size of the kernel's send buffer for the socket, in bytes.
+    ///
+    /// The value is returned exactly as reported by the kernel. On Linux, this means that it's double the amount passed
+    /// to [`set_send_buffer_size()`](Self::set_send_buffer_size).
+    ///
+    /// # System calls
+    /// - `getsockopt` (`SO_SNDBUF`)
+    #[inline]
+    fn send_buffer_size(&self) -> io::Result<usize> {
+        c_wrappers::get_buffer_size(self.as_fd(), libc::SO_SNDBUF)
+    }
+    /// Sets the size of the kernel's receive buffer for the socket, in bytes.
+    ///
+    /// As with [`set_send_buffer_size()`](Self::set_send_buffer_size), the kernel may adjust the value.
+    ///
+    /// # System calls
+    /// - `setsockopt` (`SO_RCVBUF`)
+    #[inline]
+    fn set_recv_buffer_size(&self, size: usize) -> io::Result<()> {
+        c_wrappers::set_buffer_size(self.as_fd(), libc::SO_RCVBUF, size)
+    }
+    /// Returns the size of the kernel's receive buffer for the socket, in bytes, exactly as reported by the kernel.
+    ///
+    /// # System calls
+    /// - `getsockopt` (`SO_RCVBUF`)
+    #[inline]
+    fn recv_buffer_size(&self) -> io::Result<usize> {
+        c_wrappers::get_buffer_size(self.as_fd(), libc::SO_RCVBUF)
+    }
+    /// Sets the mark of the socket, which can be used by the firewall and policy routing to classify traffic
+    /// associated with it.
+    ///
+    /// # Errors
+    /// Changing the mark requires the `CAP_NET_ADMIN` capability. Without it, the call fails with a
+    /// [`PermissionDenied`](io::ErrorKind::PermissionDenied) error which carries the raw `EPERM` error code.
+    ///
+    /// # System calls
+    /// - `setsockopt` (`SO_MARK`)
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    fn set_mark(&self, mark: u32) -> io::Result<()> {
+        c_wrappers::set_mark(self.as_fd(), mark)
+    }
+    /// Returns the mark of the socket, which is zero unless set with [`set_mark()`](Self::set_mark). Reading the mark
+    /// doesn't require any special privileges.
+    ///
+    /// # System calls
+    /// - `getsockopt` (`SO_MARK`)
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    fn mark(&self) -> io::Result<u32> {
+        c_wrappers::get_mark(self.as_fd())
+    }
+    /// Sets the timeout for blocking receive operations on the socket. `None` clears the timeout, which makes receive
+    /// operations wait indefinitely.
+    ///
+    /// When the timeout expires, the receive operation fails with [`WouldBlock`](io::ErrorKind::WouldBlock) – the same
+    /// error that is returned when no data is available in nonblocking mode. **A zero [`Duration`] is rejected with an
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) error**, since the kernel would interpret it as the absence of a
+    /// timeout.
+    ///
+    /// Receive operations interrupted by a signal are retried only until the timeout runs out. Since each retry makes
+    /// the kernel wait for the whole timeout again, such an operation can take up to twice as long to time out.
+    ///
+    /// # System calls
+    /// - `setsockopt` (`SO_RCVTIMEO`)
+    #[inline]
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        c_wrappers::set_timeout(self.as_fd(), libc::SO_RCVTIMEO, timeout)
+    }
+    /// Returns the timeout for blocking receive operations on the socket, or `None` if there is none.
+    ///
+    /// # System calls
+    /// - `getsockopt` (`SO_RCVTIMEO`)
+    #[inline]
+    fn read_timeout(&self) -> io::Result<Option<Duration>> {
+        c_wrappers::get_timeout(self.as_fd(), libc::SO_RCVTIMEO)
+    }
+    /// Sets the timeout for blocking send operations on the socket. `None` clears the timeout.
+    ///
+    /// Expiry of the timeout, interruptions by signals and zero durations are handled the same way as with
+    /// [`set_read_timeout()`](Self::set_read_timeout).
+    ///
+    /// # System calls
+    /// - `setsockopt` (`SO_SNDTIMEO`)
+    #[inline]
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        c_wrappers::set_timeout(self.as_fd(), libc::SO_SNDTIMEO, timeout)
+    }
+    /// Returns the timeout for blocking send operations on the socket, or `None` if there is none.
+    ///
+    /// # System calls
+    /// - `getsockopt` (`SO_SNDTIMEO`)
+    #[inline]
+    fn write_timeout(&self) -> io::Result<Option<Duration>> {
+        c_wrappers::get_timeout(self.as_fd(), libc::SO_SNDTIMEO)
+    }
+    /// Returns the path to which the socket on the other end of the connection is bound.
+    ///
+    /// If the peer did not bind its socket to any path (as is the case for client sockets created with `connect`),
+    /// [`UdSocketPath::Unnamed`] is returned instead of an error.
+    ///
+    /// # System calls
+    /// - `getpeername`
+    #[inline]
+    fn peer_addr(&self) -> io::Result<UdSocketPath<'static>> {
+        let (addr, addrlen) = c_wrappers::get_peer_name(self.as_fd())?;
+        UdSocketPath::from_sockaddr_un(&addr, addrlen)
+    }
+    /// Returns the path to which this socket is bound.
+    ///
+    /// Sockets which were never bound to a path, such as client sockets created with `connect`, yield
+    /// [`UdSocketPath::Unnamed`]. On Linux, sockets which were autobound to a name in the abstract namespace yield
+    /// that name as the `Namespaced` variant.
+    ///
+    /// # System calls
+    /// - `getsockname`
+    #[inline]
+    fn local_addr(&self) -> io::Result<UdSocketPath<'static>> {
+        let (addr, addrlen) = c_wrappers::get_socket_name(self.as_fd())?;
+        UdSocketPath::from_sockaddr_un(&addr, addrlen)
+    }
     /// Fetches the credentials of the other end of the connection without using ancillary data. The set of credentials
     /// returned depends on the platform.
     ///
@@ -36,6 +225,11 @@ pub trait UdSocket: AsFd {
     /// The credential tables used are as follows:
     /// - **Linux:** `ucred` (PID, UID, GID)
     /// - **FreeBSD, DragonFly BSD, Apple:** `xucred` (effective UID, up to 16 supplementary groups)
+    /// - **NetBSD:** `unpcbid` via `LOCAL_PEEREID` (PID, effective UID, effective GID)
+    /// - **OpenBSD:** `sockpeercred` via `SO_PEERCRED` (PID, effective UID, effective GID)
+    ///
+    /// # System calls
+    /// - `getsockopt`
     #[cfg_attr(
         feature = "doc_cfg",
         doc(cfg(any(
@@ -45,13 +239,15 @@ pub trait UdSocket: AsFd {
             target_os = "fuchsia",
             target_os = "freebsd",
             target_os = "dragonfly",
+            target_os = "netbsd",
+            target_os = "openbsd",
             target_os = "macos",
             target_os = "ios",
             target_os = "tvos",
             target_os = "watchos",
         )))
     )]
-    #[cfg(any(uds_ucred, uds_xucred))]
+    #[cfg(any(uds_ucred, uds_xucred, uds_unpcbid, uds_sockpeercred))]
     #[inline]
     fn get_peer_credentials(&self) -> io::Result<credentials::Credentials<'static>> {
         use credentials::{Credentials, CredentialsInner};
@@ -65,8 +261,63 @@ pub trait UdSocket: AsFd {
             let xucred = c_wrappers::get_peer_xucred(self.as_fd())?;
             CredentialsInner::Xucred(xucred, std::marker::PhantomData)
         };
+        #[cfg(uds_unpcbid)]
+        let cred = {
+            let unpcbid = c_wrappers::get_peer_unpcbid(self.as_fd())?;
+            CredentialsInner::Unpcbid(unpcbid, std::marker::PhantomData)
+        };
+        #[cfg(uds_sockpeercred)]
+        let cred = {
+            let sockpeercred = c_wrappers::get_peer_sockpeercred(self.as_fd())?;
+            CredentialsInner::Sockpeercred(sockpeercred, std::marker::PhantomData)
+        };
         Ok(Credentials(cred))
     }
+    /// Returns a pidfd referring to the process on the other side of the connection, as of the time when the connection
+    /// was established (or the socket pair was created).
+    ///
+    /// Unlike the PID in the [peer credentials](Self::get_peer_credentials), which may get reused by an unrelated
+    /// process once the peer exits, the pidfd always refers to the exact same process, and can be used to signal it
+    /// with `pidfd_send_signal()` or wait for it to exit via `poll()` without racing against PID reuse. The file
+    /// descriptor has the close-on-exec flag set.
+    ///
+    /// # Errors
+    /// The socket option was added in Linux 6.5. On older kernels, an [`Unsupported`](io::ErrorKind::Unsupported) error
+    /// is returned.
+    ///
+    /// # System calls
+    /// - `getsockopt` (`SO_PEERPIDFD`)
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    fn get_peer_pidfd(&self) -> io::Result<OwnedFd> {
+        c_wrappers::get_peer_pidfd(self.as_fd())
+    }
+    /// Returns the supplementary groups of the process on the other side of the connection, as of the time when the
+    /// connection was established (or the socket pair was created).
+    ///
+    /// The [peer credentials](Self::get_peer_credentials) only contain the primary group ID. This allows for
+    /// authorizing the peer based on group membership without any cooperation on its side, such as sending
+    /// credentials via ancillary data. The primary group is only included if it's also a supplementary group.
+    ///
+    /// # Errors
+    /// The socket option was added in Linux 4.13. On older kernels, an [`Unsupported`](io::ErrorKind::Unsupported)
+    /// error is returned.
+    ///
+    /// # System calls
+    /// - `getsockopt` (`SO_PEERGROUPS`), repeatedly if the peer is a member of many groups
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    fn get_peer_groups(&self) -> io::Result<Vec<gid_t>> {
+        c_wrappers::get_peer_groups(self.as_fd())
+    }
     /// Enables or disables continuous reception of credentials via ancillary data.
     ///
     /// After this option is set to `true`, every ancillary-enabled receive call will return a table of credentials of
@@ -74,6 +325,13 @@ pub trait UdSocket: AsFd {
     ///
     /// Note that this has absolutely no effect on explicit sending of credentials – that can be done regardless of
     /// whether this option is enabled.
+    ///
+    /// The option is disabled by default, and can be turned off again at any time, e.g. once a handshake which
+    /// authenticated the peer is over, to avoid having every receive operation carry a credentials message from then
+    /// on. Messages attached to data which was received while the option was enabled are still delivered.
+    ///
+    /// # System calls
+    /// - `setsockopt` (`SO_PASSCRED` on Linux, `LOCAL_CREDS_PERSISTENT` on FreeBSD)
     #[cfg_attr( // uds_cont_credentials template
         feature = "doc_cfg",
         doc(cfg(any(
@@ -89,6 +347,64 @@ pub trait UdSocket: AsFd {
     fn set_continuous_ancillary_credentials(&self, val: bool) -> io::Result<()> {
         c_wrappers::set_continuous_ancillary_cred(self.as_fd(), val)
     }
+    /// Checks whether continuous reception of credentials via ancillary data is enabled. See
+    /// [`set_continuous_ancillary_credentials()`](Self::set_continuous_ancillary_credentials).
+    ///
+    /// # System calls
+    /// - `getsockopt` (`SO_PASSCRED` on Linux, `LOCAL_CREDS_PERSISTENT` on FreeBSD)
+    #[cfg_attr( // uds_cont_credentials template
+        feature = "doc_cfg",
+        doc(cfg(any(
+            target_os = "linux",
+            target_os = "redox",
+            target_os = "android",
+            target_os = "fuchsia",
+            target_os = "freebsd",
+        )))
+    )]
+    #[cfg(uds_cont_credentials)]
+    #[inline]
+    fn continuous_ancillary_credentials(&self) -> io::Result<bool> {
+        c_wrappers::get_continuous_ancillary_cred(self.as_fd())
+    }
+    /// Enables or disables continuous reception of the security context of the peer via ancillary data.
+    ///
+    /// After this option is set to `true`, every ancillary-enabled receive call will return a
+    /// [`SecurityContext`](cmsg::ancillary::security_context::SecurityContext) message containing the label which the
+    /// active Linux security module, such as SELinux, has assigned to the process on the other side. No such message
+    /// is produced if no security module of the kind is active, which is not an error.
+    ///
+    /// # System calls
+    /// - `setsockopt` (`SO_PASSSEC`)
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    fn set_continuous_ancillary_security(&self, val: bool) -> io::Result<()> {
+        c_wrappers::set_continuous_ancillary_security(self.as_fd(), val)
+    }
+    /// Enables or disables kernel receive timestamps via ancillary data.
+    ///
+    /// After this option is set to `true`, every ancillary-enabled receive call which receives data will also return a
+    /// [`Timestamp`](cmsg::ancillary::timestamp::Timestamp) message with the time at which the kernel queued that data
+    /// for the socket, which allows for measuring latency without the sender having to timestamp the data itself.
+    ///
+    /// Only datagram and sequenced packet sockets are timestamped: Linux never attaches timestamps to data received
+    /// from byte streams, even with the option enabled.
+    ///
+    /// # System calls
+    /// - `setsockopt` (`SO_TIMESTAMPNS`)
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    fn set_receive_timestamps(&self, val: bool) -> io::Result<()> {
+        c_wrappers::set_receive_timestamps(self.as_fd(), val)
+    }
     /// Enables or disables one-time reception of credentials via ancillary data.
     ///
     /// After this option is set to `true`, the next ancillary-enabled receive call will return a table of credentials
@@ -110,6 +426,7 @@ pub trait UdSocket: AsFd {
 
 impl UdSocket for UdStream {}
 impl UdSocket for UdDatagram {}
+impl UdSocket for UdSeqpacket {}
 #[cfg(feature = "tokio")]
 impl UdSocket for super::tokio::UdStream {}
 #[cfg(feature = "tokio")]
@@ -0,0 +1,960 @@
+use super::{
+    ancillary_io::sync::{read_in_terms_of_vectored, write_in_terms_of_vectored},
+    ancwrap, c_wrappers,
+    cmsg::{
+        ancillary::file_descriptors::FileDescriptors,
+        context::{Collector, DummyCollector},
+        Cmsg, CmsgMut, CmsgMutBuf, CmsgMutExt, CmsgRef, CmsgVecBuf,
+    },
+    ReadAncillary, ReadAncillarySuccess, ReadAncillaryWithFlags, ToUdSocketPath, UdSocketPath, WriteAncillary,
+};
+use crate::os::unix::{unixprelude::*, FdOps};
+use libc::{c_uint, SOCK_STREAM};
+use std::{
+    io::{self, IoSlice, IoSliceMut, Read, Write},
+    mem::size_of,
+    os::unix::net::UnixStream as StdUdStream,
+    time::{Duration, Instant},
+};
+
+mod split;
+mod timeout_guard;
+pub use {split::*, timeout_guard::*};
+
+/// A Unix domain socket byte stream, obtained either from [`UdStreamListener`](super::UdStreamListener) or by
+/// connecting to an existing server.
+///
+/// # Examples
+///
+/// ## Basic client
+/// ```no_run
+/// use interprocess::os::unix::udsocket::UdStream;
+/// use std::io::prelude::*;
+///
+/// let mut conn = UdStream::connect("/tmp/example1.sock")?;
+/// conn.write_all(b"Hello from client!")?;
+/// let mut string_buffer = String::new();
+/// conn.read_to_string(&mut string_buffer)?;
+/// println!("Server answered: {}", string_buffer);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+///
+/// # Sharing between threads
+/// [`Read`] and [`Write`] are implemented for `&UdStream`, so a stream can be used by several threads at once by
+/// wrapping it in an [`Arc`](std::sync::Arc) and going through a reference to its contents, as in `(&*stream).read(…)`.
+/// The traits cannot be implemented for `Arc<UdStream>` itself, since neither the traits nor `Arc` are defined in this
+/// crate. [`.into_split()`](Self::into_split) is an alternative which hands out the reading and writing directions to
+/// different owners.
+///
+/// Each individual read or write is a single system call, so concurrent use never corrupts the stream. Nothing larger
+/// than one system call is atomic, however: a `write_all()` may be split into several writes with those of another
+/// thread interleaved between them, and concurrent readers each get an arbitrary portion of the incoming data. Threads
+/// that write whole messages have to serialize with a lock or keep to one writer per stream.
+///
+/// # Ancillary data
+/// The [`Read`] implementation, which performs a plain `read`, is the fastest way to receive data. Only use
+/// [`ReadAncillary`] and the `recv_ancillary…` methods when control messages are expected, since they go through
+/// `recvmsg`, which has to set up a message header and process control data in the kernel even if there isn't any. Any
+/// control messages that do arrive while using `Read` are discarded, with the file descriptors among them closed.
+// TODO update with comments and stuff
+#[derive(Debug)]
+pub struct UdStream(FdOps);
+impl UdStream {
+    /// Connects to a Unix domain socket server at the specified path.
+    ///
+    /// See [`ToUdSocketPath`] for an example of using various string types to specify socket paths.
+    ///
+    /// # System calls
+    /// - `socket`
+    /// - `connect`
+    pub fn connect<'a>(path: impl ToUdSocketPath<'a>) -> io::Result<Self> {
+        Self::_connect(path.to_socket_path()?, false)
+    }
+    /// Connects to a Unix domain socket server at the specified path, giving up with an error of kind
+    /// [`TimedOut`](io::ErrorKind::TimedOut) if the connection could not be established within the given timeout.
+    ///
+    /// Unlike with TCP, connecting to a local server normally completes immediately – this is for the case when the
+    /// server's accept queue is full, where [`connect`](Self::connect) would block until the server accepts enough
+    /// connections to make room. Linux does not report when that happens, so the connection attempt is periodically
+    /// retried there in the meantime.
+    ///
+    /// A zero timeout is rejected with [`InvalidInput`](io::ErrorKind::InvalidInput). The resulting stream is in
+    /// blocking mode.
+    ///
+    /// # System calls
+    /// - `socket`
+    /// - `fcntl`
+    /// - `connect`
+    /// - `poll`
+    /// - `getsockopt`
+    pub fn connect_timeout<'a>(path: impl ToUdSocketPath<'a>, timeout: Duration) -> io::Result<Self> {
+        if timeout.is_zero() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot connect with a zero duration timeout",
+            ));
+        }
+        let (addr, addrlen) = path.to_socket_path()?.to_sockaddr_un()?;
+        let deadline = Instant::now() + timeout;
+        let timed_out = || io::Error::new(io::ErrorKind::TimedOut, "connection timed out");
+
+        let fd = c_wrappers::create_uds(SOCK_STREAM, true)?;
+        let mut backoff = Duration::from_micros(100);
+        loop {
+            let rslt = unsafe {
+                // SAFETY: addr is well-constructed
+                c_wrappers::connect(fd.0.as_fd(), &addr, addrlen)
+            };
+            match rslt {
+                Ok(()) => break,
+                // A retried attempt which went through in the meantime
+                Err(e) if e.raw_os_error() == Some(libc::EISCONN) => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) if e.raw_os_error() == Some(libc::EINPROGRESS) => {
+                    let left = deadline.saturating_duration_since(Instant::now());
+                    if !c_wrappers::wait_writable_timeout(fd.0.as_fd(), left)? {
+                        return Err(timed_out());
+                    }
+                    if let Some(e) = c_wrappers::take_error(fd.0.as_fd())? {
+                        return Err(e);
+                    }
+                    break;
+                }
+                // Linux fails with EAGAIN instead of starting the connection when the accept queue is full.
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    let left = deadline.saturating_duration_since(Instant::now());
+                    if left.is_zero() {
+                        return Err(timed_out());
+                    }
+                    std::thread::sleep(backoff.min(left));
+                    backoff = (backoff * 2).min(Duration::from_millis(10));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        c_wrappers::set_nonblocking(fd.0.as_fd(), false)?;
+
+        Ok(Self(fd))
+    }
+    #[cfg(feature = "tokio")]
+    pub(crate) fn connect_nonblocking<'a>(path: impl ToUdSocketPath<'a>) -> io::Result<Self> {
+        Self::_connect(path.to_socket_path()?, true)
+    }
+    fn _connect(path: UdSocketPath<'_>, nonblocking: bool) -> io::Result<Self> {
+        let (addr, addrlen) = path.to_sockaddr_un()?;
+
+        let fd = c_wrappers::create_uds(SOCK_STREAM, nonblocking)?;
+        unsafe {
+            // SAFETY: addr is well-constructed
+            c_wrappers::connect(fd.0.as_fd(), &addr, addrlen)?;
+        }
+
+        Ok(Self(fd))
+    }
+    /// Creates a pair of unnamed streams which are connected to each other, without involving the filesystem or the
+    /// socket namespace.
+    ///
+    /// This is useful for communicating between threads, or between a parent and a child process which inherits one
+    /// end of the pair. Both ends have the close-on-exec flag set, just like streams created via
+    /// [`connect`](Self::connect).
+    ///
+    /// # System calls
+    /// - `socketpair`
+    pub fn pair() -> io::Result<(Self, Self)> {
+        let (fd1, fd2) = c_wrappers::create_uds_pair(SOCK_STREAM, false)?;
+        Ok((Self(fd1), Self(fd2)))
+    }
+
+    /// Receives bytes from the stream without removing them from the receive queue, so that subsequent reads (or
+    /// peeks) see the same data again.
+    ///
+    /// Use this repeatedly with a growing buffer to inspect more of the queued data. In nonblocking mode, a
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) error is returned if no data is queued.
+    ///
+    /// # System calls
+    /// - `recv` (`MSG_PEEK`)
+    #[inline]
+    pub fn peek(&self, buf: &mut [u8]) -> io::Result<usize> {
+        c_wrappers::recv(self.as_fd(), buf, libc::MSG_PEEK)
+    }
+    /// Same as [`peek()`](Self::peek), but makes use of [scatter input].
+    ///
+    /// # System calls
+    /// - `recvmsg` (`MSG_PEEK`)
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    #[inline]
+    pub fn peek_vectored(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.peek_ancillary_vectored(bufs, &mut CmsgMutBuf::new(&mut []))
+            .map(|s| s.main)
+    }
+    /// Receives bytes and ancillary data from the stream without removing either from the receive queue. This allows
+    /// for examining credentials attached to the data before deciding whether to actually read it.
+    ///
+    /// Note that peeking at file descriptors sent via ancillary data installs them into the process just like a
+    /// regular read does – they have to be closed (which happens automatically if they're decoded) even if the data
+    /// is later read again.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    #[inline]
+    pub fn peek_ancillary<AB: CmsgMut + ?Sized>(
+        &self,
+        buf: &mut [u8],
+        abuf: &mut AB,
+    ) -> io::Result<ReadAncillarySuccess> {
+        self.peek_ancillary_vectored(&mut [IoSliceMut::new(buf)], abuf)
+    }
+    /// Same as [`peek_ancillary()`](Self::peek_ancillary), but makes use of [scatter input].
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    #[inline]
+    pub fn peek_ancillary_vectored<AB: CmsgMut + ?Sized>(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut AB,
+    ) -> io::Result<ReadAncillarySuccess> {
+        ancwrap::recvmsg(self.as_fd(), bufs, abuf, None, libc::MSG_PEEK).map(|r| r.success)
+    }
+
+    /// Receives bytes and ancillary data from the stream, passing the given flags to `recvmsg` and reporting the flags
+    /// returned by it.
+    ///
+    /// This allows for flags such as `MSG_PEEK`, `MSG_WAITALL` or `MSG_DONTWAIT` to be used for a single call.
+    /// [`ancillary_truncated()`](ReadAncillaryWithFlags::ancillary_truncated) on the return value tells whether some
+    /// control messages, and thus possibly some file descriptors, had to be discarded because `abuf` was too small.
+    ///
+    /// Where it's supported (Linux, Android, Fuchsia, FreeBSD, DragonFly BSD, NetBSD and OpenBSD), `MSG_CMSG_CLOEXEC`
+    /// is always added to `flags`, like with every other receive operation of this crate, so that file descriptors
+    /// received via `SCM_RIGHTS` are not leaked into child processes across `exec`. On other platforms, received file
+    /// descriptors have to be marked close-on-exec manually. Use
+    /// [`recv_ancillary_vectored_inheritable()`](Self::recv_ancillary_vectored_inheritable) to opt out.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    #[inline]
+    pub fn recv_ancillary_with_flags<AB: CmsgMut + ?Sized>(
+        &self,
+        buf: &mut [u8],
+        abuf: &mut AB,
+        flags: c_int,
+    ) -> io::Result<ReadAncillaryWithFlags> {
+        self.recv_ancillary_vectored_with_flags(&mut [IoSliceMut::new(buf)], abuf, flags)
+    }
+    /// Same as [`recv_ancillary_with_flags()`](Self::recv_ancillary_with_flags), but makes use of [scatter input].
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    #[inline]
+    pub fn recv_ancillary_vectored_with_flags<AB: CmsgMut + ?Sized>(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut AB,
+        flags: c_int,
+    ) -> io::Result<ReadAncillaryWithFlags> {
+        ancwrap::recvmsg(self.as_fd(), bufs, abuf, None, flags)
+    }
+    /// Same as [`recv_ancillary_vectored_with_flags()`](Self::recv_ancillary_vectored_with_flags), but doesn't add
+    /// `MSG_CMSG_CLOEXEC` to `flags`, which makes received file descriptors inheritable by child processes unless it's
+    /// specified explicitly.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    #[inline]
+    pub fn recv_ancillary_vectored_inheritable<AB: CmsgMut + ?Sized>(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut AB,
+        flags: c_int,
+    ) -> io::Result<ReadAncillaryWithFlags> {
+        ancwrap::recvmsg_inheritable(self.as_fd(), bufs, abuf, None, flags, DummyCollector)
+    }
+    /// Same as [`read_ancillary_vectored()`](ReadAncillary::read_ancillary_vectored), but calls the hooks of the given
+    /// [collector](super::cmsg::context) right before and right after the `recvmsg` call, allowing the state of the
+    /// socket needed to decode the received control messages to be recorded.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    /// - Whatever `collector` performs
+    #[inline]
+    pub fn recv_ancillary_vectored_with_collector<AB: CmsgMut + ?Sized>(
+        &self,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut AB,
+        collector: impl Collector,
+    ) -> io::Result<ReadAncillarySuccess> {
+        ancwrap::recvmsg_with_collector(self.as_fd(), bufs, abuf, None, 0, collector).map(|r| r.success)
+    }
+    /// Same as [`write_ancillary_vectored()`](WriteAncillary::write_ancillary_vectored), but calls the hooks of the
+    /// given [collector](super::cmsg::context) right before and right after the `sendmsg` call.
+    ///
+    /// # System calls
+    /// - `sendmsg`
+    /// - Whatever `collector` performs
+    #[inline]
+    pub fn send_ancillary_vectored_with_collector(
+        &self,
+        bufs: &[IoSlice<'_>],
+        abuf: CmsgRef<'_>,
+        collector: impl Collector,
+    ) -> io::Result<usize> {
+        ancwrap::check_stream_carrier(bufs, abuf)?;
+        ancwrap::sendmsg_with_collector(self.as_fd(), bufs, abuf, None, 0, collector)
+    }
+    /// Receives bytes and ancillary data from the stream, growing `abuf` as needed to fit all of the control messages,
+    /// up to a capacity of `max` bytes.
+    ///
+    /// `abuf` is cleared first. The receive queue is then [peeked at](Self::peek_ancillary) with the current capacity
+    /// of the buffer – or a small default one, if it has none – and every time that reports [truncation], the capacity
+    /// is doubled and the peek is repeated, so that no data is consumed until the buffer is large enough. The actual
+    /// receive operation is performed once the peek fits, or once the capacity has reached `max`, in which case the
+    /// control messages may still be truncated, as reported by the return value. File descriptors installed by the
+    /// peeks are closed right away; only those received by the final operation end up in `abuf`.
+    ///
+    /// The buffer keeps its grown capacity, so reusing it for subsequent calls avoids repeating the peeks. A buffer
+    /// which already has a capacity of `max` or more is used as-is.
+    ///
+    /// # System calls
+    /// - `recvmsg`, repeatedly
+    ///
+    /// [truncation]: ReadAncillaryWithFlags::ancillary_truncated
+    pub fn recv_ancillary_auto(
+        &self,
+        buf: &mut [u8],
+        abuf: &mut CmsgVecBuf,
+        max: usize,
+    ) -> io::Result<ReadAncillaryWithFlags> {
+        /// Enough for a handful of file descriptors or a set of credentials.
+        const INITIAL_CAPACITY: usize = 64;
+
+        // Sized exactly, since Vec::reserve() would overshoot `max` in its amortized growth
+        let resize = |abuf: &mut CmsgVecBuf, capacity| {
+            abuf.clear_and_resize(capacity)
+                .map_err(|e| io::Error::new(io::ErrorKind::OutOfMemory, e.to_string()))
+        };
+
+        abuf.clear();
+        abuf.set_truncation_flag(false);
+        if abuf.capacity() == 0 {
+            resize(abuf, INITIAL_CAPACITY.min(max))?;
+        }
+        loop {
+            let peeked = self.recv_ancillary_with_flags(buf, abuf, libc::MSG_PEEK)?;
+            for msg in abuf.as_ref().decode::<FileDescriptors<'_>>() {
+                // Dropping them closes them – the final receive operation installs the file descriptors anew.
+                drop(msg.map(FileDescriptors::into_owned_fds));
+            }
+            abuf.clear();
+            let capacity = abuf.capacity();
+            if !peeked.ancillary_truncated() || capacity >= max {
+                break;
+            }
+            resize(abuf, capacity.saturating_mul(2).min(max))?;
+        }
+        self.recv_ancillary_with_flags(buf, abuf, 0)
+    }
+
+    /// Checks whether the other end of the connection is still there, without consuming any data or blocking.
+    ///
+    /// A single byte is peeked from the receive queue. The peer is reported to be connected if there's data queued or
+    /// if the operation would block, and disconnected if end of file is reached or the connection has been reset.
+    ///
+    /// # Limitations
+    /// This is a heuristic suited for things like connection pool health checks, not a substitute for handling errors
+    /// on actual reads and writes:
+    /// - A peer which is alive but unresponsive (busy, deadlocked or simply idle) is indistinguishable from a healthy
+    ///   one – only the kernel's view of the connection is consulted.
+    /// - A peer which has only shut down its writing direction is reported as disconnected, even though it may still
+    ///   be reading what this end sends.
+    /// - End of file is only seen once all data queued before it has been read, so a peer which disconnected after
+    ///   sending data is reported as connected until that data is consumed.
+    /// - The result can be outdated by the time it is returned.
+    ///
+    /// # System calls
+    /// - `recv` (`MSG_PEEK | MSG_DONTWAIT`)
+    pub fn is_peer_connected(&self) -> io::Result<bool> {
+        let mut buf = [0];
+        loop {
+            let rslt = c_wrappers::recv(self.as_fd(), &mut buf, libc::MSG_PEEK | libc::MSG_DONTWAIT);
+            return match rslt {
+                Ok(n) => Ok(n != 0),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => Ok(true),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) if matches!(e.kind(), io::ErrorKind::ConnectionReset | io::ErrorKind::NotConnected) => Ok(false),
+                Err(e) => Err(e),
+            };
+        }
+    }
+
+    /// Reads all data until end of file, appending it to `buf` and returning the amount of bytes read.
+    ///
+    /// End of file is reached once the peer has shut down its writing direction, either explicitly via
+    /// [`shutdown(Shutdown::Write)`](super::UdSocket::shutdown) or implicitly by closing the connection, and all data
+    /// sent before that has been read. Both count as a clean end – a peer which has only shut down writing can still
+    /// receive data sent to it afterwards, so this can be used to read a full request before writing the response.
+    ///
+    /// A connection which was torn down abnormally is reported as an error instead: on Linux, that is the case when
+    /// the peer closes its end of the connection without having read all of the data sent to it, which results in a
+    /// [`ConnectionReset`](io::ErrorKind::ConnectionReset) error. Data appended to `buf` before the error was
+    /// encountered is kept. Interrupted reads are retried.
+    ///
+    /// # System calls
+    /// - `read` (repeatedly)
+    #[inline]
+    pub fn read_until_eof(&self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        (&*self).read_to_end(buf)
+    }
+
+    /// Receives data into `buf` along with up to `max_fds` file descriptors passed by the peer, returning the amount of
+    /// bytes received, the file descriptors, and whether any file descriptors had to be discarded.
+    ///
+    /// A control message buffer with room for exactly `max_fds` file descriptors is allocated for the operation. If
+    /// the peer sends more than that, the kernel delivers as many as fit, closes the rest and flags the control data as
+    /// truncated, which is reported via the third element of the tuple – the file descriptors which did arrive are
+    /// still returned, and the data in `buf` is intact. Control messages of other types, such as credentials, are not
+    /// accounted for by the buffer size and are discarded.
+    ///
+    /// This is a shorthand for [`read_ancillary()`](ReadAncillary::read_ancillary) with a
+    /// [`CmsgVecBuf`] followed by decoding of [`FileDescriptors`], for use when file descriptors are the only ancillary
+    /// data in use.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    pub fn recv_with_fds(&self, buf: &mut [u8], max_fds: usize) -> io::Result<(usize, Vec<OwnedFd>, bool)> {
+        let payload_size = max_fds
+            .checked_mul(size_of::<RawFd>())
+            .and_then(|n| c_uint::try_from(n).ok())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "too many file descriptors requested"))?;
+        // Not rounded up to CMSG_SPACE, since the kernel would fill the padding with extra file descriptors.
+        let abuf_size = if max_fds == 0 {
+            0
+        } else {
+            Cmsg::cmsg_len_for_payload_size(payload_size)
+        };
+        let mut abuf = CmsgVecBuf::new(abuf_size);
+        let rslt = ancwrap::recvmsg(self.as_fd(), &mut [IoSliceMut::new(buf)], &mut abuf, None, 0)?;
+
+        let mut fds = Vec::with_capacity(max_fds);
+        for msg in abuf.as_ref().decode::<FileDescriptors<'_>>() {
+            // Received messages always own their file descriptors, and anything else carries no resources.
+            if let Ok(Ok(owned)) = msg.map(FileDescriptors::into_owned_fds) {
+                fds.extend(owned);
+            }
+        }
+        Ok((rslt.success.main, fds, rslt.ancillary_truncated()))
+    }
+    /// Sends the data in `buf` along with the given file descriptors, returning the amount of bytes sent.
+    ///
+    /// The file descriptors are attached to the first byte sent, and are sent in their entirety or not at all. Since
+    /// control messages need data to be attached to, an [`InvalidInput`](io::ErrorKind::InvalidInput) error is
+    /// returned if `fds` is not empty but `buf` is.
+    ///
+    /// # System calls
+    /// - `sendmsg`
+    pub fn send_with_fds(&self, buf: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<usize> {
+        let mut abuf = CmsgVecBuf::new(0);
+        if !fds.is_empty() {
+            abuf.add_message(&FileDescriptors::new(fds));
+        }
+        (&*self).write_ancillary(buf, abuf.as_ref())
+    }
+    /// Sends the entirety of `buf`, attaching the given file descriptors to the first chunk of it.
+    ///
+    /// The file descriptors are sent exactly once, with the first `sendmsg` call, which is repeated if it gets
+    /// interrupted; once any data has gone through, the rest of the buffer is sent as plain data via
+    /// [`write_all()`](Write::write_all). As with [`send_with_fds()`](Self::send_with_fds), an
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) error is returned if `fds` is not empty but `buf` is. If the
+    /// socket refuses to accept any more data, a [`WriteZero`](io::ErrorKind::WriteZero) error is returned; the amount
+    /// of data that was sent is unspecified if any error is returned.
+    ///
+    /// # System calls
+    /// - `sendmsg`
+    /// - `write`, repeatedly (if the first call does not send all of `buf`)
+    pub fn write_all_with_fds(&self, buf: &[u8], fds: &[BorrowedFd<'_>]) -> io::Result<()> {
+        if buf.is_empty() && fds.is_empty() {
+            return Ok(());
+        }
+        let sent = loop {
+            match self.send_with_fds(buf, fds) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "failed to write the first chunk of the buffer",
+                    ))
+                }
+                Ok(sent) => break sent,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            }
+        };
+        (&*self).write_all(&buf[sent..])
+    }
+
+    /// Receives exactly enough data to fill `buf`, collecting the ancillary data that arrives with it into `abuf`.
+    ///
+    /// Receive operations are repeated until `buf` is full, with every one of them appending its control messages to
+    /// those already in `abuf`; the returned [`ReadAncillarySuccess`] holds the totals across all of them. Interrupted
+    /// operations are retried. If the stream reaches end of file before `buf` is filled, an
+    /// [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error is returned, and the contents of both buffers are
+    /// unspecified.
+    ///
+    /// Unlike [`read_exact_with_ancillary()`](super::ReadAncillaryExt::read_exact_with_ancillary), this never resizes
+    /// `abuf` – if it fills up, the kernel discards the control messages which don't fit and sets the truncation flag.
+    ///
+    /// # System calls
+    /// - `recvmsg`, repeatedly
+    pub fn read_exact_ancillary<AB: CmsgMut + ?Sized>(
+        &self,
+        mut buf: &mut [u8],
+        abuf: &mut AB,
+    ) -> io::Result<ReadAncillarySuccess> {
+        let mut total = ReadAncillarySuccess { main: 0, ancillary: 0 };
+        while !buf.is_empty() {
+            match ancwrap::recvmsg(self.as_fd(), &mut [IoSliceMut::new(buf)], abuf, None, 0) {
+                Ok(r) if r.success.main == 0 => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "stream closed before the buffer was filled",
+                    ))
+                }
+                Ok(r) => {
+                    total += r.success;
+                    buf = &mut buf[r.success.main..];
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(total)
+    }
+
+    /// Receives exactly enough data to fill `buf`, giving up with a [`TimedOut`](io::ErrorKind::TimedOut) error if
+    /// that doesn't happen before `deadline`.
+    ///
+    /// Waiting is done with `poll`, with the remaining time recomputed before each wait, and data is only ever
+    /// received when it's already available. As such, the call neither depends on nor changes the
+    /// [receive timeout](super::UdSocket::set_read_timeout) or the nonblocking mode of the socket. Interrupted
+    /// operations are retried.
+    ///
+    /// If the stream reaches end of file before the buffer is filled, an
+    /// [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error is returned. When an error is returned, the data that was
+    /// received up to that point has been consumed from the stream and the contents of `buf` are unspecified, which
+    /// typically means that the connection can no longer be used for framed communication.
+    ///
+    /// # System calls
+    /// - `poll`, repeatedly
+    /// - `recv` (`MSG_DONTWAIT`), repeatedly
+    pub fn recv_exact_with_deadline(&self, mut buf: &mut [u8], deadline: Instant) -> io::Result<()> {
+        while !buf.is_empty() {
+            match c_wrappers::recv(self.as_fd(), buf, libc::MSG_DONTWAIT) {
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        "stream closed before the buffer was filled",
+                    ))
+                }
+                Ok(n) => buf = &mut buf[n..],
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    let left = deadline.saturating_duration_since(Instant::now());
+                    let pfd = c_wrappers::pollfd_for(self.as_fd(), libc::POLLIN);
+                    if left.is_zero() || c_wrappers::poll(&mut [pfd], Some(left))? == 0 {
+                        return Err(io::Error::new(
+                            io::ErrorKind::TimedOut,
+                            "deadline passed before the buffer was filled",
+                        ));
+                    }
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Receives exactly enough data to fill all of the given buffers, in order, making use of [scatter input].
+    ///
+    /// Interrupted operations are retried. If the stream reaches end of file before all buffers are filled, an
+    /// [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error is returned, and the contents of the buffers are
+    /// unspecified.
+    ///
+    /// # System calls
+    /// - `readv`, repeatedly
+    /// - `read` (if a `readv` call stops in the middle of a buffer)
+    ///
+    /// [scatter input]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    #[inline]
+    pub fn read_vectored_exact(&self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<()> {
+        self.0.read_vectored_exact(bufs)
+    }
+    /// Sends all of the given buffers, in order, making use of [gather output], the counterpart of
+    /// [`read_vectored_exact()`](Self::read_vectored_exact).
+    ///
+    /// Short writes are continued from where they stopped, including in the middle of a buffer, and interrupted
+    /// operations are retried. If the socket refuses to accept any more data, a
+    /// [`WriteZero`](io::ErrorKind::WriteZero) error is returned. The slice of buffers is taken by mutable reference
+    /// to match the standard library's `write_all_vectored()`; its contents after the call are unspecified, and so is
+    /// the amount of data that was sent if an error is returned.
+    ///
+    /// # System calls
+    /// - `writev`, repeatedly
+    /// - `write` (if a `writev` call stops in the middle of a buffer)
+    ///
+    /// [gather output]: https://en.wikipedia.org/wiki/Vectored_I/O " "
+    #[inline]
+    pub fn send_all_vectored(&self, bufs: &mut [IoSlice<'_>]) -> io::Result<()> {
+        self.0.write_vectored_all(bufs)
+    }
+
+    /// Moves up to `len` bytes received by this stream into `dst`, returning the amount of bytes moved.
+    ///
+    /// On Linux, this is done without copying the data to userspace, which makes it well-suited for relaying data
+    /// between two connections. Elsewhere, the data is moved through a small stack buffer, which means that less than
+    /// `len` bytes may be moved even if more are available.
+    ///
+    /// Whether the call waits for data to arrive is governed by the nonblocking mode of `self` – in nonblocking mode, a
+    /// [`WouldBlock`](io::ErrorKind::WouldBlock) error is returned if no data is available. **Once data has been taken
+    /// from `self`, however, the call waits until all of it has been written into `dst`, even if `dst` is in
+    /// nonblocking mode**, since the data would otherwise be lost.
+    ///
+    /// # Data loss on errors
+    /// If writing into `dst` fails after data has been taken from `self`, the error is returned and **the data that was
+    /// taken but not yet written is lost** – it is no longer in the receive buffer of `self`, and the intermediate
+    /// pipe (or stack buffer) it was held in is discarded. The amount of data lost is not reported. The same goes for
+    /// `dst` accepting no more data, which is reported as a [`WriteZero`](io::ErrorKind::WriteZero) error. An error
+    /// returned by this method should thus be treated as fatal for the relay.
+    ///
+    /// # System calls
+    /// - **Linux:**
+    ///     - `pipe2`
+    ///     - `splice`
+    ///     - `poll` (if `dst` is in nonblocking mode and cannot fit the data right away)
+    /// - **Elsewhere:**
+    ///     - `read`
+    ///     - `write`
+    ///     - `poll` (same as above)
+    pub fn splice_to(&self, dst: &UdStream, len: usize) -> io::Result<usize> {
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        {
+            let (pipe_r, pipe_w) = c_wrappers::create_splice_pipe()?;
+            let taken = c_wrappers::splice(self.as_fd(), pipe_w.as_fd(), len)?;
+            let mut left = taken;
+            while left > 0 {
+                match c_wrappers::splice(pipe_r.as_fd(), dst.as_fd(), left) {
+                    Ok(0) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::WriteZero,
+                            "destination accepted only part of the spliced data",
+                        ))
+                    }
+                    Ok(n) => left -= n,
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => c_wrappers::wait_writable(dst.as_fd())?,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(taken - left)
+        }
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        {
+            let mut buf = [0; 8192];
+            let taken = (&*self).read(&mut buf[..len.min(8192)])?;
+            let mut written = 0;
+            while written < taken {
+                match (&*dst).write(&buf[written..taken]) {
+                    Ok(0) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::WriteZero,
+                            "destination accepted only part of the spliced data",
+                        ))
+                    }
+                    Ok(n) => written += n,
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => c_wrappers::wait_writable(dst.as_fd())?,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            Ok(written)
+        }
+    }
+
+    /// Returns how much of the data sent through the socket is still sitting in the kernel, waiting to be read by the
+    /// peer. Does not block or wait for the data to be received.
+    ///
+    /// [`flush()`](Write::flush) does nothing on sockets, since data is handed to the kernel as soon as it is written.
+    /// This can be polled instead to find out whether everything sent has actually been picked up by the other side,
+    /// e.g. before closing the connection in a way which would discard unread data.
+    ///
+    /// The figure is in terms of kernel memory usage rather than payload size, and thus also includes bookkeeping
+    /// overhead – all that can be relied upon is that it is zero once the peer has read everything.
+    ///
+    /// # System calls
+    /// - `ioctl` (`SIOCOUTQ`)
+    #[cfg_attr( // uds_linux_namespace template
+        feature = "doc_cfg",
+        doc(cfg(any(target_os = "linux", target_os = "android")))
+    )]
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    pub fn drain_send_buffer(&self) -> io::Result<usize> {
+        c_wrappers::send_queue_len(self.as_fd())
+    }
+
+    /// Sets or clears the close-on-exec flag of the file descriptor, which determines whether the socket is inherited
+    /// by programs started with `exec`.
+    ///
+    /// Sockets created by this crate always have the flag set, but those adopted via `From<OwnedFd>` have whatever
+    /// state the file descriptor came with – one received from FFI code or inherited from a parent process might not
+    /// have it set, which would leak the socket into child processes.
+    ///
+    /// # System calls
+    /// - `fcntl` (`F_GETFD`)
+    /// - `fcntl` (`F_SETFD`, only if the flag has to be changed)
+    #[inline]
+    pub fn set_cloexec(&self, cloexec: bool) -> io::Result<()> {
+        self.0.set_cloexec(cloexec)
+    }
+    /// Checks whether the close-on-exec flag of the file descriptor is set.
+    ///
+    /// # System calls
+    /// - `fcntl` (`F_GETFD`)
+    #[inline]
+    pub fn get_cloexec(&self) -> io::Result<bool> {
+        self.0.get_cloexec()
+    }
+    /// Duplicates the socket onto the file descriptor number `target`, with the close-on-exec flag cleared, so that it
+    /// survives `exec` at a number known in advance.
+    ///
+    /// This is how sockets are handed to programs that expect them at a fixed position, such as inetd-style servers
+    /// which use their standard input and output as the connection, or ones started via socket activation, which
+    /// expect the sockets from file descriptor 3 onwards. The stream itself is left intact – typically, the duplicate
+    /// is made in a child process before `exec`, e.g. in
+    /// [`pre_exec()`](std::os::unix::process::CommandExt::pre_exec). See
+    /// [`dup_to_with_cloexec()`](Self::dup_to_with_cloexec) to keep the flag set instead.
+    ///
+    /// **Whatever `target` referred to is silently closed.** It must therefore not be owned by anything else in the
+    /// process, such as an [`OwnedFd`], which would otherwise end up operating on the socket instead or closing it from
+    /// under the duplicate. If `target` is the file descriptor of the stream itself, nothing is done – not even the
+    /// close-on-exec flag is changed, which [`set_cloexec()`](Self::set_cloexec) is for.
+    ///
+    /// # System calls
+    /// - `dup3` on Linux and Android
+    /// - `dup2` elsewhere
+    #[inline]
+    pub fn dup_to(&self, target: RawFd) -> io::Result<()> {
+        self.dup_to_with_cloexec(target, false)
+    }
+    /// Same as [`dup_to()`](Self::dup_to), but sets the close-on-exec flag of `target` to `cloexec` instead of always
+    /// clearing it. Where available, `dup3` is used to do so atomically.
+    ///
+    /// # System calls
+    /// - `dup3` on Linux and Android
+    /// - `dup2` elsewhere, followed by `fcntl` if `cloexec` is `true`
+    #[inline]
+    pub fn dup_to_with_cloexec(&self, target: RawFd, cloexec: bool) -> io::Result<()> {
+        c_wrappers::dup_to(self.as_fd(), target, cloexec)
+    }
+}
+
+/// A list of used system calls is available.
+impl Read for &UdStream {
+    /// # System calls
+    /// - `read`
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&self.0).read(buf)
+    }
+    /// # System calls
+    /// - `readv`
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        (&self.0).read_vectored(bufs)
+    }
+}
+/// A list of used system calls is available.
+impl Read for UdStream {
+    /// # System calls
+    /// - `read`
+    #[inline(always)]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        (&*self).read(buf)
+    }
+    /// # System calls
+    /// - `readv`
+    #[inline(always)]
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        (&*self).read_vectored(bufs)
+    }
+}
+
+/// A list of used system calls is available.
+impl<AB: CmsgMut + ?Sized> ReadAncillary<AB> for &UdStream {
+    /// Implemented in terms of `read_ancillary_vectored`.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    #[inline]
+    fn read_ancillary(&mut self, buf: &mut [u8], abuf: &mut AB) -> io::Result<ReadAncillarySuccess> {
+        read_in_terms_of_vectored(self, buf, abuf)
+    }
+    /// # System calls
+    /// - `recvmsg`
+    #[inline]
+    fn read_ancillary_vectored(
+        &mut self,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut AB,
+    ) -> io::Result<ReadAncillarySuccess> {
+        ancwrap::recvmsg(self.as_fd(), bufs, abuf, None, 0).map(|r| r.success)
+    }
+}
+/// A list of used system calls is available.
+impl<AB: CmsgMut + ?Sized> ReadAncillary<AB> for UdStream {
+    /// Implemented in terms of `read_ancillary_vectored()`.
+    ///
+    /// # System calls
+    /// - `recvmsg`
+    #[inline(always)]
+    fn read_ancillary(&mut self, buf: &mut [u8], abuf: &mut AB) -> io::Result<ReadAncillarySuccess> {
+        (&*self).read_ancillary(buf, abuf)
+    }
+    /// # System calls
+    /// - `recvmsg`
+    #[inline(always)]
+    fn read_ancillary_vectored(
+        &mut self,
+        bufs: &mut [IoSliceMut<'_>],
+        abuf: &mut AB,
+    ) -> io::Result<ReadAncillarySuccess> {
+        (&*self).read_ancillary_vectored(bufs, abuf)
+    }
+}
+
+/// A list of used system calls is available.
+impl Write for &UdStream {
+    /// # System calls
+    /// - `write`
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&self.0).write(buf)
+    }
+    /// # System calls
+    /// - `writev`
+    #[inline]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        (&self.0).write_vectored(bufs)
+    }
+    /// # System calls
+    /// None performed.
+    #[inline(always)]
+    fn flush(&mut self) -> io::Result<()> {
+        // You cannot flush a socket
+        Ok(())
+    }
+}
+/// A list of used system calls is available.
+impl Write for UdStream {
+    /// # System calls
+    /// - `write`
+    #[inline(always)]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (&*self).write(buf)
+    }
+    /// # System calls
+    /// - `writev`
+    #[inline(always)]
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        (&*self).write_vectored(bufs)
+    }
+    /// # System calls
+    /// None performed.
+    #[inline(always)]
+    fn flush(&mut self) -> io::Result<()> {
+        // You cannot flush a socket
+        Ok(())
+    }
+}
+
+/// A list of used system calls is available.
+impl WriteAncillary for &UdStream {
+    /// Implemented in terms of `write_ancillary_vectored()`.
+    ///
+    /// # System calls
+    /// - `sendmsg`
+    #[inline]
+    fn write_ancillary(&mut self, buf: &[u8], abuf: CmsgRef<'_>) -> io::Result<usize> {
+        write_in_terms_of_vectored(self, buf, abuf)
+    }
+    /// # System calls
+    /// - `sendmsg`
+    #[inline]
+    fn write_ancillary_vectored(&mut self, bufs: &[IoSlice<'_>], abuf: CmsgRef<'_>) -> io::Result<usize> {
+        ancwrap::check_stream_carrier(bufs, abuf)?;
+        ancwrap::sendmsg(self.as_fd(), bufs, abuf, None, 0)
+    }
+}
+/// A list of used system calls is available.
+impl WriteAncillary for UdStream {
+    /// Implemented in terms of `write_ancillary_vectored()`.
+    ///
+    /// # System calls
+    /// - `sendmsg`
+    #[inline(always)]
+    fn write_ancillary(&mut self, buf: &[u8], abuf: CmsgRef<'_>) -> io::Result<usize> {
+        (&*self).write_ancillary(buf, abuf)
+    }
+    /// # System calls
+    /// - `sendmsg`
+    #[inline(always)]
+    fn write_ancillary_vectored(&mut self, bufs: &[IoSlice<'_>], abuf: CmsgRef<'_>) -> io::Result<usize> {
+        (&*self).write_ancillary_vectored(bufs, abuf)
+    }
+}
+
+impl AsFd for UdStream {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0 .0.as_fd()
+    }
+}
+impl From<UdStream> for OwnedFd {
+    #[inline]
+    fn from(x: UdStream) -> Self {
+        x.0 .0
+    }
+}
+impl From<OwnedFd> for UdStream {
+    #[inline]
+    fn from(fd: OwnedFd) -> Self {
+        UdStream(FdOps(fd))
+    }
+}
+/// Converts into the standard library's Unix domain stream without any system calls, by means of transferring
+/// ownership of the file descriptor.
+///
+/// Socket options, such as whether credentials are passed as ancillary data, are a property of the socket itself and
+/// are thus kept, but the standard library type does not provide any way of receiving ancillary data (or querying
+/// most of those options to begin with).
+impl From<UdStream> for StdUdStream {
+    #[inline]
+    fn from(x: UdStream) -> Self {
+        OwnedFd::from(x).into()
+    }
+}
+/// Adopts the file descriptor of the standard library's Unix domain stream, keeping all state associated with the
+/// socket (nonblocking mode included).
+impl From<StdUdStream> for UdStream {
+    #[inline]
+    fn from(x: StdUdStream) -> Self {
+        OwnedFd::from(x).into()
+    }
+}
+
+// TODO use the forwarding macros
+multimacro! {
+    UdStream,
+    forward_try_clone,
+    derive_raw(unix),
+}
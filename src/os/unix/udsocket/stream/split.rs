@@ -0,0 +1,192 @@
+use super::UdStream;
+use crate::os::unix::{
+    udsocket::{
+        c_wrappers,
+        cmsg::{CmsgMut, CmsgRef},
+        ReadAncillary, ReadAncillarySuccess, WriteAncillary,
+    },
+    unixprelude::*,
+};
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    io::{self, IoSlice, IoSliceMut, Read, Write},
+    net::Shutdown,
+    sync::Arc,
+};
+
+/// Borrowed read half of a [`UdStream`], created by [`.split()`](UdStream::split).
+#[derive(Copy, Clone, Debug)]
+pub struct ReadHalf<'a>(&'a UdStream);
+/// Borrowed write half of a [`UdStream`], created by [`.split()`](UdStream::split).
+#[derive(Copy, Clone, Debug)]
+pub struct WriteHalf<'a>(&'a UdStream);
+
+/// Owned read half of a [`UdStream`], created by [`.into_split()`](UdStream::into_split).
+#[derive(Debug)]
+pub struct OwnedReadHalf(Arc<UdStream>);
+/// Owned write half of a [`UdStream`], created by [`.into_split()`](UdStream::into_split).
+#[derive(Debug)]
+pub struct OwnedWriteHalf(Arc<UdStream>);
+
+impl UdStream {
+    /// Splits the stream into a read half and a write half which borrow from it, allowing the two directions to be
+    /// handed to different threads in a scope.
+    ///
+    /// Since `UdStream` can be read from and written to by reference, this is simply a way of restricting each handle
+    /// to one direction. Shutting down the write half with [`.shutdown()`](WriteHalf::shutdown) only shuts down the
+    /// writing direction of the stream.
+    #[inline]
+    pub fn split(&self) -> (ReadHalf<'_>, WriteHalf<'_>) {
+        (ReadHalf(self), WriteHalf(self))
+    }
+    /// Splits the stream into an owned read half and an owned write half, which can be moved to different threads
+    /// independently, entailing a memory allocation. Use [`OwnedReadHalf::reunite()`] to recover the stream.
+    ///
+    /// Dropping the halves does not shut down the stream – the connection is closed once both of them are dropped.
+    pub fn into_split(self) -> (OwnedReadHalf, OwnedWriteHalf) {
+        let stream = Arc::new(self);
+        (OwnedReadHalf(Arc::clone(&stream)), OwnedWriteHalf(stream))
+    }
+}
+
+impl OwnedReadHalf {
+    /// Attempts to put two owned halves of a stream back together and recover the original stream. Succeeds only if
+    /// the two halves originated from the same call to [`.into_split()`](UdStream::into_split).
+    pub fn reunite(self, write: OwnedWriteHalf) -> Result<UdStream, ReuniteError> {
+        if !Arc::ptr_eq(&self.0, &write.0) {
+            return Err(ReuniteError(self, write));
+        }
+        drop(write);
+        // The other clone of the Arc has just been dropped, and halves cannot be cloned.
+        Ok(Arc::try_unwrap(self.0).expect("owned halves of a stream must not be cloned"))
+    }
+}
+
+macro_rules! write_half_shutdown {
+    ($ty:ty) => {
+        impl $ty {
+            /// Shuts down the writing direction of the stream, signalling end of file to the other side.
+            ///
+            /// Attempting to call this method multiple times may return `Ok(())` every time or it may return an error
+            /// the second time it is called, depending on the platform. You must either avoid using the same value
+            /// twice or ignore the error entirely.
+            ///
+            /// # System calls
+            /// - `shutdown`
+            #[inline]
+            pub fn shutdown(&self) -> io::Result<()> {
+                c_wrappers::shutdown(self.0.as_fd(), Shutdown::Write)
+            }
+        }
+    };
+}
+write_half_shutdown!(WriteHalf<'_>);
+write_half_shutdown!(OwnedWriteHalf);
+
+macro_rules! read_half_impls {
+    ($ty:ty) => {
+        /// A list of used system calls is available.
+        impl Read for $ty {
+            /// # System calls
+            /// - `read`
+            #[inline(always)]
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                (&*self.0).read(buf)
+            }
+            /// # System calls
+            /// - `readv`
+            #[inline(always)]
+            fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+                (&*self.0).read_vectored(bufs)
+            }
+        }
+        /// A list of used system calls is available.
+        impl<AB: CmsgMut + ?Sized> ReadAncillary<AB> for $ty {
+            /// # System calls
+            /// - `recvmsg`
+            #[inline(always)]
+            fn read_ancillary(&mut self, buf: &mut [u8], abuf: &mut AB) -> io::Result<ReadAncillarySuccess> {
+                (&*self.0).read_ancillary(buf, abuf)
+            }
+            /// # System calls
+            /// - `recvmsg`
+            #[inline(always)]
+            fn read_ancillary_vectored(
+                &mut self,
+                bufs: &mut [IoSliceMut<'_>],
+                abuf: &mut AB,
+            ) -> io::Result<ReadAncillarySuccess> {
+                (&*self.0).read_ancillary_vectored(bufs, abuf)
+            }
+        }
+        impl AsFd for $ty {
+            #[inline(always)]
+            fn as_fd(&self) -> BorrowedFd<'_> {
+                self.0.as_fd()
+            }
+        }
+    };
+}
+read_half_impls!(ReadHalf<'_>);
+read_half_impls!(OwnedReadHalf);
+
+macro_rules! write_half_impls {
+    ($ty:ty) => {
+        /// A list of used system calls is available.
+        impl Write for $ty {
+            /// # System calls
+            /// - `write`
+            #[inline(always)]
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                (&*self.0).write(buf)
+            }
+            /// # System calls
+            /// - `writev`
+            #[inline(always)]
+            fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+                (&*self.0).write_vectored(bufs)
+            }
+            /// # System calls
+            /// None performed.
+            #[inline(always)]
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        /// A list of used system calls is available.
+        impl WriteAncillary for $ty {
+            /// # System calls
+            /// - `sendmsg`
+            #[inline(always)]
+            fn write_ancillary(&mut self, buf: &[u8], abuf: CmsgRef<'_>) -> io::Result<usize> {
+                (&*self.0).write_ancillary(buf, abuf)
+            }
+            /// # System calls
+            /// - `sendmsg`
+            #[inline(always)]
+            fn write_ancillary_vectored(&mut self, bufs: &[IoSlice<'_>], abuf: CmsgRef<'_>) -> io::Result<usize> {
+                (&*self.0).write_ancillary_vectored(bufs, abuf)
+            }
+        }
+        impl AsFd for $ty {
+            #[inline(always)]
+            fn as_fd(&self) -> BorrowedFd<'_> {
+                self.0.as_fd()
+            }
+        }
+    };
+}
+write_half_impls!(WriteHalf<'_>);
+write_half_impls!(OwnedWriteHalf);
+
+/// Error indicating that an owned read half and an owned write half were not from the same stream, and thus could not
+/// be reunited. Contains both halves, so that they can be recovered.
+#[derive(Debug)]
+pub struct ReuniteError(pub OwnedReadHalf, pub OwnedWriteHalf);
+impl Error for ReuniteError {}
+impl Display for ReuniteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("tried to reunite halves of different streams")
+    }
+}
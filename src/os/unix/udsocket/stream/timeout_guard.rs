@@ -0,0 +1,72 @@
+use super::UdStream;
+use crate::os::unix::udsocket::UdSocket;
+use std::{io, ops::Deref, time::Duration};
+
+impl UdStream {
+    /// Sets the [receive timeout](UdSocket::set_read_timeout) of the stream for as long as the returned guard is alive,
+    /// restoring the previous one when the guard is dropped.
+    ///
+    /// This prevents a timeout meant for one exchange from leaking into later ones when the code in between returns
+    /// early. The guard dereferences to the stream, so it can be read from directly. Note that the timeout is a
+    /// property of the socket rather than of the guard: it applies to all reads on the stream while the guard is alive,
+    /// including ones through clones of the file descriptor.
+    ///
+    /// A zero `timeout` is rejected with an [`InvalidInput`](io::ErrorKind::InvalidInput) error, as with
+    /// `set_read_timeout()`.
+    ///
+    /// # System calls
+    /// - `getsockopt` (`SO_RCVTIMEO`)
+    /// - `setsockopt` (`SO_RCVTIMEO`)
+    /// - `setsockopt` (`SO_RCVTIMEO`, when the guard is dropped)
+    pub fn with_read_timeout(&self, timeout: Duration) -> io::Result<TimeoutGuard<'_>> {
+        let prev = self.read_timeout()?;
+        self.set_read_timeout(Some(timeout))?;
+        Ok(TimeoutGuard {
+            stream: self,
+            prev,
+            restored: false,
+        })
+    }
+}
+
+/// Guard that restores the previous receive timeout of a [`UdStream`] when dropped, created by
+/// [`.with_read_timeout()`](UdStream::with_read_timeout).
+///
+/// Errors that occur while restoring the timeout on drop are ignored; use [`.restore()`](Self::restore) to handle
+/// them.
+#[derive(Debug)]
+pub struct TimeoutGuard<'a> {
+    stream: &'a UdStream,
+    prev: Option<Duration>,
+    restored: bool,
+}
+impl<'a> TimeoutGuard<'a> {
+    /// Restores the previous receive timeout right away, reporting errors instead of ignoring them.
+    ///
+    /// # System calls
+    /// - `setsockopt` (`SO_RCVTIMEO`)
+    pub fn restore(mut self) -> io::Result<&'a UdStream> {
+        self.restored = true;
+        self.stream.set_read_timeout(self.prev)?;
+        Ok(self.stream)
+    }
+    /// Returns the receive timeout that will be restored.
+    #[inline]
+    pub fn previous_timeout(&self) -> Option<Duration> {
+        self.prev
+    }
+}
+impl Deref for TimeoutGuard<'_> {
+    type Target = UdStream;
+    #[inline]
+    fn deref(&self) -> &UdStream {
+        self.stream
+    }
+}
+impl Drop for TimeoutGuard<'_> {
+    fn drop(&mut self) {
+        if !self.restored {
+            let _ = self.stream.set_read_timeout(self.prev);
+        }
+    }
+}
@@ -1,7 +1,13 @@
 use crate::os::unix::udsocket::{
-    tokio::UdStream, ToUdSocketPath, UdSocketPath, UdStreamListener as SyncUdStreamListener,
+    tokio::UdStream, ToUdSocketPath, UdSocketPath, UdStreamListener as SyncUdStreamListener, UdStreamListenerBuilder,
+};
+use futures_core::{FusedStream, Stream};
+use std::{
+    io,
+    os::unix::net::UnixListener as StdUdStreamListener,
+    pin::Pin,
+    task::{Context, Poll},
 };
-use std::{io, os::unix::net::UnixListener as StdUdStreamListener};
 use tokio::net::UnixListener as TokioUdStreamListener;
 
 /// A Tokio-based Unix domain byte stream socket server, listening for connections.
@@ -115,13 +121,58 @@ impl UdStreamListener {
         Self::_bind(path.to_socket_path()?)
     }
     fn _bind(path: UdSocketPath<'_>) -> io::Result<Self> {
-        let listener = SyncUdStreamListener::_bind(path, false, true)?;
+        let listener = UdStreamListenerBuilder::new().nonblocking(true)._bind(path)?;
         Self::try_from(listener).map_err(Into::into)
     }
     /// Listens for incoming connections to the socket, asynchronously waiting a client is connected.
+    ///
+    /// While there are no clients to accept, the returned future waits for the reactor to report the listener as
+    /// readable instead of retrying right away.
     pub async fn accept(&self) -> io::Result<UdStream> {
         Ok(self.0.accept().await?.0.into())
     }
+    /// Raw polling interface for accepting connections. You probably want `.accept()` instead.
+    #[inline]
+    pub fn poll_accept(&self, cx: &mut Context<'_>) -> Poll<io::Result<UdStream>> {
+        self.0.poll_accept(cx).map_ok(|(stream, _)| stream.into())
+    }
+    /// Creates an infinite asynchronous stream which accepts connections, i.e. calls
+    /// [`.poll_accept()`](Self::poll_accept) whenever it's polled for the next element. The stream never yields
+    /// `None`.
+    ///
+    /// This is the async counterpart of
+    /// [`UdStreamListener::incoming()`](super::super::UdStreamListener::incoming) from the blocking API.
+    #[inline]
+    pub fn incoming(&self) -> Incoming<'_> {
+        Incoming { listener: self }
+    }
+}
+
+/// An infinite asynchronous stream over incoming client connections of a [`UdStreamListener`].
+///
+/// This stream is created by the [`incoming()`](UdStreamListener::incoming) method on `UdStreamListener` – see its
+/// documentation for more.
+#[derive(Debug)]
+pub struct Incoming<'a> {
+    listener: &'a UdStreamListener,
+}
+impl Stream for Incoming<'_> {
+    type Item = io::Result<UdStream>;
+    #[inline]
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.listener.poll_accept(cx).map(Some)
+    }
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+impl FusedStream for Incoming<'_> {
+    /// Always `false`, as the stream is infinite.
+    #[inline]
+    fn is_terminated(&self) -> bool {
+        false
+    }
 }
 tokio_wrapper_trait_impls!(
     for UdStreamListener,
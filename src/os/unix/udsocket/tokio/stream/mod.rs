@@ -1,15 +1,19 @@
-use crate::os::unix::udsocket::{
-    ancwrap, c_wrappers,
-    cmsg::{CmsgMut, CmsgMutBuf, CmsgRef},
-    poll::{read_in_terms_of_vectored, write_in_terms_of_vectored},
-    AsyncReadAncillary, AsyncWriteAncillary, ReadAncillarySuccess, ToUdSocketPath, UdSocket, UdSocketPath,
-    UdStream as SyncUdStream,
+use crate::{
+    error::ConversionError,
+    os::unix::udsocket::{
+        ancwrap, c_wrappers,
+        cmsg::{CmsgMut, CmsgRef},
+        poll::{read_in_terms_of_vectored, write_in_terms_of_vectored},
+        AsyncReadAncillary, AsyncWriteAncillary, ReadAncillarySuccess, ToUdSocketPath, UdSocket, UdSocketPath,
+        UdStream as SyncUdStream,
+    },
 };
 use futures_core::ready;
 use futures_io::{AsyncRead, AsyncWrite};
 use std::{
     error::Error,
     fmt::{self, Formatter},
+    future::poll_fn,
     io,
     net::Shutdown,
     os::{fd::AsFd, unix::net::UnixStream as StdUdStream},
@@ -17,7 +21,7 @@ use std::{
     task::{Context, Poll},
 };
 use tokio::{
-    io::{AsyncRead as TokioAsyncRead, AsyncWrite as TokioAsyncWrite, ReadBuf as TokioReadBuf},
+    io::{AsyncRead as TokioAsyncRead, AsyncWrite as TokioAsyncWrite, Interest, ReadBuf as TokioReadBuf},
     net::{unix::ReuniteError as TokioReuniteError, UnixStream as TokioUdStream},
 };
 
@@ -88,7 +92,91 @@ impl UdStream {
     }
     async fn _connect(path: &UdSocketPath<'_>) -> io::Result<Self> {
         let stream = ConnectFuture { path }.await?;
-        Self::try_from(stream).map_err(|e| e.cause.unwrap())
+        Self::try_from(stream).map_err(|e| e.into_cause().unwrap())
+    }
+
+    /// Registers a blocking stream in the Tokio runtime this function is called in, switching it to nonblocking mode.
+    ///
+    /// Unlike the `TryFrom<UdStream>` implementation for this type, which expects the stream to already be in
+    /// nonblocking mode, this can be used on streams fresh out of a blocking constructor.
+    ///
+    /// # Errors
+    /// Returns an error, together with the original stream, if called outside of a Tokio runtime or if either the mode
+    /// switch or registration with the reactor fails.
+    pub fn from_std(stream: SyncUdStream) -> Result<Self, ConversionError<SyncUdStream>> {
+        if let Err(e) = stream.set_nonblocking(true) {
+            return Err(ConversionError::from_source_and_cause(stream, e));
+        }
+        Self::try_from(stream)
+    }
+    /// Detaches the stream from the Tokio runtime and converts it into a blocking one.
+    ///
+    /// The returned stream is still in nonblocking mode; use [`.set_nonblocking(false)`](UdSocket::set_nonblocking)
+    /// on it if blocking behavior is desired. This is the same as the `TryFrom<UdStream>` implementation for the
+    /// blocking stream type.
+    ///
+    /// # Errors
+    /// Returns an error, together with the original stream, if called outside of a Tokio runtime.
+    #[inline]
+    pub fn into_std(self) -> Result<SyncUdStream, ConversionError<Self>> {
+        SyncUdStream::try_from(self)
+    }
+
+    /// Receives bytes and ancillary data from the socket stream, making use of [`recvmsg`]. When the stream has no data
+    /// available, the returned future waits for the reactor to report readability and retries.
+    ///
+    /// This is a shorthand for
+    /// [`AsyncReadAncillaryExt::read_ancillary()`](super::super::AsyncReadAncillaryExt::read_ancillary) which doesn't
+    /// require `&mut self` or pinning.
+    ///
+    /// [`recvmsg`]: https://man7.org/linux/man-pages/man2/recvmsg.2.html
+    #[inline]
+    pub async fn recv_ancillary(&self, buf: &mut [u8], abuf: &mut impl CmsgMut) -> io::Result<ReadAncillarySuccess> {
+        self.recv_ancillary_vectored(&mut [io::IoSliceMut::new(buf)], abuf)
+            .await
+    }
+    /// Same as [`.recv_ancillary()`](Self::recv_ancillary), but performs a
+    /// [scatter read](https://en.wikipedia.org/wiki/Vectored_I%2FO) instead.
+    #[inline]
+    pub async fn recv_ancillary_vectored(
+        &self,
+        bufs: &mut [io::IoSliceMut<'_>],
+        abuf: &mut impl CmsgMut,
+    ) -> io::Result<ReadAncillarySuccess> {
+        poll_fn(|cx| poll_read_ancvec_ref(&self.0, cx, bufs, abuf)).await
+    }
+    /// Raw polling interface for receiving bytes and ancillary data. You probably want `.recv_ancillary()` instead.
+    #[inline]
+    pub fn poll_recv_ancillary(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+        abuf: &mut impl CmsgMut,
+    ) -> Poll<io::Result<ReadAncillarySuccess>> {
+        poll_read_ancvec_ref(&self.0, cx, &mut [io::IoSliceMut::new(buf)], abuf)
+    }
+    /// Sends bytes and ancillary data into the socket stream, making use of [`sendmsg`]. When the send buffer is full,
+    /// the returned future waits for the reactor to report writability and retries.
+    ///
+    /// The ancillary data is sent along with the first byte that makes it into the stream. As with
+    /// [`AsyncWriteAncillaryExt::write_ancillary()`](super::super::AsyncWriteAncillaryExt::write_ancillary), of which
+    /// this is a shorthand, a partial write is reported as success.
+    ///
+    /// [`sendmsg`]: https://man7.org/linux/man-pages/man2/sendmsg.2.html
+    #[inline]
+    pub async fn send_ancillary(&self, buf: &[u8], abuf: CmsgRef<'_>) -> io::Result<usize> {
+        self.send_ancillary_vectored(&[io::IoSlice::new(buf)], abuf).await
+    }
+    /// Same as [`.send_ancillary()`](Self::send_ancillary), but performs a
+    /// [gather write](https://en.wikipedia.org/wiki/Vectored_I%2FO) instead.
+    #[inline]
+    pub async fn send_ancillary_vectored(&self, bufs: &[io::IoSlice<'_>], abuf: CmsgRef<'_>) -> io::Result<usize> {
+        poll_fn(|cx| poll_write_ancvec_ref(&self.0, cx, bufs, abuf)).await
+    }
+    /// Raw polling interface for sending bytes and ancillary data. You probably want `.send_ancillary()` instead.
+    #[inline]
+    pub fn poll_send_ancillary(&self, cx: &mut Context<'_>, buf: &[u8], abuf: CmsgRef<'_>) -> Poll<io::Result<usize>> {
+        poll_write_ancvec_ref(&self.0, cx, &[io::IoSlice::new(buf)], abuf)
     }
 
     /// Splits a stream into a read half and a write half, which can be used to read and write the stream concurrently
@@ -137,8 +225,14 @@ fn poll_read_vec_ref(
     cx: &mut Context<'_>,
     bufs: &mut [io::IoSliceMut<'_>],
 ) -> Poll<io::Result<usize>> {
-    // PERF should use readv instead
-    poll_read_ancvec_ref(slf, cx, bufs, &mut CmsgMutBuf::new(&mut [])).map(|p| p.map(|s| s.main))
+    loop {
+        match slf.try_read_vectored(bufs) {
+            Ok(s) => return Poll::Ready(Ok(s)),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+            Err(e) => return Poll::Ready(Err(e)),
+        }
+        ready!(slf.poll_read_ready(cx))?;
+    }
 }
 
 fn poll_read_ancvec_ref<AB: CmsgMut + ?Sized>(
@@ -148,8 +242,12 @@ fn poll_read_ancvec_ref<AB: CmsgMut + ?Sized>(
     abuf: &mut AB,
 ) -> Poll<io::Result<ReadAncillarySuccess>> {
     loop {
-        match ancwrap::recvmsg(slf.as_fd(), bufs, abuf, None) {
-            Ok(r) => return Poll::Ready(Ok(r)),
+        // Going through try_io() clears the readiness on WouldBlock, without which poll_read_ready() would keep
+        // returning Ready and this would spin instead of yielding
+        match slf.try_io(Interest::READABLE, || {
+            ancwrap::recvmsg(slf.as_fd(), bufs, abuf, None, 0)
+        }) {
+            Ok(r) => return Poll::Ready(Ok(r.success)),
             Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
             Err(e) => return Poll::Ready(Err(e)),
         }
@@ -185,8 +283,11 @@ fn poll_write_ancvec_ref(
     bufs: &[io::IoSlice<'_>],
     abuf: CmsgRef<'_>,
 ) -> Poll<io::Result<usize>> {
+    ancwrap::check_stream_carrier(bufs, abuf)?;
     loop {
-        match ancwrap::sendmsg(slf.as_fd(), bufs, abuf) {
+        match slf.try_io(Interest::WRITABLE, || {
+            ancwrap::sendmsg(slf.as_fd(), bufs, abuf, None, 0)
+        }) {
             Ok(r) => return Poll::Ready(Ok(r)),
             Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
             Err(e) => return Poll::Ready(Err(e)),
@@ -1,4 +1,4 @@
-use super::FdOps;
+use super::{c_wrappers, FdOps};
 use crate::{
     unnamed_pipe::{UnnamedPipeReader as PubReader, UnnamedPipeWriter as PubWriter},
     Sealed,
@@ -9,33 +9,63 @@ use std::{
     io,
     os::{
         fd::OwnedFd,
-        unix::io::{AsRawFd, FromRawFd},
+        unix::io::{AsFd, AsRawFd, FromRawFd},
     },
 };
 
 pub(crate) fn pipe() -> io::Result<(PubWriter, PubReader)> {
-    let (success, fds) = unsafe {
-        let mut fds: [c_int; 2] = [0; 2];
-        let result = libc::pipe(fds.as_mut_ptr());
-        (result == 0, fds)
-    };
-    if success {
-        let (w, r) = unsafe {
-            // SAFETY: we just created both of those file descriptors, which means that neither of
-            // them can be in use elsewhere.
-            let w = OwnedFd::from_raw_fd(fds[1]);
-            let r = OwnedFd::from_raw_fd(fds[0]);
-            (w, r)
-        };
-        let w = PubWriter(UnnamedPipeWriter(FdOps(w)));
-        let r = PubReader(UnnamedPipeReader(FdOps(r)));
-        Ok((w, r))
-    } else {
-        Err(io::Error::last_os_error())
+    let (w, r) = create_pipe()?;
+    let w = PubWriter(UnnamedPipeWriter(FdOps(w)));
+    let r = PubReader(UnnamedPipeReader(FdOps(r)));
+    Ok((w, r))
+}
+
+fn raw_pipe(create: impl FnOnce(*mut c_int) -> c_int) -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut fds: [c_int; 2] = [0; 2];
+    if create(fds.as_mut_ptr()) != 0 {
+        return Err(io::Error::last_os_error());
     }
+    // SAFETY: we just created both of those file descriptors, which means that neither of
+    // them can be in use elsewhere.
+    unsafe { Ok((OwnedFd::from_raw_fd(fds[1]), OwnedFd::from_raw_fd(fds[0]))) }
+}
+#[cfg(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "illumos",
+    target_os = "solaris",
+))]
+fn create_pipe() -> io::Result<(OwnedFd, OwnedFd)> {
+    raw_pipe(|fds| unsafe { libc::pipe2(fds, libc::O_CLOEXEC) })
+}
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "illumos",
+    target_os = "solaris",
+)))]
+fn create_pipe() -> io::Result<(OwnedFd, OwnedFd)> {
+    // Without pipe2, there is a window in which a concurrent fork-exec can inherit the descriptors.
+    let (w, r) = raw_pipe(|fds| unsafe { libc::pipe(fds) })?;
+    c_wrappers::set_cloexec(w.as_fd(), true)?;
+    c_wrappers::set_cloexec(r.as_fd(), true)?;
+    Ok((w, r))
 }
 
 pub(crate) struct UnnamedPipeReader(FdOps);
+impl UnnamedPipeReader {
+    pub(crate) fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        c_wrappers::set_nonblocking(self.0 .0.as_fd(), nonblocking)
+    }
+}
 impl Sealed for UnnamedPipeReader {}
 impl Debug for UnnamedPipeReader {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -54,6 +84,11 @@ multimacro! {
 }
 
 pub(crate) struct UnnamedPipeWriter(FdOps);
+impl UnnamedPipeWriter {
+    pub(crate) fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        c_wrappers::set_nonblocking(self.0 .0.as_fd(), nonblocking)
+    }
+}
 impl Sealed for UnnamedPipeWriter {}
 impl Debug for UnnamedPipeWriter {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
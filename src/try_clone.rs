@@ -1,3 +1,5 @@
+use crate::error::{CloneError, ConversionError};
+
 /// Fallible OS object cloning.
 ///
 /// The `DuplicateHandle`/`dup` system calls can fail for a variety of reasons, most of them being related to system
@@ -6,6 +8,16 @@
 pub trait TryClone: Sized {
     /// Clones `self`, possibly returning an error.
     fn try_clone(&self) -> std::io::Result<Self>;
+    /// Clones `self` like [`.try_clone()`](Self::try_clone) does, but classifies the error, so that running out of
+    /// handles or file descriptors can be told apart from other failures without inspecting OS error codes.
+    ///
+    /// No ownership is returned in the error, since nothing is consumed; its cause is always present.
+    fn try_clone_with_details(&self) -> Result<Self, ConversionError<(), CloneError>> {
+        self.try_clone().map_err(|e| {
+            let details = CloneError::from_cause(&e);
+            ConversionError::from_cause_and_details(e, details)
+        })
+    }
 }
 impl<T: Clone> TryClone for T {
     fn try_clone(&self) -> std::io::Result<Self> {
@@ -18,6 +18,11 @@ use std::io;
 
 /// Creates a new pipe with the default creation settings and returns the handles to its writing end and reading end.
 ///
+/// On Unix, both ends have the close-on-exec flag set, atomically via `pipe2` where available, and are thus not
+/// inherited by programs started with `exec` – to hand one to a child process, either clear the flag in the child
+/// after forking or [pass the file descriptor over a Unix domain
+/// socket](crate::os::unix::udsocket::cmsg::ancillary::file_descriptors).
+///
 /// The platform-specific builders in the `os` module of the crate might be more helpful if a configuration process for
 /// the pipe is needed.
 pub fn pipe() -> io::Result<(UnnamedPipeWriter, UnnamedPipeReader)> {
@@ -39,6 +44,23 @@ pub fn pipe() -> io::Result<(UnnamedPipeWriter, UnnamedPipeReader)> {
 /// [FRF]: https://doc.rust-lang.org/std/os/unix/io/trait.FromRawFd.html
 // field is pub(crate) to allow the platform specific builders to create the public-facing pipe types
 pub struct UnnamedPipeReader(pub(crate) UnnamedPipeReaderImpl);
+impl UnnamedPipeReader {
+    /// Enables or disables the nonblocking mode for the reading end. In nonblocking mode, reading from a pipe which
+    /// has no data available returns a [`WouldBlock`](io::ErrorKind::WouldBlock) error instead of waiting for the
+    /// writer.
+    ///
+    /// The mode is a property of the open pipe rather than the handle, so it is shared with
+    /// [clones](crate::TryClone) and with other processes that the file descriptor has been passed to.
+    ///
+    /// # System calls
+    /// - `fcntl` (`F_GETFL`)
+    /// - `fcntl` (`F_SETFL`)
+    #[cfg(unix)]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(unix)))]
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.0.set_nonblocking(nonblocking)
+    }
+}
 multimacro! {
     UnnamedPipeReader,
     forward_sync_read,
@@ -62,6 +84,23 @@ multimacro! {
 /// [IntoRawFd]: https://doc.rust-lang.org/std/os/unix/io/trait.IntoRawFd.html
 /// [FromRawFd]: https://doc.rust-lang.org/std/os/unix/io/trait.FromRawFd.html
 pub struct UnnamedPipeWriter(pub(crate) UnnamedPipeWriterImpl);
+impl UnnamedPipeWriter {
+    /// Enables or disables the nonblocking mode for the writing end. In nonblocking mode, writing to a pipe whose
+    /// buffer is full returns a [`WouldBlock`](io::ErrorKind::WouldBlock) error instead of waiting for the reader to
+    /// make room, or writes only as much as fits.
+    ///
+    /// Like with the [reading end](UnnamedPipeReader::set_nonblocking), the mode is shared by all handles to the
+    /// same open pipe.
+    ///
+    /// # System calls
+    /// - `fcntl` (`F_GETFL`)
+    /// - `fcntl` (`F_SETFL`)
+    #[cfg(unix)]
+    #[cfg_attr(feature = "doc_cfg", doc(cfg(unix)))]
+    pub fn set_nonblocking(&self, nonblocking: bool) -> io::Result<()> {
+        self.0.set_nonblocking(nonblocking)
+    }
+}
 multimacro! {
     UnnamedPipeWriter,
     forward_sync_write,
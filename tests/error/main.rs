@@ -0,0 +1,79 @@
+use interprocess::error::ConversionError;
+use std::io;
+
+#[test]
+fn conversion_error_kind() {
+    let cause = io::Error::new(io::ErrorKind::PermissionDenied, "not allowed");
+    let err = ConversionError::<()>::from_source_and_cause((), cause);
+    let ioerr = io::Error::from(err);
+    assert_eq!(ioerr.kind(), io::ErrorKind::PermissionDenied);
+    assert_eq!(ioerr.to_string(), "not allowed");
+
+    let ioerr = ConversionError::<()>::from_source(()).to_io_error();
+    assert_eq!(ioerr.kind(), io::ErrorKind::Other);
+}
+
+#[cfg(unix)]
+#[test]
+fn clone_error_details() {
+    use interprocess::{error::CloneError, os::unix::udsocket::UdStream, TryClone};
+
+    let (conn, _peer) = UdStream::pair().unwrap();
+    drop(conn.try_clone_with_details().unwrap());
+
+    // No other test in this binary opens file descriptors, so changing the limit for the whole process is fine
+    let mut limit = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    assert_eq!(unsafe { libc::getrlimit(libc::RLIMIT_NOFILE, &mut limit) }, 0);
+    // Only leaves room for the standard streams, which are already open. (A limit of zero would make the duplication
+    // fail with EINVAL instead on Linux.)
+    let lowered = libc::rlimit { rlim_cur: 3, ..limit };
+    assert_eq!(unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &lowered) }, 0);
+    let rslt = conn.try_clone_with_details();
+    assert_eq!(unsafe { libc::setrlimit(libc::RLIMIT_NOFILE, &limit) }, 0);
+
+    let err = rslt.unwrap_err();
+    assert_eq!(err.details, CloneError::HandleLimit);
+    assert_eq!(err.cause.as_ref().and_then(io::Error::raw_os_error), Some(libc::EMFILE));
+}
+
+#[test]
+fn conversion_error_downcast() {
+    use interprocess::error::NoDetails;
+
+    let cause = io::Error::new(io::ErrorKind::PermissionDenied, "not allowed");
+    let ioerr = ConversionError::<&str>::from_source_and_cause("input", cause).into_io_error();
+    assert_eq!(ioerr.kind(), io::ErrorKind::PermissionDenied);
+    assert_eq!(ioerr.to_string(), "not allowed");
+
+    let err = ioerr
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<ConversionError<&str, NoDetails>>())
+        .expect("inner error is not a ConversionError");
+    assert_eq!(err.source, Some("input"));
+    assert_eq!(
+        err.cause.as_ref().map(io::Error::kind),
+        Some(io::ErrorKind::PermissionDenied)
+    );
+
+    let ioerr = ConversionError::<()>::from_source(()).into_io_error();
+    assert_eq!(ioerr.kind(), io::ErrorKind::Other);
+}
+
+#[test]
+fn conversion_error_new() {
+    #[derive(Debug, PartialEq)]
+    struct Stage(u8);
+
+    let cause = io::Error::new(io::ErrorKind::InvalidInput, "bad input");
+    let (source, cause, details) = ConversionError::new("input", Some(cause), Stage(2)).into_parts();
+    assert_eq!(source, Some("input"));
+    assert_eq!(cause.map(|e| e.kind()), Some(io::ErrorKind::InvalidInput));
+    assert_eq!(details, Stage(2));
+
+    let err = ConversionError::new((), None, Stage(0));
+    assert!(err.cause.is_none());
+    assert_eq!(err.details, Stage(0));
+}
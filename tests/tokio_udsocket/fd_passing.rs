@@ -0,0 +1,108 @@
+use super::util::*;
+use color_eyre::eyre::{bail, Context};
+use interprocess::os::unix::udsocket::{
+    cmsg::{ancillary::file_descriptors::FileDescriptors, CmsgMutExt, CmsgVecBuf},
+    tokio::UdStream,
+    UdStream as SyncUdStream,
+};
+use std::{
+    io::{self, Read, Write},
+    mem::zeroed,
+    os::fd::AsFd,
+    thread,
+    time::Duration,
+};
+
+static MSG: &str = "Hello through a passed file descriptor!";
+
+pub async fn run() -> TestResult {
+    let (sender, receiver) = SyncUdStream::pair().context("socketpair creation failed")?;
+    let sender = UdStream::from_std(sender).context("registration with the reactor failed")?;
+    let receiver = UdStream::from_std(receiver).context("registration with the reactor failed")?;
+    let (passed_end, mut kept_end) = SyncUdStream::pair().context("socketpair creation failed")?;
+
+    // Receive first, so that the future has to wait for the reactor at least once.
+    let recv = async {
+        let mut abuf = CmsgVecBuf::new(64);
+        let mut buf = [0; 1];
+        receiver
+            .recv_ancillary(&mut buf, &mut abuf)
+            .await
+            .context("ancillary receive failed")?;
+        ensure_eq!(&buf, b"F");
+        Ok(abuf)
+    };
+    let send = async {
+        let mut abuf = CmsgVecBuf::new(0);
+        abuf.add_message(&FileDescriptors::new(&[passed_end.as_fd()]));
+        sender
+            .send_ancillary(b"F", abuf.as_ref())
+            .await
+            .context("ancillary send failed")?;
+        Ok(())
+    };
+    let (abuf, ()) = ::tokio::try_join!(recv, send)?;
+    drop(passed_end);
+
+    let mut fds = take_received_fds(&abuf)?;
+    ensure_eq!(fds.len(), 1);
+    let mut received = SyncUdStream::from(fds.next().unwrap());
+
+    received
+        .write_all(MSG.as_bytes())
+        .context("send via passed descriptor failed")?;
+    let mut buf = [0; 64];
+    let buf = &mut buf[..MSG.len()];
+    kept_end
+        .read_exact(buf)
+        .context("receive from passed descriptor failed")?;
+    ensure_eq!(buf, MSG.as_bytes());
+
+    Ok(())
+}
+
+fn thread_cpu_time() -> TestResult<Duration> {
+    let mut ts = unsafe { zeroed::<libc::timespec>() };
+    if unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut ts) } == -1 {
+        return Err(io::Error::last_os_error()).context("clock_gettime failed");
+    }
+    Ok(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+}
+
+/// Receives twice, both times before the data is sent. The first receive leaves the socket marked as readable, which
+/// the second one has to clear when it finds no data, instead of spinning until the data arrives.
+pub async fn run_second_recv() -> TestResult {
+    let (mut sender, receiver) = SyncUdStream::pair().context("socketpair creation failed")?;
+    let receiver = UdStream::from_std(receiver).context("registration with the reactor failed")?;
+    let sender = thread::spawn(move || {
+        for msg in [b"A", b"B"] {
+            thread::sleep(Duration::from_millis(300));
+            sender.write_all(msg)?;
+        }
+        io::Result::Ok(())
+    });
+
+    let mut abuf = CmsgVecBuf::new(64);
+    let mut buf = [0; 1];
+    receiver
+        .recv_ancillary(&mut buf, &mut abuf)
+        .await
+        .context("first ancillary receive failed")?;
+    ensure_eq!(&buf, b"A");
+
+    let start = thread_cpu_time()?;
+    receiver
+        .recv_ancillary(&mut buf, &mut abuf)
+        .await
+        .context("second ancillary receive failed")?;
+    let spent = thread_cpu_time()? - start;
+    ensure_eq!(&buf, b"B");
+    if spent > Duration::from_millis(150) {
+        bail!("second receive spent {spent:?} of CPU time waiting for data");
+    }
+
+    match sender.join() {
+        Ok(rslt) => rslt.context("socket send failed"),
+        Err(_) => bail!("sender thread panicked"),
+    }
+}
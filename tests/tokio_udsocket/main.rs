@@ -0,0 +1,45 @@
+#![cfg(all(unix, feature = "tokio"))]
+#[path = "../util/mod.rs"]
+#[macro_use]
+mod util;
+use util::{install_color_eyre, TestResult};
+
+mod fd_passing;
+mod stream;
+
+use tokio::try_join;
+
+#[tokio::test]
+async fn tokio_udsocket_stream() -> TestResult {
+    use stream::*;
+    install_color_eyre();
+    let f1 = util::tokio::drive_server_and_multiple_clients(|s, n| server(s, n, false, false), client);
+    if cfg!(target_os = "linux") {
+        let f2 = util::tokio::drive_server_and_multiple_clients(|s, n| server(s, n, true, false), client);
+        try_join!(f1, f2)?;
+    } else {
+        f1.await?;
+    }
+    Ok(())
+}
+#[tokio::test]
+async fn tokio_udsocket_incoming() -> TestResult {
+    use stream::*;
+    install_color_eyre();
+    util::tokio::drive_server_and_multiple_clients(|s, n| server(s, n, false, true), client).await
+}
+#[tokio::test]
+async fn tokio_udsocket_std_conversion() -> TestResult {
+    install_color_eyre();
+    stream::run_std_conversion().await
+}
+#[tokio::test]
+async fn tokio_udsocket_fd_passing() -> TestResult {
+    install_color_eyre();
+    fd_passing::run().await
+}
+#[tokio::test]
+async fn tokio_udsocket_second_recv() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_second_recv().await
+}
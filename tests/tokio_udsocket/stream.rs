@@ -0,0 +1,103 @@
+use super::util::*;
+use ::tokio::{sync::oneshot::Sender, task, try_join};
+use color_eyre::eyre::{bail, Context};
+use futures::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader as AsyncBufReader},
+    StreamExt,
+};
+use interprocess::os::unix::udsocket::{
+    tokio::{UdStream, UdStreamListener},
+    UdSocket, UdStream as SyncUdStream,
+};
+use std::{
+    convert::TryInto,
+    io::{BufRead, BufReader, Write},
+    sync::Arc,
+};
+
+fn msg(server: bool) -> Box<str> {
+    message(None, server, Some('\n'))
+}
+
+pub async fn server(
+    name_sender: Sender<Arc<str>>,
+    num_clients: u32,
+    namespaced: bool,
+    use_incoming: bool,
+) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut NameGen::new(make_id!(), namespaced), |nm| {
+        UdStreamListener::bind(nm)
+    })?;
+
+    let _ = name_sender.send(name);
+
+    let mut tasks = Vec::with_capacity(num_clients.try_into().unwrap());
+    let mut incoming = listener.incoming();
+    for _ in 0..num_clients {
+        let conn = if use_incoming {
+            match incoming.next().await {
+                Some(c) => c.context("incoming connection failed")?,
+                None => bail!("incoming() yielded None"),
+            }
+        } else {
+            listener.accept().await.context("accept failed")?
+        };
+        tasks.push(task::spawn(converse(conn, msg(true), msg(false))));
+    }
+    for task in tasks {
+        task.await
+            .context("server task panicked")?
+            .context("server task returned early with error")?;
+    }
+    Ok(())
+}
+pub async fn client(nm: Arc<str>) -> TestResult {
+    let conn = UdStream::connect(&*nm).await.context("connect failed")?;
+    converse(conn, msg(false), msg(true)).await
+}
+
+async fn converse(conn: UdStream, sent: Box<str>, expected: Box<str>) -> TestResult {
+    let (reader, mut writer) = conn.split();
+    let read = async {
+        let mut reader = AsyncBufReader::new(reader);
+        let mut buffer = String::with_capacity(128);
+        reader.read_line(&mut buffer).await.context("receive failed")?;
+        ensure_eq!(buffer, &*expected);
+        Ok(())
+    };
+    let write = async {
+        writer.write_all(sent.as_bytes()).await.context("send failed")?;
+        Ok(())
+    };
+    try_join!(read, write).map(|((), ())| ())
+}
+
+pub async fn run_std_conversion() -> TestResult {
+    let (sync_server, client) = SyncUdStream::pair().context("socketpair creation failed")?;
+    let server = UdStream::from_std(sync_server).context("registration with the reactor failed")?;
+
+    let mut server = AsyncBufReader::new(server);
+    let mut buffer = String::with_capacity(128);
+    (&client)
+        .write_all(msg(false).as_bytes())
+        .context("blocking send failed")?;
+    server.read_line(&mut buffer).await.context("async receive failed")?;
+    ensure_eq!(buffer, &*msg(false));
+    buffer.clear();
+
+    let server = server
+        .into_inner()
+        .into_std()
+        .context("deregistration from the reactor failed")?;
+    server
+        .set_nonblocking(false)
+        .context("failed to restore blocking mode")?;
+    (&server)
+        .write_all(msg(true).as_bytes())
+        .context("blocking send failed")?;
+    BufReader::new(client)
+        .read_line(&mut buffer)
+        .context("blocking receive failed")?;
+    ensure_eq!(buffer, &*msg(true));
+    Ok(())
+}
@@ -0,0 +1,301 @@
+use super::util::*;
+use color_eyre::eyre::{bail, Context};
+use interprocess::os::unix::udsocket::{
+    cmsg::{
+        ancillary::file_descriptors::FileDescriptors, Cmsg, CmsgErrorKind, CmsgMut, CmsgMutBuf, CmsgMutExt, CmsgRef,
+        CmsgVecBuf, ReserveError,
+    },
+    ReadAncillary, UdStream, WriteAncillary,
+};
+use libc::cmsghdr;
+use std::{
+    io::{self, Read, Write},
+    mem::{align_of, size_of, MaybeUninit},
+    os::fd::{AsFd, AsRawFd, RawFd},
+};
+
+#[repr(C, align(16))]
+struct Aligned([u8; 256]);
+
+pub(super) fn run_validate() -> TestResult {
+    let stdin = io::stdin();
+    // The messages never get decoded, so stdin doesn't end up being closed.
+    let mut abuf = CmsgVecBuf::new(0);
+    abuf.add_message(&FileDescriptors::new(&[stdin.as_fd()]));
+    abuf.add_message(&FileDescriptors::new(&[stdin.as_fd(), stdin.as_fd()]));
+    let valid = abuf.valid_part();
+    let second = abuf.as_ref().cmsgs().next().unwrap().space_occupied();
+
+    let mut storage = Aligned([0; 256]);
+    let copy = &mut storage.0[..valid.len()];
+    copy.copy_from_slice(valid);
+    let validate = |buf: &[u8]| unsafe { CmsgRef::new_unchecked(buf) }.validate();
+    ensure_eq!(validate(copy), Ok(()));
+    ensure_eq!(validate(&[]), Ok(()));
+
+    let set_len = |buf: &mut [u8], offset: usize, len: usize| unsafe {
+        let hdr = buf.as_mut_ptr().add(offset).cast::<cmsghdr>();
+        #[allow(clippy::useless_conversion)]
+        {
+            (*hdr).cmsg_len = len.try_into().unwrap();
+        }
+    };
+
+    set_len(copy, second, 1);
+    match validate(copy) {
+        Err(e) if e.offset == second && e.kind == CmsgErrorKind::TooShort(1) => {}
+        els => bail!("validation of a buffer with a short header returned {els:?}"),
+    }
+    set_len(copy, second, valid.len());
+    match validate(copy) {
+        Err(e) if e.offset == second && e.kind == CmsgErrorKind::OutOfBounds(valid.len()) => {}
+        els => bail!("validation of a buffer with an overlong header returned {els:?}"),
+    }
+
+    let misaligned = &storage.0[align_of::<cmsghdr>() / 2..][..size_of::<cmsghdr>()];
+    match validate(misaligned) {
+        Err(e) if e.offset == 0 && e.kind == CmsgErrorKind::Misaligned => {}
+        els => bail!("validation of a misaligned buffer returned {els:?}"),
+    }
+    Ok(())
+}
+
+pub(super) fn run_relay() -> TestResult {
+    ensure_eq!(CmsgRef::empty().len(), 0);
+    ensure_eq!(CmsgRef::empty().is_empty(), true);
+
+    let (sender, relay_in) = UdStream::pair().context("socketpair creation failed")?;
+    let (relay_out, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed_end, kept_end) = UdStream::pair().context("socketpair creation failed")?;
+
+    let mut abuf = CmsgVecBuf::new(0);
+    abuf.add_message(&FileDescriptors::new(&[passed_end.as_fd()]));
+    ensure_eq!(
+        abuf.as_ref().len(),
+        Cmsg::cmsg_space_for_payload_size(size_of::<i32>() as _)
+    );
+    ensure_eq!(abuf.as_ref().as_bytes(), abuf.valid_part());
+    (&sender)
+        .write_ancillary(b"R", abuf.as_ref())
+        .context("ancillary send failed")?;
+    drop(passed_end);
+
+    // The relay copies the control data verbatim, without looking into it
+    let mut abuf = CmsgVecBuf::new(64);
+    let mut buf = [0; 1];
+    (&relay_in)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive on the relay failed")?;
+    let received = abuf.as_ref();
+    ensure_eq!(received.is_empty(), false);
+    let mut storage = Aligned([0; 256]);
+    let copy = &mut storage.0[..received.len()];
+    copy.copy_from_slice(received.as_bytes());
+    (&relay_out)
+        .write_ancillary(&buf, unsafe { CmsgRef::new_unchecked(copy) })
+        .context("relayed ancillary send failed")?;
+    // Closes the relay's own copy of the descriptor
+    abuf.as_ref().decode::<FileDescriptors>().for_each(drop);
+
+    let mut abuf = CmsgVecBuf::new(64);
+    (&receiver)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    let fd = take_received_fds(&abuf)?.next().unwrap();
+    (&UdStream::from(fd))
+        .write_all(b"relayed")
+        .context("send via relayed descriptor failed")?;
+    let mut msg = [0; 7];
+    (&kept_end)
+        .read_exact(&mut msg)
+        .context("receive from relayed descriptor failed")?;
+    ensure_eq!(&msg, b"relayed");
+    Ok(())
+}
+
+pub(super) fn run_forward() -> TestResult {
+    let (sender, relay_in) = UdStream::pair().context("socketpair creation failed")?;
+    let (relay_out, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed_end, kept_end) = UdStream::pair().context("socketpair creation failed")?;
+
+    let mut abuf = CmsgVecBuf::new(0);
+    abuf.add_message(&FileDescriptors::new(&[passed_end.as_fd()]));
+    (&sender)
+        .write_ancillary(b"F", abuf.as_ref())
+        .context("ancillary send failed")?;
+    drop(passed_end);
+
+    // The received descriptor stays open in the relay until forwarding is done
+    let mut abuf = CmsgVecBuf::new(64);
+    let mut buf = [0; 1];
+    (&relay_in)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive on the relay failed")?;
+    (&relay_out)
+        .write_ancillary(&buf, abuf.as_ref())
+        .context("forwarding send failed")?;
+    abuf.as_ref().decode::<FileDescriptors>().for_each(drop);
+
+    let mut abuf = CmsgVecBuf::new(64);
+    (&receiver)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    ensure_eq!(&buf, b"F");
+    let fd = take_received_fds(&abuf)?.next().unwrap();
+    (&UdStream::from(fd))
+        .write_all(b"forwarded")
+        .context("send via forwarded descriptor failed")?;
+    let mut msg = [0; 9];
+    (&kept_end)
+        .read_exact(&mut msg)
+        .context("receive from forwarded descriptor failed")?;
+    ensure_eq!(&msg, b"forwarded");
+    Ok(())
+}
+
+pub(super) fn run_clear_and_resize() -> TestResult {
+    let stdin = io::stdin();
+    let mut abuf = CmsgVecBuf::new(1024);
+    abuf.add_message(&FileDescriptors::new(&[stdin.as_fd()]));
+    abuf.set_truncation_flag(true);
+
+    ensure_eq!(abuf.clear_and_resize(64).is_ok(), true);
+    ensure_eq!(abuf.valid_len(), 0);
+    ensure_eq!(abuf.is_truncated(), false);
+    ensure_eq!((64..1024).contains(&abuf.capacity()), true);
+
+    // Same size, same allocation
+    let (base, cap) = (abuf.as_bytes().as_ptr(), abuf.capacity());
+    ensure_eq!(abuf.clear_and_resize(cap).is_ok(), true);
+    ensure_eq!(abuf.as_bytes().as_ptr(), base);
+
+    ensure_eq!(abuf.clear_and_resize(512).is_ok(), true);
+    ensure_eq!(abuf.capacity() >= 512, true);
+
+    // Fixed-size buffers are still cleared
+    let mut storage = [MaybeUninit::uninit(); 128];
+    let mut abuf = CmsgMutBuf::new(&mut storage);
+    abuf.add_message(&FileDescriptors::new(&[stdin.as_fd()]));
+    match abuf.clear_and_resize(64) {
+        Err(ReserveError::Unsupported) => {}
+        els => bail!("resizing a fixed-size buffer returned {els:?} instead of Unsupported"),
+    }
+    ensure_eq!(abuf.valid_len(), 0);
+    ensure_eq!(abuf.capacity(), 128);
+    Ok(())
+}
+
+pub(super) fn run_collect() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (first, _first_peer) = UdStream::pair().context("socketpair creation failed")?;
+    let (second, _second_peer) = UdStream::pair().context("socketpair creation failed")?;
+
+    let mut abuf = CmsgVecBuf::new(0);
+    abuf.add_message(&FileDescriptors::new(&[first.as_fd()]));
+    abuf.add_message(&FileDescriptors::new(&[second.as_fd()]));
+    let mut cmsgs = abuf.as_ref().cmsgs();
+    ensure_eq!(cmsgs.len(), 2);
+    cmsgs.next();
+    ensure_eq!(cmsgs.size_hint(), (1, Some(1)));
+    (&sender)
+        .write_ancillary(b"L", abuf.as_ref())
+        .context("ancillary send failed")?;
+
+    let mut abuf = CmsgVecBuf::new(64);
+    let mut buf = [0; 1];
+    (&receiver)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    // Collecting relies on the exact size, and the kernel may have merged the two messages into one
+    let fds = match abuf.as_ref().decode::<FileDescriptors>().collect::<Result<Vec<_>, _>>() {
+        Ok(fds) => fds,
+        Err(e) => bail!("parsing of file descriptors failed: {e}"),
+    };
+    ensure_eq!(fds.iter().map(FileDescriptors::len).sum::<usize>(), 2);
+    Ok(())
+}
+
+pub(super) fn run_clone() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed_end, kept_end) = UdStream::pair().context("socketpair creation failed")?;
+    let mut abuf = CmsgVecBuf::new(0);
+    abuf.add_message(&FileDescriptors::new(&[passed_end.as_fd()]));
+    (&sender)
+        .write_ancillary(b"C", abuf.as_ref())
+        .context("ancillary send failed")?;
+    drop(passed_end);
+
+    let mut abuf = CmsgVecBuf::new(64);
+    let mut buf = [0; 1];
+    (&receiver)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    let snapshot = abuf.clone();
+    ensure_eq!(snapshot.valid_part(), abuf.valid_part());
+    ensure_eq!(snapshot.is_truncated(), abuf.is_truncated());
+
+    // The receive buffer gets reused while the snapshot is kept for later
+    abuf.clear();
+    (&sender).write_all(b"D").context("socket send failed")?;
+    (&receiver)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    ensure_eq!(abuf.as_ref().is_empty(), true);
+
+    let fd = take_received_fds(&snapshot)?.next().unwrap();
+    (&UdStream::from(fd))
+        .write_all(b"cloned")
+        .context("send via snapshotted descriptor failed")?;
+    let mut msg = [0; 6];
+    (&kept_end)
+        .read_exact(&mut msg)
+        .context("receive from snapshotted descriptor failed")?;
+    ensure_eq!(&msg, b"cloned");
+    Ok(())
+}
+
+pub(super) fn run_iter_raw() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed_end, _kept_end) = UdStream::pair().context("socketpair creation failed")?;
+
+    let mut abuf = CmsgVecBuf::new(0);
+    abuf.add_message(&FileDescriptors::new(&[passed_end.as_fd()]));
+    // A message type the crate knows nothing about, which never leaves the buffer
+    let payload = [1, 2, 3, 4, 5];
+    abuf.add_raw_message(unsafe { Cmsg::new(0x1234, 0x5678, &payload) });
+    let raw = abuf.iter_raw().collect::<Vec<_>>();
+    ensure_eq!(raw.len(), 2);
+    ensure_eq!((raw[0].0, raw[0].1), (libc::SOL_SOCKET, libc::SCM_RIGHTS));
+    ensure_eq!(raw[0].2, passed_end.as_raw_fd().to_ne_bytes());
+    ensure_eq!(raw[1], (0x1234, 0x5678, &payload[..]));
+
+    let mut sabuf = CmsgVecBuf::new(0);
+    sabuf.add_message(&FileDescriptors::new(&[passed_end.as_fd()]));
+    (&sender)
+        .write_ancillary(b"R", sabuf.as_ref())
+        .context("ancillary send failed")?;
+    let mut abuf = CmsgVecBuf::new(64);
+    let mut buf = [0; 1];
+    (&receiver)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    let mut iter = abuf.as_ref().iter_raw();
+    ensure_eq!(iter.len(), 1);
+    let Some((level, ty, payload)) = iter.next() else {
+        bail!("no control messages received");
+    };
+    ensure_eq!(
+        (level, ty, payload.len()),
+        (libc::SOL_SOCKET, libc::SCM_RIGHTS, size_of::<RawFd>())
+    );
+    ensure_eq!(iter.next(), None);
+    ensure_eq!(iter.len(), 0);
+
+    // Not decoded by the raw iterator, so it still has to be closed
+    for fds in abuf.as_ref().decode::<FileDescriptors<'_>>() {
+        if let Err(e) = fds {
+            bail!("parsing of file descriptors failed: {e}");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use super::util::*;
+use color_eyre::eyre::Context;
+use interprocess::os::unix::udsocket::{
+    cmsg::{
+        ancillary::file_descriptors::FileDescriptors,
+        context::{Collector, DummyCollector, FlagsCollector, FnCollector, IterCollector},
+        Cmsg, CmsgMutExt, CmsgVecBuf,
+    },
+    UdDatagram, UdSocket, UdStream,
+};
+use std::{
+    cell::RefCell,
+    io::{IoSlice, IoSliceMut},
+    mem::size_of,
+    os::fd::{AsFd, AsRawFd, BorrowedFd, RawFd},
+};
+
+pub(super) fn run_composition() -> TestResult {
+    let (sock, _other) = UdStream::pair().context("socketpair creation failed")?;
+    let log = RefCell::new(Vec::new());
+    let logger = |name: &'static str| {
+        let log = &log;
+        FnCollector::new(
+            move |_| log.borrow_mut().push((name, None)),
+            move |_, flags| log.borrow_mut().push((name, Some(flags))),
+        )
+    };
+
+    let mut collector = (
+        logger("first"),
+        IterCollector([logger("second"), logger("third")]),
+        (
+            DummyCollector,
+            FnCollector::after(|_, flags| log.borrow_mut().push(("fourth", Some(flags)))),
+        ),
+    );
+    collector.pre_op_collect(sock.as_fd());
+    collector.post_op_collect(sock.as_fd(), 42);
+
+    ensure_eq!(
+        log.into_inner(),
+        [
+            ("first", None),
+            ("second", None),
+            ("third", None),
+            ("first", Some(42)),
+            ("second", Some(42)),
+            ("third", Some(42)),
+            ("fourth", Some(42)),
+        ]
+    );
+    Ok(())
+}
+
+pub(super) fn run_flags() -> TestResult {
+    let (sock, _other) = UdStream::pair().context("socketpair creation failed")?;
+    let flags = FlagsCollector::new();
+    ensure_eq!(flags.last_flags(), None);
+
+    let mut collector = (&flags, DummyCollector);
+    collector.pre_op_collect(sock.as_fd());
+    ensure_eq!(flags.last_flags(), None);
+    collector.post_op_collect(sock.as_fd(), libc::MSG_CTRUNC);
+    ensure_eq!(flags.last_flags(), Some(libc::MSG_CTRUNC));
+    collector.post_op_collect(sock.as_fd(), 0);
+    ensure_eq!(flags.last_flags(), Some(0));
+    Ok(())
+}
+
+pub(super) fn run_stream_ops() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed_end, _kept_end) = UdStream::pair().context("socketpair creation failed")?;
+    let log = RefCell::new(Vec::new());
+    let logger = || {
+        FnCollector::new(
+            |fd: BorrowedFd<'_>| log.borrow_mut().push(("pre", fd.as_raw_fd())),
+            |fd: BorrowedFd<'_>, _| log.borrow_mut().push(("post", fd.as_raw_fd())),
+        )
+    };
+
+    let mut sabuf = CmsgVecBuf::new(0);
+    sabuf.add_message(&FileDescriptors::new(&[passed_end.as_fd(), passed_end.as_fd()]));
+    sender
+        .send_ancillary_vectored_with_collector(&[IoSlice::new(b"C")], sabuf.as_ref(), logger())
+        .context("send with collector failed")?;
+    let (s, r) = (sender.as_raw_fd(), receiver.as_raw_fd());
+    ensure_eq!(log.take(), [("pre", s), ("post", s)]);
+
+    // Only room for one of the two file descriptors
+    let flags = FlagsCollector::new();
+    let mut abuf = CmsgVecBuf::new(0);
+    abuf.reserve(Cmsg::cmsg_len_for_payload_size(size_of::<RawFd>() as libc::c_uint));
+    let mut buf = [0; 1];
+    let rs = receiver
+        .recv_ancillary_vectored_with_collector(&mut [IoSliceMut::new(&mut buf)], &mut abuf, (&flags, logger()))
+        .context("receive with collector failed")?;
+    ensure_eq!(rs.main, 1);
+    ensure_eq!(log.take(), [("pre", r), ("post", r)]);
+    ensure_eq!(flags.last_flags().map(|f| f & libc::MSG_CTRUNC != 0), Some(true));
+    drop(FileDescriptors::take_limited(abuf.as_ref(), 2));
+
+    // Collectors are not called if the operation fails
+    receiver
+        .set_nonblocking(true)
+        .context("failed to enable nonblocking mode")?;
+    ensure_eq!(
+        receiver
+            .recv_ancillary_vectored_with_collector(&mut [IoSliceMut::new(&mut buf)], &mut abuf, logger())
+            .is_err(),
+        true
+    );
+    ensure_eq!(log.take(), [("pre", r)]);
+    Ok(())
+}
+
+pub(super) fn run_datagram_ops() -> TestResult {
+    let (sender, receiver) = UdDatagram::pair().context("socketpair creation failed")?;
+    let sent = FlagsCollector::new();
+    let received = FlagsCollector::new();
+
+    sender
+        .send_ancillary_vectored_with_collector(&[IoSlice::new(b"datagram")], CmsgVecBuf::new(0).as_ref(), &sent)
+        .context("send with collector failed")?;
+    ensure_eq!(sent.last_flags(), Some(0));
+
+    let mut buf = [0; 4];
+    let mut abuf = CmsgVecBuf::new(0);
+    let rs = receiver
+        .recv_ancillary_vectored_with_collector(&mut [IoSliceMut::new(&mut buf)], &mut abuf, &received)
+        .context("receive with collector failed")?;
+    ensure_eq!(rs.main, 4);
+    ensure_eq!(&buf, b"data");
+    ensure_eq!(received.last_flags().map(|f| f & libc::MSG_TRUNC != 0), Some(true));
+    Ok(())
+}
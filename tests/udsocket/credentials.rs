@@ -26,6 +26,33 @@ pub(super) fn run(namegen: NameGen, contcred: bool) -> TestResult {
     )
 }
 
+#[cfg(uds_cont_credentials)]
+pub(super) fn run_toggle() -> TestResult {
+    let (sock, _peer) = UdStream::pair().context("socketpair creation failed")?;
+    for val in [false, true, false] {
+        if val != sock.continuous_ancillary_credentials().context("option query failed")? {
+            sock.set_continuous_ancillary_credentials(val)
+                .context("option toggle failed")?;
+        }
+        ensure_eq!(
+            sock.continuous_ancillary_credentials().context("option query failed")?,
+            val
+        );
+    }
+    Ok(())
+}
+
+#[cfg(any(uds_ucred, uds_xucred, uds_unpcbid, uds_sockpeercred))]
+pub(super) fn run_peer_eq() -> TestResult {
+    let (a, b) = UdStream::pair().context("socketpair creation failed")?;
+    let a_creds = a.get_peer_credentials().context("peer credentials query failed")?;
+    let b_creds = b.get_peer_credentials().context("peer credentials query failed")?;
+    ckcreds(&a_creds)?;
+    // Both ends belong to the same process
+    ensure_eq!(a_creds, b_creds);
+    Ok(())
+}
+
 fn enable_passcred(sock: &UdStream) -> TestResult {
     #[cfg(uds_cont_credentials)]
     {
@@ -47,11 +74,10 @@ fn decreds(abuf: CmsgRef<'_>) -> TestResult<Credentials<'_>> {
     }
 }
 fn ckcreds(creds: &Credentials) -> TestResult {
-    if let Some(pid) = creds.pid() {
-        ensure_eq!(pid, unsafe { libc::getpid() });
+    if !creds.matches_current_process() {
+        bail!("received credentials {creds:?} do not match those of the current process");
     }
-    ensure_eq!(creds.best_effort_ruid(), unsafe { libc::getuid() });
-    ensure_eq!(creds.best_effort_rgid(), unsafe { libc::getgid() });
+    ensure_eq!(creds.supplementary_groups(), creds.groups().collect::<Vec<_>>());
     Ok(())
 }
 
@@ -78,7 +104,7 @@ fn server(
     }
     let ancself = abm.as_ref();
 
-    let mut abread = CmsgVecBuf::new(Cmsg::cmsg_len_for_payload_size(Credentials::MIN_ANCILLARY_SIZE) * 8);
+    let mut abread = CmsgVecBuf::new(Cmsg::cmsg_space_for_payload_size(Credentials::MIN_ANCILLARY_SIZE) * 8);
 
     for _ in 0..num_clients {
         let mut conn = match listener.accept() {
@@ -128,7 +154,7 @@ fn client(name: &str, shutdown: bool, contcred: bool) -> TestResult {
     }
     let ancself = abm.as_ref();
 
-    let mut abread = CmsgVecBuf::new(Cmsg::cmsg_len_for_payload_size(Credentials::MIN_ANCILLARY_SIZE) * 8);
+    let mut abread = CmsgVecBuf::new(Cmsg::cmsg_space_for_payload_size(Credentials::MIN_ANCILLARY_SIZE) * 8);
 
     let mut conn = UdStream::connect(name)
         .context("connect failed")?
@@ -0,0 +1,35 @@
+use super::util::*;
+use color_eyre::eyre::bail;
+use interprocess::os::unix::udsocket::cmsg::{
+    ancillary::{
+        credentials::{Credentials, CredentialsDecodeError},
+        FromCmsg, ParseError, ParseErrorKind,
+    },
+    Cmsg,
+};
+
+pub(super) fn run_truncated() -> TestResult {
+    #[cfg(uds_ucred)]
+    let anctype = libc::SCM_CREDENTIALS;
+    #[cfg(uds_cmsgcred)]
+    let anctype = libc::SCM_CREDS;
+
+    let size = Credentials::MIN_ANCILLARY_SIZE as usize;
+    let payload = vec![0; size + 1];
+    for got in [0, size - 1, size + 1] {
+        let cmsg = unsafe { Cmsg::new(libc::SOL_SOCKET, anctype, &payload[..got]) };
+        let expected_err = if got < size {
+            CredentialsDecodeError::TooShort { got, expected: size }
+        } else {
+            CredentialsDecodeError::TooLong { got, expected: size }
+        };
+        match Credentials::try_parse(cmsg) {
+            Err(ParseError {
+                kind: ParseErrorKind::MalformedPayload(e),
+                ..
+            }) => ensure_eq!(e, expected_err),
+            els => bail!("parsing a {got}-byte credentials payload returned {els:?} instead of an error"),
+        }
+    }
+    Ok(())
+}
@@ -1,7 +1,10 @@
 use super::util::*;
-use color_eyre::eyre::Context;
-use interprocess::os::unix::udsocket::UdDatagram;
-use std::sync::{mpsc::Sender, Arc};
+use color_eyre::eyre::{bail, Context};
+use interprocess::os::unix::udsocket::{cmsg::CmsgVecBuf, ToUdSocketPath, UdDatagram, UdSocketPath};
+use std::{
+    io::IoSliceMut,
+    sync::{mpsc::Sender, Arc},
+};
 
 pub(super) fn run(mut namegen: NameGen) -> TestResult {
     let mks = |nm: &str| UdDatagram::bound(nm);
@@ -13,6 +16,104 @@ pub(super) fn run(mut namegen: NameGen) -> TestResult {
     drive_pair(side_a, "side A", side_b, "side B")
 }
 
+pub(super) fn run_truncation() -> TestResult {
+    let (a, b) = UdDatagram::pair().context("socketpair creation failed")?;
+    let msg = make_message('A', false);
+
+    a.send(&msg).context("socket send failed")?;
+    let mut buf = [0; 4];
+    let rslt = b
+        .recv_ancillary_with_flags(&mut buf, &mut CmsgVecBuf::new(0), 0)
+        .context("socket receive failed")?;
+    ensure_eq!(rslt.main_truncated(), true);
+    ensure_eq!(rslt.ancillary_truncated(), false);
+    ensure_eq!(&buf[..], &msg[..buf.len()]);
+
+    a.send(&msg).context("socket send failed")?;
+    let mut buf = [0; 64];
+    let rslt = b
+        .recv_ancillary_with_flags(&mut buf, &mut CmsgVecBuf::new(0), 0)
+        .context("socket receive failed")?;
+    ensure_eq!(rslt.main_truncated(), false);
+    ensure_eq!(&buf[..rslt.success.main], &msg[..]);
+
+    Ok(())
+}
+
+pub(super) fn run_disconnect(mut namegen: NameGen) -> TestResult {
+    let mks = |nm: &str| UdDatagram::bound(nm);
+    let (a_name, a) = listen_and_pick_name(&mut namegen, mks).context("failed to make side A socket")?;
+    let (b_name, b) = listen_and_pick_name(&mut namegen, mks).context("failed to make side B socket")?;
+    let unbound = UdDatagram::unbound().context("unbound socket creation failed")?;
+    let msg = make_message('A', false);
+    let mut buf = [0; 64];
+
+    a.set_destination(&*b_name).context("setting destination failed")?;
+    a.send(&msg).context("socket send failed")?;
+    let read = b.recv(&mut buf).context("socket receive failed")?;
+    ensure_eq!(&buf[..read], &msg[..]);
+    // On Linux, a connected socket only accepts datagrams from its destination
+    if cfg!(target_os = "linux") && unbound.send_to(&msg, &*a_name).is_ok() {
+        bail!("sending to a socket connected to a different peer succeeded");
+    }
+
+    a.disconnect().context("disconnect failed")?;
+    if a.send(&msg).is_ok() {
+        bail!("sending without a destination succeeded after disconnecting");
+    }
+    unbound.send_to(&msg, &*a_name).context("socket send failed")?;
+    let read = a.recv(&mut buf).context("socket receive failed")?;
+    ensure_eq!(&buf[..read], &msg[..]);
+
+    // Disconnecting an unconnected socket is fine too
+    a.disconnect().context("repeated disconnect failed")?;
+    Ok(())
+}
+
+pub(super) fn run_send_to(mut namegen: NameGen) -> TestResult {
+    let (name, bound) = listen_and_pick_name(&mut namegen, |nm| UdDatagram::bound(nm))?;
+    let unbound = UdDatagram::unbound().context("unbound socket creation failed")?;
+    let msg = make_message('A', false);
+    let mut buf = [0; 64];
+
+    let written = unbound.send_to(&msg, &*name).context("socket send failed")?;
+    ensure_eq!(written, msg.len());
+    let (read, addr) = bound.recv_from(&mut buf).context("socket receive failed")?;
+    ensure_eq!(&buf[..read], &msg[..]);
+    ensure_eq!(addr, UdSocketPath::Unnamed);
+
+    let reply = make_message('B', false);
+    bound.send_to(&reply, &*name).context("socket send to self failed")?;
+    let (read, addr) = bound.recv_from(&mut buf).context("socket receive from self failed")?;
+    ensure_eq!(&buf[..read], &reply[..]);
+    ensure_eq!(addr, name.to_socket_path().context("path conversion failed")?);
+
+    // Scattered over two buffers which are too small for the whole datagram combined
+    bound.send_to(&reply, &*name).context("socket send to self failed")?;
+    let (mut head, mut tail) = ([0; 4], [0; 4]);
+    let (rslt, addr) = bound
+        .recv_from_ancillary_vectored_with_flags(
+            &mut [IoSliceMut::new(&mut head), IoSliceMut::new(&mut tail)],
+            &mut CmsgVecBuf::new(0),
+            0,
+        )
+        .context("vectored socket receive from self failed")?;
+    ensure_eq!(rslt.main_truncated(), true);
+    ensure_eq!(rslt.success.main, head.len() + tail.len());
+    ensure_eq!([head, tail].concat(), &reply[..8]);
+    ensure_eq!(addr, name.to_socket_path().context("path conversion failed")?);
+
+    unbound.send_to(&msg, &*name).context("socket send failed")?;
+    let (rslt, addr) = bound
+        .recv_from_ancillary_with_flags(&mut buf, &mut CmsgVecBuf::new(0), 0)
+        .context("socket receive failed")?;
+    ensure_eq!(rslt.main_truncated(), false);
+    ensure_eq!(&buf[..rslt.success.main], &msg[..]);
+    ensure_eq!(addr, UdSocketPath::Unnamed);
+
+    Ok(())
+}
+
 fn make_message(side_name: char, second: bool) -> Vec<u8> {
     let fs = if second { "Second" } else { "First" };
     format!("{fs} message from side {side_name}").into_bytes()
@@ -0,0 +1,98 @@
+use super::util::*;
+use color_eyre::eyre::{bail, Context};
+use interprocess::os::unix::udsocket::{cmsg::CmsgVecBuf, ReadAncillary, UdSocket, UdStream, UdStreamListener};
+use std::{
+    io::{self, Write},
+    mem::zeroed,
+    os::unix::thread::JoinHandleExt,
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+extern "C" fn do_nothing(_: libc::c_int) {}
+
+/// Installs a handler for `SIGUSR1` without `SA_RESTART`, so that the kernel interrupts blocking system calls instead
+/// of restarting them by itself.
+fn install_interrupting_handler() -> TestResult {
+    unsafe {
+        let mut action: libc::sigaction = zeroed();
+        action.sa_sigaction = do_nothing as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        libc::sigemptyset(&mut action.sa_mask);
+        if libc::sigaction(libc::SIGUSR1, &action, std::ptr::null_mut()) == -1 {
+            return Err(io::Error::last_os_error()).context("sigaction failed");
+        }
+    }
+    Ok(())
+}
+
+/// Gives the thread time to block, signals it, then lets it block again before `unblock` lets it finish.
+fn interrupt<T>(thread: JoinHandle<io::Result<T>>, unblock: impl FnOnce() -> TestResult) -> TestResult<T> {
+    thread::sleep(Duration::from_millis(100));
+    ensure_eq!(unsafe { libc::pthread_kill(thread.as_pthread_t(), libc::SIGUSR1) }, 0);
+    thread::sleep(Duration::from_millis(100));
+    unblock()?;
+    match thread.join() {
+        Ok(rslt) => rslt.context("interrupted system call was not retried"),
+        Err(_) => bail!("blocked thread panicked"),
+    }
+}
+
+pub(super) fn run_recv() -> TestResult {
+    install_interrupting_handler()?;
+    let (mut sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let thread = thread::spawn(move || {
+        let mut buf = [0; 2];
+        let mut abuf = CmsgVecBuf::new(0);
+        (&receiver).read_ancillary(&mut buf, &mut abuf).map(|rs| (rs.main, buf))
+    });
+    let (len, buf) = interrupt(thread, || {
+        sender.write_all(b"hi").context("socket send failed")?;
+        Ok(())
+    })?;
+    ensure_eq!(len, 2);
+    ensure_eq!(&buf, b"hi");
+    Ok(())
+}
+
+pub(super) fn run_accept() -> TestResult {
+    install_interrupting_handler()?;
+    let mut namegen = NameGen::new(make_id!(), false);
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| UdStreamListener::bind(nm))?;
+    let thread = thread::spawn(move || listener.accept().map(drop));
+    interrupt(thread, || {
+        UdStream::connect(&*name).context("connect failed")?;
+        Ok(())
+    })
+}
+
+pub(super) fn run_recv_timeout() -> TestResult {
+    install_interrupting_handler()?;
+    let (_sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    receiver
+        .set_read_timeout(Some(Duration::from_secs(1)))
+        .context("setting read timeout failed")?;
+    let start = Instant::now();
+    let thread = thread::spawn(move || {
+        let mut buf = [0; 2];
+        let mut abuf = CmsgVecBuf::new(0);
+        (&receiver).read_ancillary(&mut buf, &mut abuf).map(drop)
+    });
+    // Interrupt the read more often than the timeout would elapse, which would keep it from ever timing out if every
+    // retry were to restart the timer unconditionally
+    while !thread.is_finished() {
+        if start.elapsed() > Duration::from_secs(5) {
+            bail!("interrupted read with a timeout did not time out");
+        }
+        thread::sleep(Duration::from_millis(100));
+        // The thread may have exited since it was last checked on
+        let ret = unsafe { libc::pthread_kill(thread.as_pthread_t(), libc::SIGUSR1) };
+        if ret != 0 && ret != libc::ESRCH {
+            bail!("pthread_kill failed: {}", io::Error::from_raw_os_error(ret));
+        }
+    }
+    match thread.join() {
+        Ok(Err(e)) if e.kind() == io::ErrorKind::WouldBlock => Ok(()),
+        Ok(els) => bail!("interrupted read with a timeout returned {els:?} instead of WouldBlock"),
+        Err(_) => bail!("blocked thread panicked"),
+    }
+}
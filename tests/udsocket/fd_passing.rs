@@ -0,0 +1,592 @@
+use super::util::*;
+use color_eyre::eyre::{bail, Context};
+use interprocess::{
+    os::unix::udsocket::{
+        cmsg::{ancillary::file_descriptors::FileDescriptors, Cmsg, CmsgMut, CmsgMutBuf, CmsgMutExt, CmsgVecBuf},
+        AncillaryBufReader, ReadAncillary, UdStream, WriteAncillary,
+    },
+    unnamed_pipe::{self, UnnamedPipeReader},
+};
+use libc::c_uint;
+use std::{
+    io::{self, BufRead, IoSliceMut, Read, Write},
+    mem::{size_of, MaybeUninit},
+    os::fd::{AsFd, AsRawFd, RawFd},
+    thread,
+};
+
+static MSG: &str = "Hello through a passed file descriptor!";
+
+pub(super) fn run() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed_end, mut kept_end) = UdStream::pair().context("socketpair creation failed")?;
+
+    let mut abuf = CmsgVecBuf::new(0);
+    abuf.add_message(&FileDescriptors::new(&[passed_end.as_fd()]));
+    // The kernel would silently drop the descriptor if there was no data to attach it to
+    match (&sender).write_ancillary(b"", abuf.as_ref()) {
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {}
+        els => bail!("ancillary send without data returned {els:?} instead of InvalidInput"),
+    }
+    (&sender)
+        .write_ancillary(b"F", abuf.as_ref())
+        .context("ancillary send failed")?;
+    drop(passed_end);
+
+    let mut abuf = CmsgVecBuf::new(64);
+    let mut buf = [0; 1];
+    (&receiver)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    ensure_eq!(&buf, b"F");
+
+    let mut fds = take_received_fds(&abuf)?;
+    ensure_eq!(fds.len(), 1);
+    let mut received = UdStream::from(fds.next().unwrap());
+
+    received
+        .write_all(MSG.as_bytes())
+        .context("send via passed descriptor failed")?;
+    let mut buf = [0; 64];
+    let buf = &mut buf[..MSG.len()];
+    kept_end
+        .read_exact(buf)
+        .context("receive from passed descriptor failed")?;
+    ensure_eq!(buf, MSG.as_bytes());
+
+    Ok(())
+}
+
+pub(super) fn run_pipe() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (mut writer, reader) = unnamed_pipe::pipe().context("pipe creation failed")?;
+    for fd in [writer.as_fd(), reader.as_fd()] {
+        ensure_eq!(
+            unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFD) } & libc::FD_CLOEXEC,
+            libc::FD_CLOEXEC
+        );
+    }
+
+    let mut abuf = CmsgVecBuf::new(0);
+    abuf.add_message(&FileDescriptors::new(&[reader.as_fd()]));
+    (&sender)
+        .write_ancillary(b"P", abuf.as_ref())
+        .context("ancillary send failed")?;
+    drop(reader);
+
+    let mut abuf = CmsgVecBuf::new(64);
+    let mut buf = [0; 1];
+    (&receiver)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    let mut received = UnnamedPipeReader::from(take_received_fds(&abuf)?.next().unwrap());
+
+    received
+        .set_nonblocking(true)
+        .context("failed to enable nonblocking mode")?;
+    let mut buf = [0; 64];
+    match received.read(&mut buf) {
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+        els => bail!("read from an empty nonblocking pipe returned {els:?} instead of WouldBlock"),
+    }
+    writer.write_all(MSG.as_bytes()).context("pipe write failed")?;
+    drop(writer);
+    received
+        .set_nonblocking(false)
+        .context("failed to disable nonblocking mode")?;
+    let mut msg = String::new();
+    received
+        .read_to_string(&mut msg)
+        .context("read via passed pipe failed")?;
+    ensure_eq!(msg, MSG);
+
+    Ok(())
+}
+
+pub(super) fn run_take_limited() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let mut buf = [0; 1];
+    let mut abuf = CmsgVecBuf::new(256);
+    for (limit, accepted) in [(2, false), (3, true)] {
+        let (passed, kept): (Vec<_>, Vec<_>) = (0..3)
+            .map(|_| UdStream::pair())
+            .collect::<io::Result<Vec<_>>>()
+            .context("socketpair creation failed")?
+            .into_iter()
+            .unzip();
+        let mut sabuf = CmsgVecBuf::new(0);
+        sabuf.add_message(&FileDescriptors::new(&[passed[0].as_fd()]));
+        sabuf.add_message(&FileDescriptors::new(&[passed[1].as_fd(), passed[2].as_fd()]));
+        (&sender)
+            .write_ancillary(b"L", sabuf.as_ref())
+            .context("ancillary send failed")?;
+        drop(passed);
+
+        abuf.clear();
+        (&receiver)
+            .read_ancillary(&mut buf, &mut abuf)
+            .context("ancillary receive failed")?;
+        match FileDescriptors::take_limited(abuf.as_ref(), limit) {
+            Ok(fds) if accepted => ensure_eq!(fds.len(), 3),
+            Err(e) if !accepted => {
+                ensure_eq!(e.limit, limit);
+                ensure_eq!(e.received, 3);
+                // With the received copies closed, nothing refers to the passed ends anymore
+                for kept in &kept {
+                    ensure_eq!((&*kept).read(&mut buf).context("socket receive failed")?, 0);
+                }
+            }
+            els => bail!("taking file descriptors with a limit of {limit} returned {els:?}"),
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn run_with_flags() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed, kept) = UdStream::pair().context("socketpair creation failed")?;
+    let mut sabuf = CmsgVecBuf::new(0);
+    sabuf.add_message(&FileDescriptors::new(&[passed.as_fd()]));
+    (&sender)
+        .write_ancillary(b"P", sabuf.as_ref())
+        .context("ancillary send failed")?;
+    drop(passed);
+
+    let mut buf = [0; 1];
+    let mut abuf = CmsgVecBuf::new(64);
+    let rslt = receiver
+        .recv_ancillary_with_flags(&mut buf, &mut abuf, libc::MSG_PEEK)
+        .context("ancillary peek failed")?;
+    ensure_eq!(&buf, b"P");
+    ensure_eq!(rslt.ancillary_truncated(), false);
+    let peeked = take_received_fds(&abuf)?.next().unwrap();
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let fdflags = unsafe { libc::fcntl(peeked.as_raw_fd(), libc::F_GETFD) };
+        ensure_eq!(fdflags & libc::FD_CLOEXEC, libc::FD_CLOEXEC);
+    }
+    drop(peeked);
+
+    // The data and the file descriptor are still queued, but there's no room for the latter this time
+    buf[0] = 0;
+    let rslt = receiver
+        .recv_ancillary_vectored_with_flags(&mut [IoSliceMut::new(&mut buf)], &mut CmsgMutBuf::new(&mut []), 0)
+        .context("ancillary receive failed")?;
+    ensure_eq!(&buf, b"P");
+    ensure_eq!(rslt.success.main, 1);
+    ensure_eq!(rslt.ancillary_truncated(), true);
+    // Both the peeked copy and the one discarded by the kernel are closed
+    ensure_eq!((&kept).read(&mut buf).context("socket receive failed")?, 0);
+    Ok(())
+}
+
+#[cfg(uds_msg_cmsg_cloexec)]
+pub(super) fn run_cloexec() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed, _kept) = UdStream::pair().context("socketpair creation failed")?;
+    let mut sabuf = CmsgVecBuf::new(0);
+    sabuf.add_message(&FileDescriptors::new(&[passed.as_fd()]));
+
+    let mut buf = [0; 1];
+    let mut abuf = CmsgVecBuf::new(64);
+    for inheritable in [false, true] {
+        (&sender)
+            .write_ancillary(b"C", sabuf.as_ref())
+            .context("ancillary send failed")?;
+        abuf.clear();
+        if inheritable {
+            receiver
+                .recv_ancillary_vectored_inheritable(&mut [IoSliceMut::new(&mut buf)], &mut abuf, 0)
+                .map(drop)
+        } else {
+            (&receiver).read_ancillary(&mut buf, &mut abuf).map(drop)
+        }
+        .context("ancillary receive failed")?;
+
+        let received = take_received_fds(&abuf)?.next().unwrap();
+        let fdflags = unsafe { libc::fcntl(received.as_raw_fd(), libc::F_GETFD) };
+        ensure_eq!(fdflags & libc::FD_CLOEXEC == 0, inheritable);
+    }
+    Ok(())
+}
+
+pub(super) fn run_with_fds() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed_a, mut kept_a) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed_b, _kept_b) = UdStream::pair().context("socketpair creation failed")?;
+
+    match sender.send_with_fds(b"", &[passed_a.as_fd()]) {
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {}
+        els => bail!("sending file descriptors without data returned {els:?} instead of InvalidInput"),
+    }
+    sender
+        .send_with_fds(b"A", &[passed_a.as_fd(), passed_b.as_fd()])
+        .context("send with file descriptors failed")?;
+    let mut buf = [0; 8];
+    let (len, fds, truncated) = receiver
+        .recv_with_fds(&mut buf, 2)
+        .context("receive with file descriptors failed")?;
+    ensure_eq!(&buf[..len], b"A");
+    ensure_eq!(fds.len(), 2);
+    ensure_eq!(truncated, false);
+
+    let received = UdStream::from(fds.into_iter().next().unwrap());
+    (&received)
+        .write_all(MSG.as_bytes())
+        .context("send via passed descriptor failed")?;
+    let mut msg = [0; 64];
+    let msg = &mut msg[..MSG.len()];
+    kept_a
+        .read_exact(msg)
+        .context("receive from passed descriptor failed")?;
+    ensure_eq!(msg, MSG.as_bytes());
+
+    // More descriptors than there is room for: some arrive, and the truncation is reported
+    sender
+        .send_with_fds(b"B", &[passed_a.as_fd(), passed_b.as_fd(), passed_a.as_fd()])
+        .context("send with file descriptors failed")?;
+    let (len, fds, truncated) = receiver
+        .recv_with_fds(&mut buf, 1)
+        .context("receive with file descriptors failed")?;
+    ensure_eq!(&buf[..len], b"B");
+    ensure_eq!(fds.len() <= 1, true);
+    ensure_eq!(truncated, true);
+
+    sender
+        .send_with_fds(b"C", &[])
+        .context("send without file descriptors failed")?;
+    let (len, fds, truncated) = receiver
+        .recv_with_fds(&mut buf, 4)
+        .context("receive with file descriptors failed")?;
+    ensure_eq!(&buf[..len], b"C");
+    ensure_eq!(fds.is_empty(), true);
+    ensure_eq!(truncated, false);
+    Ok(())
+}
+
+pub(super) fn run_write_all_with_fds() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed_end, _kept_end) = UdStream::pair().context("socketpair creation failed")?;
+    // Much bigger than the send buffer, so that the sender has to go through more than one system call
+    let payload = (0..1 << 20).map(|i| i as u8).collect::<Vec<_>>();
+
+    let (received, fds) = thread::scope(|scope| {
+        let sending = scope.spawn(|| sender.write_all_with_fds(&payload, &[passed_end.as_fd()]));
+        let mut received = Vec::with_capacity(payload.len());
+        let mut fds = Vec::new();
+        let mut buf = vec![0; 1 << 16];
+        while received.len() < payload.len() {
+            let (len, new_fds, _) = receiver
+                .recv_with_fds(&mut buf, 4)
+                .context("receive with file descriptors failed")?;
+            if len == 0 {
+                bail!("unexpected end of file after {} bytes", received.len());
+            }
+            received.extend_from_slice(&buf[..len]);
+            fds.extend(new_fds);
+        }
+        match sending.join() {
+            Ok(rslt) => rslt.context("send with file descriptors failed")?,
+            Err(_) => bail!("sending thread panicked"),
+        }
+        Ok((received, fds))
+    })?;
+    ensure_eq!(received == payload, true);
+    ensure_eq!(fds.len(), 1);
+
+    match sender.write_all_with_fds(b"", &[passed_end.as_fd()]) {
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {}
+        els => bail!("sending file descriptors without data returned {els:?} instead of InvalidInput"),
+    }
+    sender
+        .write_all_with_fds(b"", &[])
+        .context("empty send without file descriptors failed")?;
+    Ok(())
+}
+
+pub(super) fn run_auto() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed_end, mut kept_end) = UdStream::pair().context("socketpair creation failed")?;
+    let fds = [passed_end.as_fd(); 32];
+
+    sender
+        .send_with_fds(b"A", &fds)
+        .context("send with file descriptors failed")?;
+    let mut abuf = CmsgVecBuf::new(0);
+    let mut buf = [0; 1];
+    let rslt = receiver
+        .recv_ancillary_auto(&mut buf, &mut abuf, 4096)
+        .context("receive with automatic buffer growth failed")?;
+    ensure_eq!(&buf, b"A");
+    ensure_eq!(rslt.ancillary_truncated(), false);
+    let received = FileDescriptors::take_limited(abuf.as_ref(), fds.len()).context("decoding failed")?;
+    ensure_eq!(received.len(), fds.len());
+    drop(received);
+
+    // Capped below what's needed: the data still arrives, but the file descriptors are cut short
+    sender
+        .send_with_fds(b"B", &fds)
+        .context("send with file descriptors failed")?;
+    let mut abuf = CmsgVecBuf::new(0);
+    let rslt = receiver
+        .recv_ancillary_auto(&mut buf, &mut abuf, 64)
+        .context("receive with automatic buffer growth failed")?;
+    ensure_eq!(&buf, b"B");
+    ensure_eq!(rslt.ancillary_truncated(), true);
+    ensure_eq!(abuf.capacity() < 128, true);
+    let received = FileDescriptors::take_limited(abuf.as_ref(), fds.len()).context("decoding failed")?;
+    ensure_eq!(received.len() < fds.len(), true);
+    drop(received);
+
+    // A cap that isn't a power of two times the starting capacity must not be overshot by the last doubling
+    sender
+        .send_with_fds(b"C", &fds)
+        .context("send with file descriptors failed")?;
+    let mut abuf = CmsgVecBuf::new(64);
+    let rslt = receiver
+        .recv_ancillary_auto(&mut buf, &mut abuf, 100)
+        .context("receive with automatic buffer growth failed")?;
+    ensure_eq!(&buf, b"C");
+    ensure_eq!(rslt.ancillary_truncated(), true);
+    ensure_eq!(abuf.capacity(), 100);
+    drop(FileDescriptors::take_limited(abuf.as_ref(), fds.len()).context("decoding failed")?);
+
+    // If any of the copies installed by the peeks had been leaked, this would block instead of reaching end of file
+    drop(passed_end);
+    let mut rest = Vec::new();
+    kept_end
+        .read_to_end(&mut rest)
+        .context("receive from passed descriptor failed")?;
+    ensure_eq!(rest.len(), 0);
+    Ok(())
+}
+
+pub(super) fn run_uninit() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed_end, _kept_end) = UdStream::pair().context("socketpair creation failed")?;
+
+    let mut abuf = CmsgVecBuf::new(0);
+    abuf.add_message(&FileDescriptors::new(&[passed_end.as_fd()]));
+    (&sender)
+        .write_ancillary(b"F", abuf.as_ref())
+        .context("ancillary send failed")?;
+
+    // Only the part written by the kernel may be exposed
+    let mut storage = [MaybeUninit::uninit(); 256];
+    let mut abuf = CmsgMutBuf::new(&mut storage);
+    ensure_eq!(abuf.valid_len(), 0);
+    let mut buf = [0; 1];
+    let rslt = (&receiver)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    let fd_size = size_of::<RawFd>() as c_uint;
+    ensure_eq!(abuf.valid_len(), rslt.ancillary);
+    ensure_eq!(abuf.valid_len(), Cmsg::cmsg_space_for_payload_size(fd_size));
+    ensure_eq!(abuf.as_ref().inner().len(), abuf.valid_len());
+
+    let fds = match abuf.as_ref().decode::<FileDescriptors>().next() {
+        Some(Ok(fds)) => fds,
+        Some(Err(e)) => bail!("parsing of file descriptors failed: {e}"),
+        None => bail!("no file descriptors received"),
+    };
+    ensure_eq!(fds.len(), 1);
+    Ok(())
+}
+
+pub(super) fn run_read_exact() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (first, _first_peer) = UdStream::pair().context("socketpair creation failed")?;
+    let (second, _second_peer) = UdStream::pair().context("socketpair creation failed")?;
+
+    // Each chunk carries its own control message, which keeps the kernel from coalescing them into one receive.
+    for (chunk, fd) in [(b"Hel", first.as_fd()), (b"lo!", second.as_fd())] {
+        let mut abuf = CmsgVecBuf::new(0);
+        abuf.add_message(&FileDescriptors::new(&[fd]));
+        (&sender)
+            .write_ancillary(chunk, abuf.as_ref())
+            .context("ancillary send failed")?;
+    }
+
+    // Exactly enough space for two single-descriptor messages, padding included.
+    let fd_size = size_of::<RawFd>() as c_uint;
+    let mut abuf = CmsgVecBuf::new(Cmsg::cmsg_space_for_payload_size(fd_size) * 2);
+    let mut buf = [0; 6];
+    let total = receiver
+        .read_exact_ancillary(&mut buf, &mut abuf)
+        .context("exact ancillary receive failed")?;
+    ensure_eq!(&buf, b"Hello!");
+    ensure_eq!(total.main, buf.len());
+    ensure_eq!(total.ancillary, abuf.as_ref().inner().len());
+    ensure_eq!(abuf.is_truncated(), false);
+
+    let mut nfds = 0;
+    for fds in abuf.as_ref().decode::<FileDescriptors>() {
+        match fds {
+            Ok(fds) => nfds += fds.len(),
+            Err(e) => bail!("parsing of file descriptors failed: {e}"),
+        }
+    }
+    ensure_eq!(nfds, 2);
+
+    (&sender).write_all(b"He").context("socket send failed")?;
+    drop(sender);
+    let mut abuf = CmsgVecBuf::new(0);
+    match receiver.read_exact_ancillary(&mut buf, &mut abuf) {
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {}
+        els => bail!("exact receive from a closed stream returned {els:?} instead of UnexpectedEof"),
+    }
+    Ok(())
+}
+
+pub(super) fn run_reserve() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (first, _first_peer) = UdStream::pair().context("socketpair creation failed")?;
+    let (second, _second_peer) = UdStream::pair().context("socketpair creation failed")?;
+
+    let mut abuf = CmsgVecBuf::with_capacity(0);
+    ensure_eq!(abuf.capacity(), 0);
+    for fd in [first.as_fd(), second.as_fd()] {
+        abuf.clear();
+        abuf.add_message(&FileDescriptors::new(&[fd]));
+        (&sender)
+            .write_ancillary(b"F", abuf.as_ref())
+            .context("ancillary send failed")?;
+    }
+
+    let mut abuf = CmsgVecBuf::with_capacity(0);
+    let mut buf = [0; 1];
+    for _ in 0..2 {
+        // Grow the buffer without discarding the message received in the previous iteration. Decoding has to wait
+        // until the end, since every decode takes ownership of the descriptors.
+        let prev_len = abuf.as_ref().inner().len();
+        abuf.reserve(64);
+        ensure_eq!(abuf.capacity() >= prev_len + 64, true);
+        let rslt = (&receiver)
+            .read_ancillary(&mut buf, &mut abuf)
+            .context("ancillary receive failed")?;
+        ensure_eq!(&buf, b"F");
+        ensure_eq!(abuf.as_ref().inner().len(), prev_len + rslt.ancillary);
+    }
+    let mut nmsgs = 0;
+    for fds in abuf.as_ref().decode::<FileDescriptors>() {
+        match fds {
+            Ok(fds) => ensure_eq!(fds.len(), 1),
+            Err(e) => bail!("parsing of file descriptors failed: {e}"),
+        }
+        nmsgs += 1;
+    }
+    ensure_eq!(nmsgs, 2);
+    Ok(())
+}
+
+pub(super) fn run_add_to_filled() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (first, _first_peer) = UdStream::pair().context("socketpair creation failed")?;
+    let (second, _second_peer) = UdStream::pair().context("socketpair creation failed")?;
+
+    // The second message goes into a buffer that's already partially filled and isn't left with room for a third
+    let mut abuf = CmsgVecBuf::new(0);
+    let first_len = abuf.add_message(&FileDescriptors::new(&[first.as_fd()]));
+    let second_len = abuf.add_message(&FileDescriptors::new(&[second.as_fd()]));
+    ensure_eq!(
+        first_len,
+        Cmsg::cmsg_space_for_payload_size(size_of::<RawFd>() as c_uint)
+    );
+    ensure_eq!(second_len, first_len);
+    ensure_eq!(abuf.valid_len(), first_len + second_len);
+    (&sender)
+        .write_ancillary(b"F", abuf.as_ref())
+        .context("ancillary send failed")?;
+
+    let mut abuf = CmsgVecBuf::new(64);
+    let mut buf = [0; 1];
+    (&receiver)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    let mut nfds = 0;
+    for fds in abuf.as_ref().decode::<FileDescriptors>() {
+        match fds {
+            Ok(fds) => nfds += fds.len(),
+            Err(e) => bail!("parsing of file descriptors failed: {e}"),
+        }
+    }
+    ensure_eq!(nfds, 2);
+    Ok(())
+}
+
+pub(super) fn run_add_with_room() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (first, _first_peer) = UdStream::pair().context("socketpair creation failed")?;
+    let (second, _second_peer) = UdStream::pair().context("socketpair creation failed")?;
+
+    // Both messages leave room for more in the buffer, so the padding after each of them ends where the next one starts
+    let mut abuf = CmsgVecBuf::new(256);
+    let msg_len = Cmsg::cmsg_space_for_payload_size(size_of::<RawFd>() as c_uint);
+    ensure_eq!(abuf.add_message(&FileDescriptors::new(&[first.as_fd()])), msg_len);
+    ensure_eq!(abuf.add_message(&FileDescriptors::new(&[second.as_fd()])), msg_len);
+    ensure_eq!(abuf.valid_len(), 2 * msg_len);
+    ensure_eq!(abuf.as_ref().cmsgs().count(), 2);
+    (&sender)
+        .write_ancillary(b"F", abuf.as_ref())
+        .context("ancillary send failed")?;
+
+    let mut abuf = CmsgVecBuf::new(64);
+    let mut buf = [0; 1];
+    (&receiver)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    let mut nfds = 0;
+    for fds in abuf.as_ref().decode::<FileDescriptors>() {
+        match fds {
+            Ok(fds) => nfds += fds.len(),
+            Err(e) => bail!("parsing of file descriptors failed: {e}"),
+        }
+    }
+    ensure_eq!(nfds, 2);
+    Ok(())
+}
+
+pub(super) fn run_buf_reader() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    let (passed_end, mut kept_end) = UdStream::pair().context("socketpair creation failed")?;
+
+    (&sender).write_all(b"plain\n").context("socket send failed")?;
+    let mut abuf = CmsgVecBuf::new(0);
+    abuf.add_message(&FileDescriptors::new(&[passed_end.as_fd()]));
+    (&sender)
+        .write_ancillary(b"with fd\n", abuf.as_ref())
+        .context("ancillary send failed")?;
+    drop(passed_end);
+
+    let mut reader = AncillaryBufReader::new(receiver, CmsgVecBuf::new(64));
+    let mut line = String::new();
+    reader.read_line(&mut line).context("socket receive failed")?;
+    ensure_eq!(line, "plain\n");
+
+    line.clear();
+    reader.read_line(&mut line).context("socket receive failed")?;
+    ensure_eq!(line, "with fd\n");
+    let end = "plain\nwith fd\n".len() as u64;
+    ensure_eq!(reader.position(), end);
+    // The first chunk may or may not have been received together with the second one.
+    match reader.ancillary_range() {
+        Some(r) if r.end == end && r.start <= "plain\n".len() as u64 => {}
+        els => bail!("unexpected range of data associated with the file descriptor: {els:?}"),
+    }
+
+    let abuf = reader.take_ancillary();
+    ensure_eq!(reader.ancillary_range(), None);
+    ensure_eq!(reader.ancillary().inner().len(), 0);
+    let mut fds = take_received_fds(&abuf)?;
+    let mut received = UdStream::from(fds.next().unwrap());
+    received
+        .write_all(MSG.as_bytes())
+        .context("send via passed descriptor failed")?;
+    let mut buf = [0; 64];
+    let buf = &mut buf[..MSG.len()];
+    kept_end
+        .read_exact(buf)
+        .context("receive from passed descriptor failed")?;
+    ensure_eq!(buf, MSG.as_bytes());
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use super::util::*;
+use color_eyre::eyre::{bail, Context};
+use interprocess::os::unix::udsocket::{
+    framed::{FramedUdStream, DEFAULT_MAX_FRAME_SIZE},
+    UdStream,
+};
+use std::io::{self, Write};
+
+pub(super) fn run() -> TestResult {
+    let (a, b) = UdStream::pair().context("socketpair creation failed")?;
+    let (a, b) = (FramedUdStream::new(a), FramedUdStream::from(b));
+    ensure_eq!(b.max_frame_size(), DEFAULT_MAX_FRAME_SIZE);
+
+    let big = b"0123456789abcdef".repeat(256);
+    let msgs: [&[u8]; 3] = [b"first", b"", &big];
+    for msg in msgs {
+        a.send_msg(msg).context("message send failed")?;
+    }
+    for msg in msgs {
+        ensure_eq!(b.recv_msg().context("message receive failed")?, msg);
+    }
+
+    // The other end speaks the same format without this crate
+    b.get_ref()
+        .write_all(&[0, 0, 0, 5, b'h', b'e', b'l', b'l', b'o'])
+        .context("socket send failed")?;
+    ensure_eq!(a.recv_msg().context("message receive failed")?, b"hello");
+
+    drop(b);
+    match a.recv_msg() {
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {}
+        els => bail!("receive after the peer hung up returned {els:?} instead of UnexpectedEof"),
+    }
+    Ok(())
+}
+
+pub(super) fn run_max_frame_size() -> TestResult {
+    let (a, b) = UdStream::pair().context("socketpair creation failed")?;
+    let (a, mut b) = (FramedUdStream::new(a), FramedUdStream::with_max_frame_size(b, 4));
+
+    a.send_msg(b"four").context("message send failed")?;
+    ensure_eq!(b.recv_msg().context("message receive failed")?, b"four");
+
+    a.send_msg(b"five!").context("message send failed")?;
+    match b.recv_msg() {
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => {}
+        els => bail!("receiving an oversized frame returned {els:?} instead of InvalidData"),
+    }
+
+    // A hostile prefix doesn't cause a huge allocation
+    b.set_max_frame_size(DEFAULT_MAX_FRAME_SIZE);
+    let b = b.into_inner();
+    let a = a.into_inner();
+    (&a).write_all(&u32::MAX.to_be_bytes()).context("socket send failed")?;
+    match FramedUdStream::new(b).recv_msg() {
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => {}
+        els => bail!("receiving a frame with a hostile length returned {els:?} instead of InvalidData"),
+    }
+    Ok(())
+}
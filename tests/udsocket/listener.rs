@@ -0,0 +1,193 @@
+use super::util::*;
+use color_eyre::eyre::{bail, Context};
+use interprocess::{
+    os::unix::udsocket::{UdSocketPath, UdStream, UdStreamListener, UdStreamListenerBuilder},
+    TryClone,
+};
+use std::{
+    fs,
+    io::{self, Read, Write},
+    os::{
+        fd::OwnedFd,
+        unix::{fs::PermissionsExt, net::UnixListener},
+    },
+    sync::{Arc, Barrier},
+    thread,
+};
+
+pub(super) fn run_builder(mut namegen: NameGen) -> TestResult {
+    let builder = UdStreamListenerBuilder::new().mode(0o600).drop_guard(true).backlog(4);
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| builder.bind(nm))?;
+
+    let meta = fs::metadata(&*name).context("socket file metadata query failed")?;
+    ensure_eq!(meta.permissions().mode() & 0o777, 0o600);
+
+    drop(listener);
+    ensure_eq!(fs::metadata(&*name).is_err(), true);
+
+    Ok(())
+}
+
+pub(super) fn run_reclaim(mut namegen: NameGen) -> TestResult {
+    let (name, mut listener) = listen_and_pick_name(&mut namegen, |nm| UdStreamListener::bind(nm))?;
+    ensure_eq!(listener.set_reclaim_name_on_drop(true), true);
+    drop(listener);
+    ensure_eq!(fs::metadata(&*name).is_err(), true);
+
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| UdStreamListener::bind_with_drop_guard(nm))?;
+    let mut listener = UdStreamListener::from(OwnedFd::from(listener));
+    ensure_eq!(listener.set_reclaim_name_on_drop(true), false);
+    drop(listener);
+    ensure_eq!(fs::metadata(&*name).is_ok(), true);
+    fs::remove_file(&*name).context("socket file cleanup failed")?;
+
+    // Only the original deletes the file, clones keep their hands off
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| UdStreamListener::bind_with_drop_guard(nm))?;
+    drop(listener.try_clone().context("listener cloning failed")?);
+    ensure_eq!(fs::metadata(&*name).is_ok(), true);
+    let clone = listener.try_clone().context("listener cloning failed")?;
+    let _client = UdStream::connect(&*name).context("connect failed")?;
+    clone.accept().context("accept on a clone failed")?;
+    drop(listener);
+    ensure_eq!(fs::metadata(&*name).is_err(), true);
+
+    Ok(())
+}
+
+pub(super) fn run_nonblocking(mut namegen: NameGen) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| UdStreamListener::bind(nm))?;
+    ensure_eq!(
+        listener.is_nonblocking().context("nonblocking mode query failed")?,
+        false
+    );
+    listener
+        .set_nonblocking(true)
+        .context("enabling nonblocking mode failed")?;
+    ensure_eq!(
+        listener.is_nonblocking().context("nonblocking mode query failed")?,
+        true
+    );
+
+    match listener.accept() {
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+        els => bail!("nonblocking accept without pending connections returned {els:?} instead of WouldBlock"),
+    }
+    match listener.incoming().next() {
+        Some(Err(e)) if e.kind() == io::ErrorKind::WouldBlock => {}
+        els => bail!("nonblocking incoming iterator returned {els:?} instead of WouldBlock"),
+    }
+
+    let _client = UdStream::connect(&*name).context("connect failed")?;
+    listener
+        .accept()
+        .context("nonblocking accept of a pending connection failed")?;
+    Ok(())
+}
+
+pub(super) fn run_accept_nonblocking(mut namegen: NameGen) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| UdStreamListener::bind(nm))?;
+    for nonblocking in [false, true] {
+        listener
+            .set_nonblocking(nonblocking)
+            .context("nonblocking mode change failed")?;
+        if let Some(conn) = listener.accept_nonblocking().context("nonblocking accept failed")? {
+            bail!("nonblocking accept without pending connections returned {conn:?}");
+        }
+
+        let client = UdStream::connect(&*name).context("connect failed")?;
+        let Some(conn) = listener.accept_nonblocking().context("nonblocking accept failed")? else {
+            bail!("nonblocking accept of a pending connection returned None");
+        };
+        ensure_eq!(
+            listener.is_nonblocking().context("nonblocking mode query failed")?,
+            nonblocking
+        );
+        (&client).write_all(b"!").context("socket send failed")?;
+        let mut buf = [0; 1];
+        (&conn).read_exact(&mut buf).context("socket receive failed")?;
+        ensure_eq!(&buf, b"!");
+    }
+    Ok(())
+}
+
+pub(super) fn run_accept_nonblocking_race(mut namegen: NameGen) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| {
+        UdStreamListenerBuilder::new().nonblocking(true).bind(nm)
+    })?;
+    let listener = Arc::new(listener);
+    // Two accepters compete for one pending connection, and the loser must not end up waiting for another one
+    for _ in 0..32 {
+        let _client = UdStream::connect(&*name).context("connect failed")?;
+        let barrier = Arc::new(Barrier::new(2));
+        let accepters = [(); 2].map(|()| {
+            let (listener, barrier) = (Arc::clone(&listener), Arc::clone(&barrier));
+            thread::spawn(move || {
+                barrier.wait();
+                listener.accept_nonblocking()
+            })
+        });
+        let mut accepted = 0;
+        for accepter in accepters {
+            if accepter.join().unwrap().context("nonblocking accept failed")?.is_some() {
+                accepted += 1;
+            }
+        }
+        ensure_eq!(accepted, 1);
+    }
+    Ok(())
+}
+
+pub(super) fn run_accept_cloexec(mut namegen: NameGen) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| UdStreamListener::bind(nm))?;
+    let _client = UdStream::connect(&*name).context("connect failed")?;
+    let conn = listener.accept().context("accept failed")?;
+    ensure_eq!(conn.get_cloexec().context("close-on-exec flag query failed")?, true);
+
+    let _client = UdStream::connect(&*name).context("connect failed")?;
+    let (conn, _) = listener.accept_with_addr().context("accept failed")?;
+    ensure_eq!(conn.get_cloexec().context("close-on-exec flag query failed")?, true);
+    Ok(())
+}
+
+pub(super) fn run_std_conversion(mut namegen: NameGen) -> TestResult {
+    // Adopting a listener set up by someone else, as with socket activation
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| UnixListener::bind(nm))?;
+    let listener = UdStreamListener::from(listener);
+    let _client = UdStream::connect(&*name).context("connect failed")?;
+    let (_conn, addr) = listener.accept_with_addr().context("accept failed")?;
+    ensure_eq!(addr, UdSocketPath::Unnamed);
+
+    // Handing one off, after which the socket file is left alone
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| UdStreamListener::bind_with_drop_guard(nm))?;
+    let listener = UnixListener::from(listener);
+    let _client = UdStream::connect(&*name).context("connect failed")?;
+    listener.accept().context("accept failed")?;
+    drop(listener);
+    ensure_eq!(fs::metadata(&*name).is_ok(), true);
+    fs::remove_file(&*name).context("socket file cleanup failed")?;
+
+    Ok(())
+}
+
+/// Checks that abstract names are bound without nul padding by connecting to one with the standard library, which
+/// passes the exact length of the name to the kernel.
+#[cfg(target_os = "linux")]
+pub(super) fn run_abstract() -> TestResult {
+    use interprocess::os::unix::udsocket::UdSocket;
+    use std::os::{
+        linux::net::SocketAddrExt,
+        unix::net::{SocketAddr, UnixStream},
+    };
+
+    let name = format!("interprocess-test-abstract-{}", std::process::id());
+    let path = UdSocketPath::abstract_name(name.as_bytes()).context("abstract name construction failed")?;
+    ensure_eq!(path.is_abstract(), true);
+    let listener = UdStreamListener::bind(&path).context("listener bind failed")?;
+
+    let addr = SocketAddr::from_abstract_name(name.as_bytes()).context("address construction failed")?;
+    let _client = UnixStream::connect_addr(&addr).context("connect failed")?;
+    let conn = listener.accept().context("accept failed")?;
+    ensure_eq!(conn.local_addr().context("local address query failed")?, path);
+
+    Ok(())
+}
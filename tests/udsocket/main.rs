@@ -5,10 +5,25 @@
 mod util;
 use util::*;
 
+mod cmsg;
+mod collector;
 #[cfg(any(uds_cont_credentials, uds_cmsgcred))]
 mod credentials;
+#[cfg(uds_ancillary_credentials)]
+mod credentials_decode;
 mod datagram;
+mod eintr;
+mod fd_passing;
+mod framed;
+mod listener;
+mod path;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod security_context;
+#[cfg(not(target_vendor = "apple"))]
+mod seqpacket;
 mod stream;
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod timestamp;
 
 #[test]
 fn udsocket_stream() -> TestResult {
@@ -21,6 +36,413 @@ fn udsocket_stream() -> TestResult {
     Ok(())
 }
 
+#[test]
+fn udsocket_stream_pair() -> TestResult {
+    install_color_eyre();
+    stream::run_pair()
+}
+
+#[test]
+fn udsocket_listener_builder() -> TestResult {
+    install_color_eyre();
+    listener::run_builder(NameGen::new(make_id!(), false))
+}
+
+#[test]
+fn udsocket_listener_reclaim() -> TestResult {
+    install_color_eyre();
+    listener::run_reclaim(NameGen::new(make_id!(), false))
+}
+
+#[test]
+fn udsocket_listener_nonblocking() -> TestResult {
+    install_color_eyre();
+    listener::run_nonblocking(NameGen::new(make_id!(), false))
+}
+
+#[test]
+fn udsocket_listener_accept_cloexec() -> TestResult {
+    install_color_eyre();
+    listener::run_accept_cloexec(NameGen::new(make_id!(), false))
+}
+
+#[test]
+fn udsocket_listener_accept_nonblocking() -> TestResult {
+    install_color_eyre();
+    listener::run_accept_nonblocking(NameGen::new(make_id!(), false))
+}
+
+#[test]
+fn udsocket_listener_accept_nonblocking_race() -> TestResult {
+    install_color_eyre();
+    listener::run_accept_nonblocking_race(NameGen::new(make_id!(), false))
+}
+
+#[test]
+fn udsocket_listener_std_conversion() -> TestResult {
+    install_color_eyre();
+    listener::run_std_conversion(NameGen::new(make_id!(), false))
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn udsocket_listener_abstract() -> TestResult {
+    install_color_eyre();
+    listener::run_abstract()
+}
+
+#[test]
+fn udsocket_path_eq() -> TestResult {
+    install_color_eyre();
+    path::run_eq()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_path_eq_abstract() -> TestResult {
+    install_color_eyre();
+    path::run_eq_abstract()
+}
+
+#[test]
+fn udsocket_path_round_trip() -> TestResult {
+    install_color_eyre();
+    path::run_round_trip()
+}
+
+#[test]
+fn udsocket_path_os_conversions() -> TestResult {
+    install_color_eyre();
+    path::run_os_conversions()
+}
+
+#[test]
+fn udsocket_path_display() -> TestResult {
+    install_color_eyre();
+    path::run_display()
+}
+
+#[test]
+fn udsocket_path_generate_temp() -> TestResult {
+    install_color_eyre();
+    path::run_generate_temp()
+}
+
+#[test]
+fn udsocket_cmsg_validate() -> TestResult {
+    install_color_eyre();
+    cmsg::run_validate()
+}
+
+#[test]
+fn udsocket_cmsg_relay() -> TestResult {
+    install_color_eyre();
+    cmsg::run_relay()
+}
+
+#[test]
+fn udsocket_cmsg_forward() -> TestResult {
+    install_color_eyre();
+    cmsg::run_forward()
+}
+
+#[test]
+fn udsocket_cmsg_clear_and_resize() -> TestResult {
+    install_color_eyre();
+    cmsg::run_clear_and_resize()
+}
+
+#[test]
+fn udsocket_cmsg_collect() -> TestResult {
+    install_color_eyre();
+    cmsg::run_collect()
+}
+
+#[test]
+fn udsocket_cmsg_clone() -> TestResult {
+    install_color_eyre();
+    cmsg::run_clone()
+}
+
+#[test]
+fn udsocket_cmsg_iter_raw() -> TestResult {
+    install_color_eyre();
+    cmsg::run_iter_raw()
+}
+
+#[test]
+fn udsocket_collector_composition() -> TestResult {
+    install_color_eyre();
+    collector::run_composition()
+}
+
+#[test]
+fn udsocket_collector_flags() -> TestResult {
+    install_color_eyre();
+    collector::run_flags()
+}
+
+#[test]
+fn udsocket_collector_stream_ops() -> TestResult {
+    install_color_eyre();
+    collector::run_stream_ops()
+}
+
+#[test]
+fn udsocket_collector_datagram_ops() -> TestResult {
+    install_color_eyre();
+    collector::run_datagram_ops()
+}
+
+#[test]
+fn udsocket_stream_connect_timeout() -> TestResult {
+    install_color_eyre();
+    stream::run_connect_timeout(NameGen::new(make_id!(), false))
+}
+
+#[test]
+fn udsocket_stream_is_peer_connected() -> TestResult {
+    install_color_eyre();
+    stream::run_is_peer_connected()
+}
+
+#[test]
+fn udsocket_stream_read_until_eof() -> TestResult {
+    install_color_eyre();
+    stream::run_read_until_eof()
+}
+
+#[test]
+fn udsocket_stream_std_conversion() -> TestResult {
+    install_color_eyre();
+    stream::run_std_conversion()
+}
+
+#[test]
+fn udsocket_stream_cloexec() -> TestResult {
+    install_color_eyre();
+    stream::run_cloexec()
+}
+
+#[test]
+fn udsocket_stream_send_all_vectored() -> TestResult {
+    install_color_eyre();
+    stream::run_send_all_vectored()
+}
+
+#[test]
+fn udsocket_stream_poll_read_ready() -> TestResult {
+    install_color_eyre();
+    stream::run_poll_read_ready()
+}
+
+#[test]
+fn udsocket_stream_recv_exact_with_deadline() -> TestResult {
+    install_color_eyre();
+    stream::run_recv_exact_with_deadline()
+}
+
+#[test]
+fn udsocket_stream_shared() -> TestResult {
+    install_color_eyre();
+    stream::run_shared()
+}
+
+#[test]
+fn udsocket_stream_timeout_guard() -> TestResult {
+    install_color_eyre();
+    stream::run_timeout_guard()
+}
+
+#[cfg(not(target_vendor = "apple"))]
+#[test]
+fn udsocket_seqpacket() -> TestResult {
+    install_color_eyre();
+    seqpacket::run(NameGen::new(make_id!(), false))?;
+    seqpacket::run_ancillary()
+}
+
+#[test]
+fn udsocket_stream_split() -> TestResult {
+    install_color_eyre();
+    stream::run_split()?;
+    stream::run_reunite()
+}
+
+#[test]
+fn udsocket_stream_read_vectored_exact() -> TestResult {
+    install_color_eyre();
+    stream::run_read_vectored_exact()
+}
+
+#[test]
+fn udsocket_stream_splice() -> TestResult {
+    install_color_eyre();
+    stream::run_splice()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_stream_mark() -> TestResult {
+    install_color_eyre();
+    stream::run_mark()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_stream_peer_pidfd() -> TestResult {
+    install_color_eyre();
+    stream::run_peer_pidfd()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_stream_peer_groups() -> TestResult {
+    install_color_eyre();
+    stream::run_peer_groups()
+}
+
+#[test]
+fn udsocket_stream_dup_to() -> TestResult {
+    install_color_eyre();
+    stream::run_dup_to()
+}
+
+#[test]
+fn udsocket_stream_try_clone_flags() -> TestResult {
+    install_color_eyre();
+    stream::run_try_clone_flags()
+}
+
+#[test]
+fn udsocket_stream_buffer_sizes() -> TestResult {
+    install_color_eyre();
+    stream::run_buffer_sizes()
+}
+
+#[test]
+fn udsocket_stream_timeouts() -> TestResult {
+    install_color_eyre();
+    stream::run_timeouts()
+}
+
+#[test]
+fn udsocket_fdops_flush() -> TestResult {
+    install_color_eyre();
+    stream::run_fdops_flush()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_stream_take_error() -> TestResult {
+    install_color_eyre();
+    stream::run_take_error()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_stream_drain_send_buffer() -> TestResult {
+    install_color_eyre();
+    stream::run_drain_send_buffer()
+}
+
+#[test]
+fn udsocket_framed() -> TestResult {
+    install_color_eyre();
+    framed::run()
+}
+
+#[test]
+fn udsocket_framed_max_frame_size() -> TestResult {
+    install_color_eyre();
+    framed::run_max_frame_size()
+}
+
+#[test]
+fn udsocket_fd_passing() -> TestResult {
+    install_color_eyre();
+    fd_passing::run()
+}
+
+#[test]
+fn udsocket_fd_passing_uninit_buffer() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_uninit()
+}
+
+#[test]
+fn udsocket_fd_passing_pipe() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_pipe()
+}
+
+#[test]
+fn udsocket_fd_passing_with_flags() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_with_flags()
+}
+
+#[cfg(uds_msg_cmsg_cloexec)]
+#[test]
+fn udsocket_fd_passing_cloexec() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_cloexec()
+}
+
+#[test]
+fn udsocket_fd_passing_with_fds() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_with_fds()
+}
+
+#[test]
+fn udsocket_fd_passing_write_all_with_fds() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_write_all_with_fds()
+}
+
+#[test]
+fn udsocket_fd_passing_auto() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_auto()
+}
+
+#[test]
+fn udsocket_fd_passing_take_limited() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_take_limited()
+}
+
+#[test]
+fn udsocket_read_exact_ancillary() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_read_exact()
+}
+
+#[test]
+fn udsocket_ancillary_buf_reader() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_buf_reader()
+}
+
+#[test]
+fn udsocket_cmsg_buffer_growth() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_reserve()
+}
+
+#[test]
+fn udsocket_cmsg_add_to_filled() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_add_to_filled()
+}
+
+#[test]
+fn udsocket_cmsg_add_with_room() -> TestResult {
+    install_color_eyre();
+    fd_passing::run_add_with_room()
+}
+
 #[cfg(uds_cont_credentials)]
 #[test]
 fn udsocket_continuous_credentials() -> TestResult {
@@ -33,6 +455,27 @@ fn udsocket_continuous_credentials() -> TestResult {
     Ok(())
 }
 
+#[cfg(uds_cont_credentials)]
+#[test]
+fn udsocket_continuous_credentials_toggle() -> TestResult {
+    install_color_eyre();
+    credentials::run_toggle()
+}
+
+#[cfg(any(uds_ucred, uds_xucred, uds_unpcbid, uds_sockpeercred))]
+#[test]
+fn udsocket_peer_credentials_eq() -> TestResult {
+    install_color_eyre();
+    credentials::run_peer_eq()
+}
+
+#[cfg(uds_ancillary_credentials)]
+#[test]
+fn udsocket_truncated_credentials() -> TestResult {
+    install_color_eyre();
+    credentials_decode::run_truncated()
+}
+
 #[cfg(uds_cmsgcred)]
 #[test]
 fn udsocket_explicitly_sent_credentials() -> TestResult {
@@ -55,3 +498,72 @@ fn udsocket_datagram() -> TestResult {
     }
     Ok(())
 }
+
+#[test]
+fn udsocket_datagram_send_to() -> TestResult {
+    use datagram::*;
+    install_color_eyre();
+    run_send_to(NameGen::new(make_id!(), false))?;
+    if cfg!(target_os = "linux") {
+        run_send_to(NameGen::new(make_id!(), true))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn udsocket_datagram_disconnect() -> TestResult {
+    use datagram::*;
+    install_color_eyre();
+    run_disconnect(NameGen::new(make_id!(), false))?;
+    if cfg!(target_os = "linux") {
+        run_disconnect(NameGen::new(make_id!(), true))?;
+    }
+    Ok(())
+}
+
+#[test]
+fn udsocket_datagram_truncation() -> TestResult {
+    install_color_eyre();
+    datagram::run_truncation()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_security_context() -> TestResult {
+    install_color_eyre();
+    security_context::run()?;
+    security_context::run_parse()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_timestamp() -> TestResult {
+    install_color_eyre();
+    timestamp::run()?;
+    timestamp::run_parse()
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn udsocket_timestamp_datagram() -> TestResult {
+    install_color_eyre();
+    timestamp::run_datagram()
+}
+
+#[test]
+fn udsocket_eintr_recv() -> TestResult {
+    install_color_eyre();
+    eintr::run_recv()
+}
+
+#[test]
+fn udsocket_eintr_accept() -> TestResult {
+    install_color_eyre();
+    eintr::run_accept()
+}
+
+#[test]
+fn udsocket_eintr_recv_timeout() -> TestResult {
+    install_color_eyre();
+    eintr::run_recv_timeout()
+}
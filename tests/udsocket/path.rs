@@ -0,0 +1,207 @@
+use super::util::*;
+use color_eyre::eyre::{bail, Context};
+use interprocess::os::unix::udsocket::{Namespace, ToUdSocketPath, UdSocketPath};
+use std::{
+    borrow::Cow,
+    collections::hash_map::DefaultHasher,
+    ffi::{CStr, CString},
+    hash::{Hash, Hasher},
+};
+
+fn hash_of(path: &UdSocketPath<'_>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    hasher.finish()
+}
+
+pub(super) fn run_eq() -> TestResult {
+    let name = CStr::from_bytes_with_nul(b"/tmp/interprocess-path-eq\0").unwrap();
+    let borrowed = UdSocketPath::File(Cow::Borrowed(name));
+    let owned = UdSocketPath::File(Cow::Owned(CString::from(name)));
+    ensure_eq!(borrowed, owned);
+    ensure_eq!(hash_of(&borrowed), hash_of(&owned));
+
+    let converted = "/tmp/interprocess-path-eq"
+        .to_socket_path()
+        .context("path conversion failed")?;
+    ensure_eq!(converted, borrowed);
+    ensure_eq!(hash_of(&converted), hash_of(&borrowed));
+    ensure_eq!(UdSocketPath::Unnamed == borrowed, false);
+    Ok(())
+}
+
+/// Abstract names are kept without the leading nul byte that marks them at the system call level, so they must not be
+/// confused with filesystem paths consisting of the same bytes.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn run_eq_abstract() -> TestResult {
+    use interprocess::os::unix::udsocket::{UdSocket, UdStream, UdStreamListener};
+    use std::collections::HashMap;
+
+    let name = format!("interprocess-test-path-eq-{}", std::process::id());
+    let abstract_name = UdSocketPath::abstract_name(name.as_bytes()).context("abstract name construction failed")?;
+    let file = UdSocketPath::File(Cow::Owned(CString::new(name.clone()).unwrap()));
+    ensure_eq!(abstract_name == file, false);
+    ensure_eq!(
+        format!("@{name}").to_socket_path().context("path conversion failed")?,
+        abstract_name
+    );
+
+    // The kernel reports the name back in its `\0`-prefixed form, which has to decode to an equal path
+    let listener = UdStreamListener::bind(&abstract_name).context("listener bind failed")?;
+    let client = UdStream::connect(&abstract_name).context("connect failed")?;
+    let _conn = listener.accept().context("accept failed")?;
+    let peer = client.peer_addr().context("peer address query failed")?;
+    ensure_eq!(hash_of(&peer), hash_of(&abstract_name));
+
+    let mut registry = HashMap::new();
+    registry.insert(peer, "server");
+    ensure_eq!(registry.get(&abstract_name), Some(&"server"));
+    ensure_eq!(registry.get(&file), None);
+    Ok(())
+}
+
+/// Reconstructs a path from what would be stored in a config file.
+fn rebuild(namespace: Namespace, bytes: &[u8]) -> TestResult<UdSocketPath<'static>> {
+    Ok(match namespace {
+        Namespace::Unnamed => UdSocketPath::Unnamed,
+        Namespace::Pathname => UdSocketPath::file_from_vec(bytes.to_vec()).context("path construction failed")?,
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        Namespace::Abstract => UdSocketPath::abstract_name(bytes).context("abstract name construction failed")?,
+    })
+}
+
+pub(super) fn run_round_trip() -> TestResult {
+    let file = "/tmp/interprocess-path-round-trip"
+        .to_socket_path()
+        .context("path conversion failed")?;
+    ensure_eq!(file.namespace(), Namespace::Pathname);
+    ensure_eq!(file.as_bytes(), b"/tmp/interprocess-path-round-trip");
+    ensure_eq!(rebuild(file.namespace(), file.as_bytes())?, file);
+
+    ensure_eq!(UdSocketPath::Unnamed.namespace(), Namespace::Unnamed);
+    ensure_eq!(UdSocketPath::Unnamed.as_bytes(), b"");
+    ensure_eq!(rebuild(Namespace::Unnamed, b"")?, UdSocketPath::Unnamed);
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let abstract_name = UdSocketPath::abstract_name(b"interprocess-path-round-trip")
+            .context("abstract name construction failed")?;
+        ensure_eq!(abstract_name.namespace(), Namespace::Abstract);
+        // No leading nul byte
+        ensure_eq!(abstract_name.as_bytes(), b"interprocess-path-round-trip");
+        ensure_eq!(
+            rebuild(abstract_name.namespace(), abstract_name.as_bytes())?,
+            abstract_name
+        );
+    }
+    Ok(())
+}
+
+pub(super) fn run_os_conversions() -> TestResult {
+    use interprocess::os::unix::udsocket::{UdSocket, UdStream, UdStreamListener, MAX_UDSOCKET_PATH_LEN};
+    use std::{ffi::OsStr, io, os::unix::ffi::OsStrExt, path::Path};
+
+    // Not valid UTF-8, so it couldn't have gone through a `str`
+    let name = [
+        format!("/tmp/interprocess-path-os-{}-", std::process::id()).as_bytes(),
+        b"\xff",
+    ]
+    .concat();
+    let path = Path::new(OsStr::from_bytes(&name));
+    let converted = path.to_socket_path().context("path conversion failed")?;
+    ensure_eq!(converted.namespace(), Namespace::Pathname);
+    ensure_eq!(converted.as_bytes(), &name[..]);
+    let listener = UdStreamListener::bind_with_drop_guard(path).context("listener bind failed")?;
+    let client = UdStream::connect(OsStr::from_bytes(&name)).context("connect failed")?;
+    listener.accept().context("accept failed")?;
+    ensure_eq!(client.peer_addr().context("peer address query failed")?, converted);
+
+    // C strings from FFI are borrowed as they are
+    let cstr = CStr::from_bytes_with_nul(b"/tmp/interprocess-path-cstr\0").unwrap();
+    match cstr.to_socket_path().context("path conversion failed")? {
+        UdSocketPath::File(Cow::Borrowed(borrowed)) => ensure_eq!(borrowed, cstr),
+        els => bail!("C string converted to {els:?} instead of a borrowed filesystem path"),
+    }
+    // `Path`s are always filesystem paths, even if they look like abstract names
+    ensure_eq!(
+        Path::new("@interprocess")
+            .to_socket_path()
+            .context("path conversion failed")?
+            .namespace(),
+        Namespace::Pathname
+    );
+
+    for rslt in [
+        Path::new("/tmp/interprocess\0path").to_socket_path(),
+        OsStr::new("/tmp/interprocess\0path").to_socket_path(),
+    ] {
+        match rslt {
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput => {}
+            els => bail!("path with an interior nul converted to {els:?} instead of failing"),
+        }
+    }
+
+    let long = CString::new(vec![b'a'; MAX_UDSOCKET_PATH_LEN + 1]).unwrap();
+    match UdStream::connect(long.as_c_str()) {
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {}
+        els => bail!("connecting to an overlong C string path returned {els:?} instead of InvalidInput"),
+    }
+    Ok(())
+}
+
+pub(super) fn run_display() -> TestResult {
+    ensure_eq!(UdSocketPath::Unnamed.to_string(), "<unnamed>");
+    let file = UdSocketPath::File(Cow::Borrowed(
+        CStr::from_bytes_with_nul(b"/tmp/interprocess-display.sock\0").unwrap(),
+    ));
+    ensure_eq!(file.to_string(), "/tmp/interprocess-display.sock");
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let abs = |name: &[u8]| UdSocketPath::abstract_name(name).map(|p| p.to_string());
+        ensure_eq!(
+            abs(b"interprocess").context("abstract name creation failed")?,
+            "@interprocess"
+        );
+        let escaped = abs(b"a\x01b\\x01\xff").context("abstract name creation failed")?;
+        ensure_eq!(escaped, r"@a\x01b\\x01\xff");
+        // A literal backslash sequence must not look the same as the byte it spells out
+        ensure_eq!(
+            abs(br"a\x01").context("abstract name creation failed")? == abs(b"a\x01").unwrap(),
+            false
+        );
+    }
+    Ok(())
+}
+
+pub(super) fn run_generate_temp() -> TestResult {
+    use interprocess::os::unix::udsocket::{UdStream, UdStreamListener};
+    use std::{env, ffi::OsStr, os::unix::ffi::OsStrExt, path::Path};
+
+    let first = UdSocketPath::generate_temp("interprocess-test");
+    let second = UdSocketPath::generate_temp("interprocess-test");
+    ensure_eq!(first == second, false);
+    ensure_eq!(first.namespace(), Namespace::Pathname);
+    let path = Path::new(OsStr::from_bytes(first.as_bytes()));
+    ensure_eq!(path.parent(), Some(&*env::temp_dir()));
+    match path.file_name().and_then(OsStr::to_str) {
+        Some(name) if name.starts_with("interprocess-test-") && name.ends_with(".sock") => {}
+        els => bail!("unexpected file name {els:?}"),
+    }
+
+    let listener = UdStreamListener::bind_with_drop_guard(first.borrow()).context("listener bind failed")?;
+    UdStream::connect(first.borrow()).context("connect failed")?;
+    listener.accept().context("accept failed")?;
+    drop(listener);
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let name = UdSocketPath::generate_temp_abstract("interprocess-test");
+        ensure_eq!(name.namespace(), Namespace::Abstract);
+        ensure_eq!(name == UdSocketPath::generate_temp_abstract("interprocess-test"), false);
+        let listener = UdStreamListener::bind(name.borrow()).context("listener bind failed")?;
+        UdStream::connect(name.borrow()).context("connect failed")?;
+        listener.accept().context("accept failed")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use super::util::*;
+use color_eyre::eyre::{bail, Context};
+use interprocess::os::unix::udsocket::{
+    cmsg::{
+        ancillary::{security_context::SecurityContext, ControlMessage, FromCmsg},
+        Cmsg, CmsgMutExt, CmsgVecBuf,
+    },
+    ReadAncillary, UdSocket, UdStream,
+};
+use std::io::Write;
+
+static LABEL: &[u8] = b"unconfined_u:unconfined_r:unconfined_t:s0\0";
+
+pub(super) fn run() -> TestResult {
+    let (sender, receiver) = UdStream::pair().context("socketpair creation failed")?;
+    receiver
+        .set_continuous_ancillary_security(true)
+        .context("failed to enable SO_PASSSEC")?;
+    (&sender).write_all(b"S").context("socket send failed")?;
+
+    let mut abuf = CmsgVecBuf::new(512);
+    let mut buf = [0; 1];
+    (&receiver)
+        .read_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    ensure_eq!(&buf, b"S");
+    // Whether a message arrives at all depends on the security module of the machine running the test.
+    for msg in abuf.as_ref().decode_all() {
+        match msg {
+            ControlMessage::SecurityContext(ctx) => {
+                ensure_eq!(ctx.label().is_empty(), false);
+                ensure_eq!(ctx.label().contains(&0), false);
+            }
+            ControlMessage::Malformed(e) => bail!("malformed control message received: {e}"),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn run_parse() -> TestResult {
+    let cmsg = unsafe {
+        // SAFETY: any bytes are a valid SCM_SECURITY payload
+        Cmsg::new(libc::SOL_SOCKET, 0x03, LABEL)
+    };
+    let ctx = match SecurityContext::try_parse(cmsg) {
+        Ok(ctx) => ctx,
+        Err(e) => bail!("parsing of security context failed: {e}"),
+    };
+    ensure_eq!(ctx.as_bytes(), LABEL);
+    ensure_eq!(ctx.label(), &LABEL[..LABEL.len() - 1]);
+    ensure_eq!(ctx.label_str(), Some("unconfined_u:unconfined_r:unconfined_t:s0"));
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use super::util::*;
+use color_eyre::eyre::{bail, Context};
+use interprocess::os::unix::udsocket::{
+    cmsg::{ancillary::file_descriptors::FileDescriptors, CmsgMutExt, CmsgVecBuf},
+    UdSeqpacket, UdSeqpacketListener, UdSocketPath,
+};
+use std::os::fd::AsFd;
+
+static FIRST_MSG: &[u8] = b"Hello";
+static SECOND_MSG: &[u8] = b"from client!";
+
+pub(super) fn run(mut namegen: NameGen) -> TestResult {
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| UdSeqpacketListener::bind(nm))?;
+    let client = std::thread::spawn(move || -> TestResult {
+        let conn = UdSeqpacket::connect(&*name).context("connect failed")?;
+        conn.send(FIRST_MSG).context("socket send failed")?;
+        conn.send(SECOND_MSG).context("socket send failed")?;
+        conn.send(SECOND_MSG).context("socket send failed")?;
+        Ok(())
+    });
+
+    let (conn, addr) = listener.accept_with_addr().context("accept failed")?;
+    ensure_eq!(addr, UdSocketPath::Unnamed);
+    client.join().unwrap()?;
+
+    // Message boundaries are preserved even though both messages are already queued
+    let mut buf = [0; 64];
+    let len = conn.recv(&mut buf).context("socket receive failed")?;
+    ensure_eq!(&buf[..len], FIRST_MSG);
+    let len = conn.recv(&mut buf).context("socket receive failed")?;
+    ensure_eq!(&buf[..len], SECOND_MSG);
+
+    // The rest of an oversized message is discarded rather than left for the next receive
+    let mut small = [0; 4];
+    let rslt = conn.recv_with_flags(&mut small, 0).context("socket receive failed")?;
+    ensure_eq!(rslt.main_truncated(), true);
+    ensure_eq!(&small[..], &SECOND_MSG[..small.len()]);
+
+    // The client has disconnected by now
+    let rslt = conn.recv_with_flags(&mut buf, 0).context("socket receive failed")?;
+    ensure_eq!(rslt.success.main, 0);
+    ensure_eq!(rslt.main_truncated(), false);
+    Ok(())
+}
+
+pub(super) fn run_ancillary() -> TestResult {
+    let (sender, receiver) = UdSeqpacket::pair().context("socketpair creation failed")?;
+    let (passed_end, _kept_end) = UdSeqpacket::pair().context("socketpair creation failed")?;
+
+    // Unlike on streams, an empty message can carry control messages
+    let mut abuf = CmsgVecBuf::new(0);
+    abuf.add_message(&FileDescriptors::new(&[passed_end.as_fd()]));
+    sender
+        .send_ancillary(b"", abuf.as_ref())
+        .context("ancillary send failed")?;
+
+    let mut abuf = CmsgVecBuf::new(64);
+    let mut buf = [0; 8];
+    let rslt = receiver
+        .recv_ancillary_with_flags(&mut buf, &mut abuf, 0)
+        .context("ancillary receive failed")?;
+    ensure_eq!(rslt.success.main, 0);
+    ensure_eq!(rslt.ancillary_truncated(), false);
+    let fds = match abuf.as_ref().decode::<FileDescriptors>().next() {
+        Some(Ok(fds)) => fds,
+        Some(Err(e)) => bail!("parsing of file descriptors failed: {e}"),
+        None => bail!("no file descriptors received"),
+    };
+    ensure_eq!(fds.len(), 1);
+    Ok(())
+}
@@ -1,10 +1,15 @@
 use super::util::*;
 use color_eyre::eyre::{bail, Context};
-use interprocess::os::unix::udsocket::{UdSocket, UdStream, UdStreamListener};
+use interprocess::os::unix::{
+    udsocket::{poll_read_ready, ReuniteError, ToUdSocketPath, UdSocket, UdSocketPath, UdStream, UdStreamListener},
+    FdOps,
+};
 use std::{
-    io::{BufRead, BufReader, Read, Write},
+    io::{self, BufRead, BufReader, BufWriter, IoSlice, IoSliceMut, Read, Write},
     net::Shutdown,
+    os::fd::{AsFd, AsRawFd, OwnedFd},
     sync::{mpsc::Sender, Arc},
+    time::{Duration, Instant},
 };
 
 static SERVER_MSG: &str = "Hello from server!\n";
@@ -15,17 +20,548 @@ pub(super) fn run(namegen: NameGen) -> TestResult {
     drive_server_and_multiple_clients(move |snd, nc| server(snd, nc, namegen, true), |nm| client(nm, true))
 }
 
+pub(super) fn run_pair() -> TestResult {
+    let (mut server, client) = UdStream::pair().context("socketpair creation failed")?;
+    ensure_eq!(
+        client.peer_addr().context("peer address query failed")?,
+        UdSocketPath::Unnamed
+    );
+
+    let mut client = BufReader::new(client);
+    let mut buffer = String::with_capacity(128);
+
+    client
+        .get_mut()
+        .write_all(CLIENT_MSG.as_bytes())
+        .context("socket send failed")?;
+    let mut peek_buf = [0; 5];
+    for _ in 0..2 {
+        let peeked = server.peek(&mut peek_buf).context("socket peek failed")?;
+        ensure_eq!(&peek_buf[..peeked], &CLIENT_MSG.as_bytes()[..peeked]);
+    }
+    BufReader::new(&mut server)
+        .read_line(&mut buffer)
+        .context("socket receive failed")?;
+    ensure_eq!(buffer, CLIENT_MSG);
+    buffer.clear();
+
+    server.write_all(SERVER_MSG.as_bytes()).context("socket send failed")?;
+    client.read_line(&mut buffer).context("socket receive failed")?;
+    ensure_eq!(buffer, SERVER_MSG);
+
+    match server.try_recv(&mut peek_buf) {
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+        els => bail!("try_recv on an empty socket returned {els:?} instead of WouldBlock"),
+    }
+    ensure_eq!(server.is_nonblocking().context("nonblocking mode query failed")?, false);
+
+    Ok(())
+}
+
+pub(super) fn run_splice() -> TestResult {
+    let (src_writer, src) = UdStream::pair().context("socketpair creation failed")?;
+    let (dst, mut dst_reader) = UdStream::pair().context("socketpair creation failed")?;
+
+    (&src_writer)
+        .write_all(CLIENT_MSG.as_bytes())
+        .context("socket send failed")?;
+    let mut moved = 0;
+    while moved < CLIENT_MSG.len() {
+        moved += src.splice_to(&dst, CLIENT_MSG.len() - moved).context("splice failed")?;
+    }
+    let mut buf = [0; 64];
+    let buf = &mut buf[..CLIENT_MSG.len()];
+    dst_reader.read_exact(buf).context("socket receive failed")?;
+    ensure_eq!(buf, CLIENT_MSG.as_bytes());
+
+    src.set_nonblocking(true).context("failed to enable nonblocking mode")?;
+    match src.splice_to(&dst, 16) {
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+        els => bail!("splice from an empty nonblocking socket returned {els:?} instead of WouldBlock"),
+    }
+
+    // The data is taken out of the source before the write into the destination fails, and doesn't come back
+    drop(dst_reader);
+    (&src_writer)
+        .write_all(CLIENT_MSG.as_bytes())
+        .context("socket send failed")?;
+    match src.splice_to(&dst, CLIENT_MSG.len()) {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => {}
+        els => bail!("splice into a disconnected socket returned {els:?} instead of BrokenPipe"),
+    }
+    match src.try_recv(&mut buf[..1]) {
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+        els => bail!("receive after a failed splice returned {els:?} instead of WouldBlock"),
+    }
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn run_mark() -> TestResult {
+    let (conn, _peer) = UdStream::pair().context("socketpair creation failed")?;
+    ensure_eq!(conn.mark().context("mark query failed")?, 0);
+    match conn.set_mark(0x1e1) {
+        Ok(()) => ensure_eq!(conn.mark().context("mark query failed")?, 0x1e1),
+        // Not running with CAP_NET_ADMIN
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied => ensure_eq!(e.raw_os_error(), Some(libc::EPERM)),
+        Err(e) => bail!("setting the mark failed: {e}"),
+    }
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn run_peer_pidfd() -> TestResult {
+    let (conn, _peer) = UdStream::pair().context("socketpair creation failed")?;
+    let pidfd = match conn.get_peer_pidfd() {
+        Ok(pidfd) => pidfd,
+        // Kernel older than 6.5
+        Err(e) if e.kind() == io::ErrorKind::Unsupported => return Ok(()),
+        Err(e) => bail!("peer pidfd query failed: {e}"),
+    };
+    // Both ends of the pair belong to us
+    let fdinfo = std::fs::read_to_string(format!("/proc/self/fdinfo/{}", pidfd.as_raw_fd()))
+        .context("reading pidfd info failed")?;
+    let Some(pid) = fdinfo.lines().find_map(|l| l.strip_prefix("Pid:")) else {
+        bail!("pidfd info has no Pid field: {fdinfo}");
+    };
+    ensure_eq!(pid.trim(), std::process::id().to_string());
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn run_peer_groups() -> TestResult {
+    let (conn, _peer) = UdStream::pair().context("socketpair creation failed")?;
+    let mut groups = match conn.get_peer_groups() {
+        Ok(groups) => groups,
+        // Kernel older than 4.13
+        Err(e) if e.kind() == io::ErrorKind::Unsupported => return Ok(()),
+        Err(e) => bail!("peer groups query failed: {e}"),
+    };
+    let mut own = vec![0; 65536];
+    let n = unsafe { libc::getgroups(own.len() as _, own.as_mut_ptr()) };
+    if n < 0 {
+        bail!("getgroups failed: {}", io::Error::last_os_error());
+    }
+    own.truncate(n as usize);
+    groups.sort_unstable();
+    own.sort_unstable();
+    ensure_eq!(groups, own);
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn run_drain_send_buffer() -> TestResult {
+    let (conn, peer) = UdStream::pair().context("socketpair creation failed")?;
+    ensure_eq!(conn.drain_send_buffer().context("send queue query failed")?, 0);
+    (&conn).write_all(CLIENT_MSG.as_bytes()).context("socket send failed")?;
+    let queued = conn.drain_send_buffer().context("send queue query failed")?;
+    if queued < CLIENT_MSG.len() {
+        bail!(
+            "only {queued} bytes reported as queued after sending {}",
+            CLIENT_MSG.len()
+        );
+    }
+    let mut buf = [0; CLIENT_MSG.len()];
+    (&peer).read_exact(&mut buf).context("socket receive failed")?;
+    ensure_eq!(conn.drain_send_buffer().context("send queue query failed")?, 0);
+    Ok(())
+}
+
+pub(super) fn run_poll_read_ready() -> TestResult {
+    let (a, a_peer) = UdStream::pair().context("socketpair creation failed")?;
+    let (b, b_peer) = UdStream::pair().context("socketpair creation failed")?;
+    let fds = [a.as_fd(), b.as_fd()];
+
+    let ready = poll_read_ready(&fds, Some(Duration::from_millis(10))).context("poll failed")?;
+    ensure_eq!(ready, [false, false]);
+
+    (&b_peer)
+        .write_all(CLIENT_MSG.as_bytes())
+        .context("socket send failed")?;
+    let ready = poll_read_ready(&fds, None).context("poll failed")?;
+    ensure_eq!(ready, [false, true]);
+
+    // Hanging up makes the socket readable, with reads returning end of file
+    drop(a_peer);
+    let ready = poll_read_ready(&fds, Some(Duration::ZERO)).context("poll failed")?;
+    ensure_eq!(ready, [true, true]);
+
+    ensure_eq!(poll_read_ready(&[], None).context("poll failed")?, []);
+    Ok(())
+}
+
+pub(super) fn run_recv_exact_with_deadline() -> TestResult {
+    let (conn, peer) = UdStream::pair().context("socketpair creation failed")?;
+    let mut buf = [0; CLIENT_MSG.len()];
+
+    let (head, tail) = CLIENT_MSG.as_bytes().split_at(5);
+    (&peer).write_all(head).context("socket send failed")?;
+    let writer = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(20));
+        (&peer).write_all(tail).map(|()| peer)
+    });
+    conn.recv_exact_with_deadline(&mut buf, Instant::now() + Duration::from_secs(10))
+        .context("receive with deadline failed")?;
+    ensure_eq!(&buf[..], CLIENT_MSG.as_bytes());
+    let peer = writer.join().unwrap().context("socket send failed")?;
+
+    // Only part of the message arrives in time
+    conn.set_read_timeout(Some(Duration::from_secs(10)))
+        .context("setting read timeout failed")?;
+    (&peer).write_all(head).context("socket send failed")?;
+    match conn.recv_exact_with_deadline(&mut buf, Instant::now() + Duration::from_millis(20)) {
+        Err(e) if e.kind() == io::ErrorKind::TimedOut => {}
+        els => bail!("receive with deadline returned {els:?} instead of TimedOut"),
+    }
+    ensure_eq!(
+        conn.read_timeout().context("read timeout query failed")?,
+        Some(Duration::from_secs(10))
+    );
+
+    drop(peer);
+    match conn.recv_exact_with_deadline(&mut buf, Instant::now() + Duration::from_secs(10)) {
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {}
+        els => bail!("receive with deadline returned {els:?} instead of UnexpectedEof"),
+    }
+    Ok(())
+}
+
+pub(super) fn run_shared() -> TestResult {
+    let (conn, peer) = UdStream::pair().context("socketpair creation failed")?;
+    let conn = Arc::new(conn);
+    let writer = {
+        let conn = Arc::clone(&conn);
+        std::thread::spawn(move || (&*conn).write_all(CLIENT_MSG.as_bytes()))
+    };
+    (&*conn)
+        .write_all(SERVER_MSG.as_bytes())
+        .context("socket send failed")?;
+    writer
+        .join()
+        .unwrap()
+        .context("socket send from another thread failed")?;
+
+    // Each message is sent with a single write, so they arrive whole, in either order
+    let mut buf = vec![0; CLIENT_MSG.len() + SERVER_MSG.len()];
+    (&peer).read_exact(&mut buf).context("socket receive failed")?;
+    let both = [
+        [CLIENT_MSG, SERVER_MSG].concat().into_bytes(),
+        [SERVER_MSG, CLIENT_MSG].concat().into_bytes(),
+    ];
+    ensure_eq!(both.contains(&buf), true);
+    Ok(())
+}
+
+pub(super) fn run_timeout_guard() -> TestResult {
+    let (conn, _peer) = UdStream::pair().context("socketpair creation failed")?;
+    let mut buf = [0; 1];
+    {
+        let guarded = conn
+            .with_read_timeout(Duration::from_millis(10))
+            .context("setting read timeout failed")?;
+        ensure_eq!(guarded.previous_timeout(), None);
+        match (&*guarded).read(&mut buf) {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+            els => bail!("read with a timeout returned {els:?} instead of WouldBlock"),
+        }
+    }
+    ensure_eq!(conn.read_timeout().context("read timeout query failed")?, None);
+
+    conn.set_read_timeout(Some(Duration::from_secs(10)))
+        .context("setting read timeout failed")?;
+    // Whole seconds, since the kernel rounds timeouts to its tick rate
+    let guarded = conn
+        .with_read_timeout(Duration::from_secs(1))
+        .context("setting read timeout failed")?;
+    ensure_eq!(
+        conn.read_timeout().context("read timeout query failed")?,
+        Some(Duration::from_secs(1))
+    );
+    guarded.restore().context("restoring read timeout failed")?;
+    ensure_eq!(
+        conn.read_timeout().context("read timeout query failed")?,
+        Some(Duration::from_secs(10))
+    );
+
+    match conn.with_read_timeout(Duration::ZERO) {
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {}
+        els => bail!("zero timeout guard returned {els:?} instead of InvalidInput"),
+    }
+    Ok(())
+}
+
+pub(super) fn run_connect_timeout(mut namegen: NameGen) -> TestResult {
+    let timeout = Duration::from_millis(50);
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| UdStreamListener::bind_with_backlog(nm, 1))?;
+
+    // Fill up the accept queue without ever accepting
+    let mut conns = Vec::new();
+    loop {
+        match UdStream::connect_timeout(&*name, timeout) {
+            Ok(conn) => conns.push(conn),
+            Err(e) if e.kind() == io::ErrorKind::TimedOut => break,
+            Err(e) => bail!("connect with timeout failed: {e}"),
+        }
+        if conns.len() > 64 {
+            bail!(
+                "accept queue with a backlog of 1 did not fill up after {} connections",
+                conns.len()
+            );
+        }
+    }
+    ensure_eq!(conns.is_empty(), false);
+
+    // Making room allows the next attempt through, and the stream is back in blocking mode
+    let _accepted = listener.accept().context("accept failed")?;
+    let conn = UdStream::connect_timeout(&*name, timeout).context("connect with timeout failed")?;
+    ensure_eq!(conn.is_nonblocking().context("nonblocking mode query failed")?, false);
+
+    match UdStream::connect_timeout(&*name, Duration::ZERO) {
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => Ok(()),
+        els => bail!("connect with a zero timeout returned {els:?} instead of InvalidInput"),
+    }
+}
+
+pub(super) fn run_is_peer_connected() -> TestResult {
+    let (server, client) = UdStream::pair().context("socketpair creation failed")?;
+    ensure_eq!(server.is_peer_connected().context("liveness check failed")?, true);
+
+    // Queued data is left alone, and the peer counts as connected until it's read
+    (&client)
+        .write_all(CLIENT_MSG.as_bytes())
+        .context("socket send failed")?;
+    drop(client);
+    ensure_eq!(server.is_peer_connected().context("liveness check failed")?, true);
+    let mut buffer = String::new();
+    (&server).read_to_string(&mut buffer).context("socket receive failed")?;
+    ensure_eq!(buffer, CLIENT_MSG);
+    ensure_eq!(server.is_peer_connected().context("liveness check failed")?, false);
+    Ok(())
+}
+
+pub(super) fn run_read_until_eof() -> TestResult {
+    let (server, client) = UdStream::pair().context("socketpair creation failed")?;
+
+    // A write shutdown is a clean end of file, and the other direction keeps working
+    (&client)
+        .write_all(CLIENT_MSG.as_bytes())
+        .context("socket send failed")?;
+    client
+        .shutdown(Shutdown::Write)
+        .context("shutdown of writing end failed")?;
+    let mut buf = Vec::new();
+    ensure_eq!(
+        server.read_until_eof(&mut buf).context("read until EOF failed")?,
+        CLIENT_MSG.len()
+    );
+    ensure_eq!(buf, CLIENT_MSG.as_bytes());
+    (&server)
+        .write_all(SERVER_MSG.as_bytes())
+        .context("socket send failed")?;
+    let mut buffer = String::new();
+    BufReader::new(&client)
+        .read_line(&mut buffer)
+        .context("socket receive failed")?;
+    ensure_eq!(buffer, SERVER_MSG);
+
+    // Closing with unread data in the receive queue resets the connection on Linux
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        let (server, client) = UdStream::pair().context("socketpair creation failed")?;
+        (&server)
+            .write_all(SERVER_MSG.as_bytes())
+            .context("socket send failed")?;
+        drop(client);
+        buf.clear();
+        match server.read_until_eof(&mut buf) {
+            Err(e) if e.kind() == io::ErrorKind::ConnectionReset => {}
+            els => bail!("read until EOF from a reset connection returned {els:?} instead of ConnectionReset"),
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn run_std_conversion() -> TestResult {
+    let (server, client) = UdStream::pair().context("socketpair creation failed")?;
+    let mut client = std::os::unix::net::UnixStream::from(client);
+    client.write_all(CLIENT_MSG.as_bytes()).context("socket send failed")?;
+    let mut buffer = String::new();
+    BufReader::new(&server)
+        .read_line(&mut buffer)
+        .context("socket receive failed")?;
+    ensure_eq!(buffer, CLIENT_MSG);
+
+    // Socket state set through either type carries over
+    client
+        .set_nonblocking(true)
+        .context("failed to enable nonblocking mode")?;
+    let client = UdStream::from(client);
+    ensure_eq!(client.is_nonblocking().context("nonblocking mode query failed")?, true);
+    (&server)
+        .write_all(SERVER_MSG.as_bytes())
+        .context("socket send failed")?;
+    buffer.clear();
+    BufReader::new(&client)
+        .read_line(&mut buffer)
+        .context("socket receive failed")?;
+    ensure_eq!(buffer, SERVER_MSG);
+    Ok(())
+}
+
+pub(super) fn run_cloexec() -> TestResult {
+    let (conn, _peer) = UdStream::pair().context("socketpair creation failed")?;
+    ensure_eq!(conn.get_cloexec().context("close-on-exec query failed")?, true);
+    conn.set_cloexec(false).context("clearing close-on-exec failed")?;
+    ensure_eq!(conn.get_cloexec().context("close-on-exec query failed")?, false);
+    conn.set_cloexec(true).context("setting close-on-exec failed")?;
+    ensure_eq!(conn.get_cloexec().context("close-on-exec query failed")?, true);
+    Ok(())
+}
+
+pub(super) fn run_split() -> TestResult {
+    let (server, client) = UdStream::pair().context("socketpair creation failed")?;
+
+    // Borrowed halves, used from two scoped threads
+    let (mut read, mut write) = server.split();
+    std::thread::scope(|scope| -> TestResult {
+        let writer = scope.spawn(move || write.write_all(SERVER_MSG.as_bytes()));
+        (&client)
+            .write_all(CLIENT_MSG.as_bytes())
+            .context("socket send failed")?;
+        let mut buf = [0; 64];
+        let buf = &mut buf[..CLIENT_MSG.len()];
+        read.read_exact(buf).context("socket receive failed")?;
+        ensure_eq!(buf, CLIENT_MSG.as_bytes());
+        writer.join().unwrap().context("socket send failed")?;
+        Ok(())
+    })?;
+    let mut buffer = String::new();
+    BufReader::new(&client)
+        .read_line(&mut buffer)
+        .context("socket receive failed")?;
+    ensure_eq!(buffer, SERVER_MSG);
+
+    // Owned halves, reunited after use
+    let (read, mut write) = server.into_split();
+    write.write_all(SERVER_MSG.as_bytes()).context("socket send failed")?;
+    write.shutdown().context("shutdown of writing end failed")?;
+    buffer.clear();
+    (&client).read_to_string(&mut buffer).context("socket receive failed")?;
+    ensure_eq!(buffer, SERVER_MSG);
+
+    let (_, other_write) = UdStream::pair().context("socketpair creation failed")?.0.into_split();
+    if let Err(ReuniteError(..)) = read.reunite(other_write) {
+        Ok(())
+    } else {
+        bail!("halves of different streams were reunited")
+    }
+}
+
+pub(super) fn run_reunite() -> TestResult {
+    let (server, client) = UdStream::pair().context("socketpair creation failed")?;
+    let (read, write) = server.into_split();
+    let mut server = read.reunite(write).context("reuniting failed")?;
+    server.write_all(SERVER_MSG.as_bytes()).context("socket send failed")?;
+    let mut buffer = String::new();
+    BufReader::new(&client)
+        .read_line(&mut buffer)
+        .context("socket receive failed")?;
+    ensure_eq!(buffer, SERVER_MSG);
+    Ok(())
+}
+
+pub(super) fn run_read_vectored_exact() -> TestResult {
+    let (server, client) = UdStream::pair().context("socketpair creation failed")?;
+    let msg = CLIENT_MSG.as_bytes();
+    let writer = std::thread::spawn(move || -> io::Result<UdStream> {
+        // Split mid-buffer so that the receiving side has to resume in the middle of one
+        for chunk in msg.chunks(3) {
+            (&client).write_all(chunk)?;
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        Ok(client)
+    });
+
+    let (mut a, mut b, mut c) = ([0; 4], [0; 0], [0; 15]);
+    server
+        .read_vectored_exact(&mut [
+            IoSliceMut::new(&mut a),
+            IoSliceMut::new(&mut b),
+            IoSliceMut::new(&mut c),
+        ])
+        .context("vectored exact receive failed")?;
+    ensure_eq!([&a[..], &c[..]].concat(), msg);
+
+    let client = writer.join().unwrap().context("socket send failed")?;
+    (&client).write_all(&msg[..2]).context("socket send failed")?;
+    drop(client);
+    match server.read_vectored_exact(&mut [IoSliceMut::new(&mut a)]) {
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(()),
+        els => bail!("vectored exact receive from a closed stream returned {els:?} instead of UnexpectedEof"),
+    }
+}
+
+pub(super) fn run_send_all_vectored() -> TestResult {
+    let (server, client) = UdStream::pair().context("socketpair creation failed")?;
+    client
+        .set_send_buffer_size(4096)
+        .context("send buffer size change failed")?;
+    // Much more than fits into the socket buffers, in slices of uneven sizes
+    let data = (0..200_000u32).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+    let reader = std::thread::spawn(move || -> io::Result<Vec<u8>> {
+        let mut received = Vec::new();
+        server.read_until_eof(&mut received)?;
+        Ok(received)
+    });
+
+    let mut bufs = [
+        &data[..1],
+        &data[1..1],
+        &data[1..70_001],
+        &data[70_001..70_003],
+        &data[70_003..],
+    ]
+    .map(IoSlice::new);
+    client
+        .send_all_vectored(&mut bufs)
+        .context("vectored send of all data failed")?;
+    client
+        .shutdown(Shutdown::Write)
+        .context("shutdown of writing end failed")?;
+    let received = reader.join().unwrap().context("socket receive failed")?;
+    ensure_eq!(received.len(), data.len());
+    ensure_eq!(received == data, true);
+
+    client
+        .send_all_vectored(&mut [])
+        .context("vectored send of nothing failed")
+}
+
 fn server(name_sender: Sender<Arc<str>>, num_clients: u32, mut namegen: NameGen, shutdown: bool) -> TestResult {
-    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| UdStreamListener::bind(nm))?;
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| {
+        if shutdown {
+            UdStreamListener::bind_with_backlog(nm, 16)
+        } else {
+            UdStreamListener::bind(nm)
+        }
+    })?;
 
     let _ = name_sender.send(name);
 
     let mut buffer = String::with_capacity(128);
 
+    let mut incoming = listener.incoming();
     for _ in 0..num_clients {
-        let mut conn = match listener.accept() {
-            Ok(c) => BufReader::new(c),
-            Err(e) => bail!("incoming connection failed: {e}"),
+        let mut conn = if shutdown {
+            let (conn, addr) = listener.accept_with_addr().context("accept failed")?;
+            ensure_eq!(addr, UdSocketPath::Unnamed);
+            BufReader::new(conn)
+        } else {
+            match incoming.next() {
+                Some(Ok(c)) => BufReader::new(c),
+                Some(Err(e)) => bail!("incoming connection failed: {e}"),
+                None => unreachable!("incoming() never returns None"),
+            }
         };
 
         if shutdown {
@@ -54,6 +590,14 @@ fn client(name: &str, shutdown: bool) -> TestResult {
     let mut buffer = String::with_capacity(128);
 
     let conn = UdStream::connect(name).context("connect failed")?;
+    ensure_eq!(
+        conn.peer_addr().context("peer address query failed")?,
+        name.to_socket_path().context("path conversion failed")?
+    );
+    ensure_eq!(
+        conn.local_addr().context("local address query failed")?,
+        UdSocketPath::Unnamed
+    );
     let mut conn = BufReader::new(conn);
 
     conn.get_mut()
@@ -76,3 +620,165 @@ fn client(name: &str, shutdown: bool) -> TestResult {
 
     Ok(())
 }
+
+pub(super) fn run_dup_to() -> TestResult {
+    let (conn, mut peer) = UdStream::pair().context("socketpair creation failed")?;
+    // A file descriptor number of our own to overwrite, so as not to close one that belongs to another test. Once
+    // overwritten, this refers to the same socket as `conn`.
+    let (dup, _) = UdStream::pair().context("socketpair creation failed")?;
+    let target = dup.as_raw_fd();
+
+    conn.dup_to(target).context("dup_to failed")?;
+    ensure_eq!(dup.get_cloexec().context("F_GETFD failed")?, false);
+    (&dup).write_all(b"dup").context("send via duplicate failed")?;
+    let mut buf = [0; 3];
+    peer.read_exact(&mut buf).context("socket receive failed")?;
+    ensure_eq!(&buf, b"dup");
+
+    conn.dup_to_with_cloexec(target, true)
+        .context("dup_to_with_cloexec failed")?;
+    ensure_eq!(dup.get_cloexec().context("F_GETFD failed")?, true);
+
+    // Onto itself: a no-op, which leaves the flag alone either way
+    ensure_eq!(conn.get_cloexec().context("F_GETFD failed")?, true);
+    conn.dup_to(conn.as_raw_fd()).context("dup_to onto itself failed")?;
+    ensure_eq!(conn.get_cloexec().context("F_GETFD failed")?, true);
+    conn.set_cloexec(false).context("F_SETFD failed")?;
+    conn.dup_to_with_cloexec(conn.as_raw_fd(), true)
+        .context("dup_to_with_cloexec onto itself failed")?;
+    ensure_eq!(conn.get_cloexec().context("F_GETFD failed")?, false);
+
+    // The duplicate keeps the connection open after the original is gone
+    drop(conn);
+    (&dup).write_all(b"!").context("send via duplicate failed")?;
+    peer.read_exact(&mut buf[..1]).context("socket receive failed")?;
+    ensure_eq!(&buf[..1], b"!");
+    Ok(())
+}
+
+pub(super) fn run_try_clone_flags() -> TestResult {
+    use interprocess::TryClone;
+
+    let (conn, _peer) = UdStream::pair().context("socketpair creation failed")?;
+    // Even if the original is inheritable, the clone isn't
+    conn.set_cloexec(false).context("F_SETFD failed")?;
+    let clone = conn.try_clone().context("stream cloning failed")?;
+    ensure_eq!(clone.get_cloexec().context("F_GETFD failed")?, true);
+    ensure_eq!(conn.get_cloexec().context("F_GETFD failed")?, false);
+
+    // The nonblocking mode is shared with the original, however
+    conn.set_nonblocking(true)
+        .context("failed to enable nonblocking mode")?;
+    ensure_eq!(clone.is_nonblocking().context("nonblocking mode query failed")?, true);
+    Ok(())
+}
+
+/// Linux records a connection reset as a pending error when the peer is closed with unread data.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub(super) fn run_take_error() -> TestResult {
+    let (conn, peer) = UdStream::pair().context("socketpair creation failed")?;
+    ensure_eq!(conn.take_error().context("SO_ERROR query failed")?.is_none(), true);
+
+    (&conn).write_all(b"unread").context("socket send failed")?;
+    drop(peer);
+    match conn.take_error().context("SO_ERROR query failed")? {
+        Some(e) if e.kind() == io::ErrorKind::ConnectionReset => {}
+        els => bail!("pending error was {els:?} instead of a connection reset"),
+    }
+    // Taking the error clears it
+    ensure_eq!(conn.take_error().context("SO_ERROR query failed")?.is_none(), true);
+    Ok(())
+}
+
+pub(super) fn run_buffer_sizes() -> TestResult {
+    let (conn, _peer) = UdStream::pair().context("socketpair creation failed")?;
+    // The kernel may round the values up (Linux doubles them), but never hands out less than what was asked for
+    conn.set_send_buffer_size(64 * 1024)
+        .context("setting send buffer size failed")?;
+    ensure_eq!(
+        conn.send_buffer_size().context("send buffer size query failed")? >= 64 * 1024,
+        true
+    );
+    conn.set_recv_buffer_size(32 * 1024)
+        .context("setting receive buffer size failed")?;
+    ensure_eq!(
+        conn.recv_buffer_size().context("receive buffer size query failed")? >= 32 * 1024,
+        true
+    );
+    Ok(())
+}
+
+pub(super) fn run_timeouts() -> TestResult {
+    let (conn, peer) = UdStream::pair().context("socketpair creation failed")?;
+    ensure_eq!(conn.read_timeout().context("read timeout query failed")?, None);
+    ensure_eq!(conn.write_timeout().context("write timeout query failed")?, None);
+
+    // Whole seconds survive the kernel's rounding of the timeout to its own clock resolution
+    conn.set_read_timeout(Some(Duration::from_secs(1)))
+        .context("setting read timeout failed")?;
+    conn.set_write_timeout(Some(Duration::from_secs(2)))
+        .context("setting write timeout failed")?;
+    ensure_eq!(
+        conn.read_timeout().context("read timeout query failed")?,
+        Some(Duration::from_secs(1))
+    );
+    ensure_eq!(
+        conn.write_timeout().context("write timeout query failed")?,
+        Some(Duration::from_secs(2))
+    );
+
+    // Would be taken by the kernel as "no timeout", and thus isn't allowed
+    for rslt in [
+        conn.set_read_timeout(Some(Duration::ZERO)),
+        conn.set_write_timeout(Some(Duration::ZERO)),
+    ] {
+        match rslt {
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput => {}
+            els => bail!("setting a zero timeout returned {els:?} instead of InvalidInput"),
+        }
+    }
+    ensure_eq!(
+        conn.read_timeout().context("read timeout query failed")?,
+        Some(Duration::from_secs(1))
+    );
+
+    conn.set_read_timeout(Some(Duration::from_millis(50)))
+        .context("setting read timeout failed")?;
+    let start = Instant::now();
+    match (&conn).read(&mut [0; 1]) {
+        Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+        els => bail!("read with nothing to receive returned {els:?} instead of timing out"),
+    }
+    ensure_eq!(start.elapsed() >= Duration::from_millis(40), true);
+
+    conn.set_read_timeout(None).context("clearing read timeout failed")?;
+    conn.set_write_timeout(None).context("clearing write timeout failed")?;
+    ensure_eq!(conn.read_timeout().context("read timeout query failed")?, None);
+    ensure_eq!(conn.write_timeout().context("write timeout query failed")?, None);
+    drop(peer);
+    Ok(())
+}
+
+pub(super) fn run_fdops_flush() -> TestResult {
+    let (conn, peer) = UdStream::pair().context("socketpair creation failed")?;
+    let conn = FdOps::new(OwnedFd::from(conn));
+    let mut writer = BufWriter::new(&conn);
+    writer
+        .write_all(CLIENT_MSG.as_bytes())
+        .context("buffered write failed")?;
+    writer.flush().context("flush on a socket failed")?;
+    drop(writer);
+    let mut buf = [0; CLIENT_MSG.len()];
+    (&peer).read_exact(&mut buf).context("socket receive failed")?;
+    ensure_eq!(&buf[..], CLIENT_MSG.as_bytes());
+    match conn.sync_all() {
+        Err(e) if e.kind() == io::ErrorKind::InvalidInput => {}
+        els => bail!("sync_all on a socket returned {els:?} instead of InvalidInput"),
+    }
+
+    let (mut pipe_w, _pipe_r) = interprocess::unnamed_pipe::pipe().context("pipe creation failed")?;
+    let mut writer = BufWriter::new(&mut pipe_w);
+    writer.write_all(b"piped").context("buffered write failed")?;
+    writer.flush().context("flush on a pipe failed")?;
+    Ok(())
+}
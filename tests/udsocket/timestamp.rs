@@ -0,0 +1,122 @@
+use super::util::*;
+use color_eyre::eyre::{bail, Context};
+use interprocess::os::unix::udsocket::{
+    cmsg::{
+        ancillary::{
+            file_descriptors::FileDescriptors,
+            timestamp::{Timestamp, TimestampDecodeError},
+            ControlMessage, FromCmsg, ParseErrorKind,
+        },
+        Cmsg, CmsgMutExt, CmsgVecBuf,
+    },
+    UdDatagram, UdSocket, UdStream,
+};
+use std::{os::fd::AsFd, time::SystemTime};
+
+pub(super) fn run() -> TestResult {
+    let (sender, receiver) = UdDatagram::pair().context("socketpair creation failed")?;
+    let (passed_end, _kept_end) = UdStream::pair().context("socketpair creation failed")?;
+    receiver
+        .set_receive_timestamps(true)
+        .context("failed to enable SO_TIMESTAMPNS")?;
+
+    let before = SystemTime::now();
+    let mut sabuf = CmsgVecBuf::new(0);
+    sabuf.add_message(&FileDescriptors::new(&[passed_end.as_fd()]));
+    sender
+        .send_ancillary(b"T", sabuf.as_ref())
+        .context("ancillary send failed")?;
+    let after = SystemTime::now();
+
+    let mut abuf = CmsgVecBuf::new(256);
+    let mut buf = [0; 1];
+    receiver
+        .recv_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    ensure_eq!(&buf, b"T");
+
+    let (mut got_fds, mut got_timestamp) = (false, false);
+    for msg in abuf.as_ref().decode_all() {
+        match msg {
+            ControlMessage::Fds(..) => got_fds = true,
+            ControlMessage::Timestamp(ts) => {
+                if ts.system_time() < before || ts.system_time() > after {
+                    bail!("timestamp {ts:?} lies outside of the sending window");
+                }
+                got_timestamp = true;
+            }
+            ControlMessage::Malformed(e) => bail!("malformed control message received: {e}"),
+            _ => {}
+        }
+    }
+    ensure_eq!((got_fds, got_timestamp), (true, true));
+    Ok(())
+}
+
+pub(super) fn run_datagram() -> TestResult {
+    let (sender, receiver) = UdDatagram::pair().context("socketpair creation failed")?;
+    receiver
+        .set_receive_timestamps(true)
+        .context("failed to enable SO_TIMESTAMPNS")?;
+    sender.send(b"first").context("socket send failed")?;
+    sender.send(b"second").context("socket send failed")?;
+
+    let mut abuf = CmsgVecBuf::new(256);
+    let mut buf = [0; 16];
+    let mut stamps = Vec::new();
+    for _ in 0..2 {
+        abuf.clear();
+        receiver
+            .recv_ancillary(&mut buf, &mut abuf)
+            .context("ancillary receive failed")?;
+        let Some(ts) = abuf.as_ref().decode::<Timestamp>().next() else {
+            bail!("no timestamp received");
+        };
+        match ts {
+            Ok(ts) => stamps.push(ts),
+            Err(e) => bail!("parsing of timestamp failed: {e}"),
+        }
+    }
+    ensure_eq!(stamps[0] <= stamps[1], true);
+
+    // Turning the option off again
+    receiver
+        .set_receive_timestamps(false)
+        .context("failed to disable SO_TIMESTAMPNS")?;
+    sender.send(b"third").context("socket send failed")?;
+    abuf.clear();
+    receiver
+        .recv_ancillary(&mut buf, &mut abuf)
+        .context("ancillary receive failed")?;
+    ensure_eq!(abuf.as_ref().is_empty(), true);
+    Ok(())
+}
+
+pub(super) fn run_parse() -> TestResult {
+    let ts = libc::timespec {
+        tv_sec: 1_000_000_000,
+        tv_nsec: 123_456_789,
+    };
+    let payload = unsafe {
+        // SAFETY: timespec is POD
+        std::slice::from_raw_parts((&ts as *const libc::timespec).cast::<u8>(), std::mem::size_of_val(&ts))
+    };
+    let cmsg = unsafe { Cmsg::new(libc::SOL_SOCKET, libc::SO_TIMESTAMPNS, payload) };
+    let ts = match Timestamp::try_parse(cmsg) {
+        Ok(ts) => ts,
+        Err(e) => bail!("parsing of timestamp failed: {e}"),
+    };
+    ensure_eq!(ts.since_epoch().as_secs(), 1_000_000_000);
+    ensure_eq!(ts.since_epoch().subsec_nanos(), 123_456_789);
+
+    let cmsg = unsafe { Cmsg::new(libc::SOL_SOCKET, libc::SO_TIMESTAMPNS, &payload[1..]) };
+    match Timestamp::try_parse(cmsg) {
+        Err(e)
+            if matches!(
+                e.kind,
+                ParseErrorKind::MalformedPayload(TimestampDecodeError::SizeMismatch(..))
+            ) => {}
+        els => bail!("parsing of a truncated timestamp returned {els:?}"),
+    }
+    Ok(())
+}
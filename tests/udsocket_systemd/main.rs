@@ -0,0 +1,74 @@
+//! Kept in a test binary of its own, since it has to modify the environment, which is not thread-safe.
+#![cfg(target_os = "linux")]
+
+#[path = "../util/mod.rs"]
+#[macro_use]
+mod util;
+use util::*;
+
+use color_eyre::eyre::{bail, Context};
+use interprocess::os::unix::udsocket::{UdStream, UdStreamListener};
+use std::{
+    env,
+    io::{self, Write},
+    os::fd::AsRawFd,
+};
+
+/// Far enough from the descriptors opened by the test harness.
+const INDEX: usize = 900;
+
+fn expect_kind(rslt: io::Result<UdStreamListener>, kind: io::ErrorKind, what: &str) -> TestResult {
+    match rslt {
+        Err(e) if e.kind() == kind => Ok(()),
+        els => bail!("{what} returned {els:?} instead of {kind:?}"),
+    }
+}
+
+#[test]
+fn udsocket_systemd_activation() -> TestResult {
+    install_color_eyre();
+    let mut namegen = NameGen::new(make_id!(), false);
+    let (name, listener) = listen_and_pick_name(&mut namegen, |nm| UdStreamListener::bind_with_drop_guard(nm))?;
+
+    // Put the socket where systemd would have
+    let raw = (3 + INDEX) as libc::c_int;
+    ensure_eq!(unsafe { libc::dup2(listener.as_raw_fd(), raw) }, raw);
+
+    env::remove_var("LISTEN_PID");
+    env::remove_var("LISTEN_FDS");
+    expect_kind(
+        UdStreamListener::from_systemd(INDEX),
+        io::ErrorKind::NotFound,
+        "claiming outside of socket activation",
+    )?;
+
+    env::set_var("LISTEN_PID", (std::process::id() + 1).to_string());
+    env::set_var("LISTEN_FDS", (INDEX + 1).to_string());
+    expect_kind(
+        UdStreamListener::from_systemd(INDEX),
+        io::ErrorKind::NotFound,
+        "claiming sockets meant for a different process",
+    )?;
+
+    env::set_var("LISTEN_PID", std::process::id().to_string());
+    expect_kind(
+        UdStreamListener::from_systemd(INDEX + 1),
+        io::ErrorKind::InvalidInput,
+        "claiming an out-of-range socket",
+    )?;
+
+    let activated = UdStreamListener::from_systemd(INDEX).context("claiming the activated socket failed")?;
+    let mut client = UdStream::connect(&*name).context("connect failed")?;
+    client.write_all(b"hi").context("socket send failed")?;
+    activated.accept().context("accept failed")?;
+    expect_kind(
+        UdStreamListener::from_systemd(INDEX),
+        io::ErrorKind::AlreadyExists,
+        "claiming the same socket twice",
+    )?;
+
+    // The original was duplicated into the activated one, so only one of them has the drop guard
+    drop(activated);
+    drop(listener);
+    Ok(())
+}
@@ -1,5 +1,5 @@
 //! Test utilities for allocating an address for the server and then spawning clients to connect to it.
-#![allow(dead_code, unused_macros)]
+#![allow(dead_code, unused_imports, unused_macros)]
 
 mod choke;
 
@@ -17,7 +17,12 @@ pub mod tokio;
 const NUM_CLIENTS: u32 = 80;
 const NUM_CONCURRENT_CLIENTS: u32 = 6;
 
-use color_eyre::eyre::Context;
+use color_eyre::eyre::{bail, Context};
+#[cfg(unix)]
+use interprocess::os::unix::udsocket::cmsg::{
+    ancillary::file_descriptors::{FileDescriptors, IntoOwnedFds},
+    CmsgMutExt, CmsgVecBuf,
+};
 use std::{fmt::Arguments, io, sync::Arc};
 
 pub fn message(msg: Option<Arguments<'_>>, server: bool, terminator: Option<char>) -> Box<str> {
@@ -46,3 +51,17 @@ pub fn listen_and_pick_name<T>(
         .unwrap() // Infinite iterator
         .context("listener bind failed")
 }
+
+/// Takes ownership of the file descriptors in the first `SCM_RIGHTS` message of `abuf`, which is expected to have been
+/// filled by a receive operation.
+#[cfg(unix)]
+pub fn take_received_fds(abuf: &CmsgVecBuf) -> TestResult<IntoOwnedFds<'_>> {
+    match abuf.as_ref().decode::<FileDescriptors>().next() {
+        Some(Ok(fds)) => match fds.into_owned_fds() {
+            Ok(fds) => Ok(fds),
+            Err(..) => bail!("received file descriptors are not owned"),
+        },
+        Some(Err(e)) => bail!("parsing of file descriptors failed: {e}"),
+        None => bail!("no file descriptors received"),
+    }
+}